@@ -2,13 +2,19 @@ static USAGE: &str = r#"
 Apply a series of transformation functions to a given CSV column. This can be used to
 perform typical data-wrangling tasks and/or to harmonize some values, etc.
 
-It has six subcommands:
- * operations - 33 string, format, currency, regex & NLP operators.
+It has ten subcommands:
+ * operations - 47 string, Unicode, format, currency, regex, hashing, masking & NLP operators.
  * emptyreplace - replace empty cells with <--replacement> string.
  * datefmt - Formats a recognized date column to a specified format using <--formatstr>.
+ * dateops - timezone conversion, arithmetic, truncation, part extraction & differences of
+     recognized dates.
  * dynfmt - Dynamically constructs a new column from other columns using the <--formatstr> template.
  * geocode - geocodes a WGS84 location against a static copy of the Geonames cities database.
+ * fwdgeocode - geocodes a place name (e.g. "Brooklyn, NY") into a WGS84 location, against a
+     Geonames cities file.
  * calcconv - parse and evaluate math expressions, with support for units and conversions.
+ * extract - extracts the capture groups of a regex into new columns.
+ * recipe - applies the operation chains of a YAML/JSON recipe file to many columns, in one pass.
 
 OPERATIONS
 Multiple operations can be applied, with the comma-delimited operation series
@@ -24,13 +30,23 @@ number of transformed columns with the --rename option is the same. e.g.:
 
 $ qsv apply operations trim,upper col1,col2,col3 -r newcol1,newcol2,newcol3 file.csv  
 
-It has 33 supported operations:
+It has 47 supported operations:
 
   * len: Return string length
   * lower: Transform to lowercase
   * upper: Transform to uppercase
   * squeeze: Compress consecutive whitespaces
   * squeeze0: Remove whitespace
+  * nfc: Unicode Normalization Form C (canonical composition)
+  * nfd: Unicode Normalization Form D (canonical decomposition)
+  * nfkc: Unicode Normalization Form KC (compatibility composition, e.g. ﬁ becomes fi)
+  * nfkd: Unicode Normalization Form KD (compatibility decomposition)
+  * unaccent: Strip accents & other diacritics (e.g. Crème Brûlée becomes Creme Brulee)
+  * ascii: Transliterate to ASCII, in the style of unidecode (e.g. Αθήνα becomes Athena,
+      北京 becomes Bei Jing)
+  * casefold: Unicode full case folding, for caseless matching (e.g. Straße becomes strasse)
+  * strip_invisible: Remove zero-width, bidirectional & other invisible formatting characters,
+      and control characters other than tab, newline & carriage return
   * trim: Trim (drop whitespace left & right of the string)
   * ltrim: Left trim whitespace
   * rtrim: Right trim whitespace
@@ -42,6 +58,17 @@ It has 33 supported operations:
   * escape - escape (Rust escape_default)
   * encode64: base64 encode
   * decode64: base64 decode
+  * md5: MD5 hash, as lowercase hex
+  * sha256: SHA-256 hash, as lowercase hex
+  * blake3: BLAKE3 hash, as lowercase hex
+  * xxh3: XXH3 64-bit hash, as lowercase hex. Fast, but NOT cryptographic.
+  * hmac: keyed HMAC-SHA256, as lowercase hex. The secret key is read from the source
+      given with --hmac-key, or from the QSV_HMAC_KEY environment variable.
+      Unlike plain hashes, the values can't be recovered with a dictionary attack
+      without the key.
+  * mask: format-preserving mask, keeping the last N characters (N set with --mask-keep,
+      default: 4) & the character classes - uppercase letters become 'X', lowercase
+      letters 'x' & digits '9'. Other characters (e.g. spaces & punctuation) are kept.
   * replace: Replace all matches of a pattern (using --comparand)
       with a string (using --replacement) (Rust replace)
   * regex_replace: Replace all regex matches in --comparand w/ --replacement.
@@ -116,6 +143,20 @@ and save it to a new column named dln_roxbury_score.
 
   $ qsv apply operations lower,simdln neighborhood --comparand roxbury -c dln_roxbury_score boston311.csv
 
+Pseudonymize the email column with a keyed hash, with the key in the PII_KEY env var.
+
+  $ qsv apply operations lower,hmac email --hmac-key env:PII_KEY file.csv
+
+Normalize the name column for matching across sources - removing invisible characters &
+accents, then case folding.
+
+  $ qsv apply operations strip_invisible,nfkc,unaccent,casefold name -c match_key file.csv
+
+Mask the card_number column, keeping only its last 4 digits
+(e.g. 4111-1111-1111-1234 becomes 9999-9999-9999-1234).
+
+  $ qsv apply operations mask card_number file.csv
+
 You can also use this subcommand command to make a copy of a column:
 
   $ qsv apply operations copy col_to_copy -c col_copy file.csv
@@ -168,6 +209,60 @@ Get the day of the week for several date columns and store it in the correspondi
 
   $ qsv apply dateformat OpenDate,CloseDate --formatstr '%u' --rename Open_weekday,Close_weekday file.csv
 
+DATEOPS
+Applies a series of datetime operations to a recognized date column, with the comma-delimited
+operation series applied in order, as with OPERATIONS. Dates are parsed as with DATEFMT - as UTC
+when they have no timezone - and the resulting dates are formatted using <--formatstr>.
+
+The supported operations are:
+
+  * to_tz=<timezone>: Convert to the IANA timezone, e.g. to_tz=America/New_York
+  * from_tz=<timezone>: Interpret the date & time as a local time in the IANA timezone,
+      instead of UTC. Only meant for dates without timezone.
+  * add=<duration>: Add the duration, e.g. add=1y6mo or add=2w3d12h. The units are y(ears),
+      mo(nths), w(eeks), d(ays), h(ours), m(inutes) & s(econds). Years, months, weeks & days
+      are added in local calendar time, e.g. 2022-01-31 + 1mo is 2022-02-28.
+  * sub=<duration>: Subtract the duration.
+  * trunc=<unit>: Truncate to the start of the year, quarter, month, week (starting on Monday),
+      day, hour, minute or second.
+
+And the following operations return a number instead of a date, so they must be the last one:
+
+  * year, quarter, month, day, hour, minute, second: Extract the part of the date.
+  * weekday: The ISO weekday number, from 1 (Monday) to 7 (Sunday).
+  * isoweek, isoyear: The ISO 8601 week number, and its year.
+  * dayofyear: The day of the year, from 1 to 366.
+  * epoch: The Unix timestamp, in seconds.
+  * diff=<unit>: The number of whole years, quarters, months, weeks, days, hours, minutes or
+      seconds from the date of the --comparand column to the date. Negative when the date is
+      before the --comparand date.
+
+Local times skipped by a daylight saving time change (e.g. 02:30 when the clocks go from
+02:00 to 03:00) are moved forward by the length of the change (e.g. to 03:30).
+
+Cells that are not recognized dates are left as is.
+
+Examples:
+Convert the UTC created_at column to New York time:
+
+  $ qsv apply dateops to_tz=America/New_York created_at file.csv
+
+Get the month of the Paris local time of the created_at column, in a new month column:
+
+  $ qsv apply dateops to_tz=Europe/Paris,trunc=month created_at -c month --formatstr '%Y-%m' file.csv
+
+Get the due date, 30 days after the invoice date:
+
+  $ qsv apply dateops add=30d invoice_date -c due_date file.csv
+
+Get the ISO week number of the local Tokyo time of the OpenDate column:
+
+  $ qsv apply dateops from_tz=Asia/Tokyo,isoweek OpenDate -c week file.csv
+
+Get the number of days between the OpenDate & CloseDate columns:
+
+  $ qsv apply dateops diff=days CloseDate --comparand OpenDate -c days_open file.csv
+
 DYNFMT
 Dynamically constructs a new column from other columns using the <--formatstr> template.
 The template can contain arbitrary characters. To insert a column value, enclose the
@@ -203,6 +298,36 @@ set the geocoded value a new column named City.
 
 $ qsv apply geocode Location --formatstr city-state --new-column City file.csv
 
+FWDGEOCODE
+The opposite of GEOCODE - geocodes a place name column (e.g. "Brooklyn, NY" or "Paris, FR")
+into the WGS84 location of the city center, i.e. "latitude, longitude".
+
+As the Geonames data embedded for GEOCODE can't be searched by name, the place names are
+looked up in a Geonames cities CSV file instead, set with --cities or the QSV_GEOCODE_CITIES
+environment variable. It must have the same "lat,lon,name,admin1,admin2,cc" columns as the
+embedded data, so the simplest is to download the cities.csv file the embedded data comes
+from, in the repository of the reverse_geocoder crate - https://github.com/gx0r/rrgeo.
+
+The place name is the city name, optionally followed by comma-separated qualifiers to
+disambiguate it - a country code (e.g. FR), an admin1/state name or, in the US, state
+abbreviation (e.g. NY), or an admin2/county name. Names are matched case-insensitively,
+ignoring punctuation. When there's no exact match, the most similar city name (with a
+Jaro-Winkler similarity of at least 0.9) that matches the qualifiers is used instead, so minor
+misspellings are tolerated. Place names that can't be found are left as is, as are ambiguous
+ones matching several places (e.g. "Springfield" without qualifiers).
+
+The geocoded information is formatted based on --formatstr, returning
+it in 'location' format if not specified.
+
+Examples:
+Geocode file.csv Place column into a new column named Location.
+
+$ qsv apply fwdgeocode Place --cities cities.csv --new-column Location file.csv
+
+Get the normalized 'city-state-country' name of the Place column.
+
+$ qsv apply fwdgeocode Place --cities cities.csv --formatstr city-state-country file.csv
+
 CALCCONV
 Parse and evaluate math expressions into a new column, with support for units and conversions.
 The math expression is built dynamically using the <--formatstr> template, similar to the DYNFMT
@@ -240,32 +365,127 @@ $ qsv apply calcconv --formatstr '10% of abs(sin(pi)) horsepower to watts' -c wa
 And use very large numbers:
 $ qsv apply calcconv --formatstr '{col1} Billion Trillion * {col2} quadrillion vigintillion' -c num_atoms file.csv 
 
+EXTRACT
+Extracts the capture groups of the <regex> matches in a column into new columns - one per
+capture group, or a single column with the whole match when the regex has no capture groups.
+See https://docs.rs/regex/latest/regex/#syntax for the regex syntax.
+
+The new columns are named after the --new-column comma-separated names, or after the named
+capture groups (e.g. (?P<code>[A-Z]{3})), or after the column name followed by the group
+number (e.g. notes_1) otherwise.
+
+With --matches first (the default), the groups of the first match are extracted. With
+--matches join, the groups of all the matches are extracted, joined with --separator. With
+--matches explode, each match is extracted into its own row, with the other columns copied.
+When nothing matches, the new columns are set to the --replacement fallback value.
+
+Examples:
+Extract the ticket code & number of the notes column (e.g. "see ABC-123") in new code & number
+columns:
+
+  $ qsv apply extract '(?P<code>[A-Z]{3})-(?P<number>[0-9]+)' notes file.csv
+
+Extract all the hashtags of the tweet column, joined with a space, in a new hashtags column:
+
+  $ qsv apply extract '#\w+' tweet --matches join --separator ' ' -c hashtags file.csv
+
+Extract each email address of the contacts column in its own row, with N/A when there's none:
+
+  $ qsv apply extract '[\w.+-]+@[\w-]+\.[\w.]+' contacts --matches explode -R N/A -c email file.csv
+
+RECIPE
+Applies the steps of a reusable <recipe> file to the CSV, in one pass. Each step applies an
+OPERATIONS, DATEFMT, DATEOPS or EMPTYREPLACE chain to the columns of its selector (see
+'qsv select --help'), with the settings of the equivalent apply options. The steps are run in
+order, each one working on the results of the previous ones - so the columns created by a
+step can be used by the following ones.
+
+The recipe is a YAML (or JSON) file with a list of steps:
+
+  steps:
+    - columns: first_name,last_name
+      operations: strip_invisible,trim,titlecase
+    - columns: email
+      operations: trim,lower
+    - columns: email
+      operations: hmac
+      new_column: email_key
+    - columns: /_date$/
+      datefmt: '%Y-%m-%d'
+    - columns: created_at
+      dateops: to_tz=Europe/Paris,trunc=month
+      formatstr: '%Y-%m'
+      new_column: created_month
+    - columns: notes
+      operations: replace
+      comparand: ' and '
+      replacement: ' & '
+    - columns: country
+      emptyreplace: Unknown
+
+Each step has a columns selector and exactly one of these chains:
+  * operations: the comma-separated operations, as with OPERATIONS.
+  * datefmt: the date format, as with the --formatstr of DATEFMT (e.g. '%+' for ISO 8601).
+  * dateops: the comma-separated date operations, as with DATEOPS.
+  * emptyreplace: the replacement of the empty cells, as with EMPTYREPLACE.
+
+And these optional settings:
+  * comparand: the --comparand of the operations & dateops chains.
+  * replacement: the --replacement of the operations chain.
+  * formatstr: the --formatstr of the dateops chain. [default: %+]
+  * mask_keep: the --mask-keep of the mask operation. [default: 4]
+  * new_column: the --new-column to put the transformed values in. Requires a single column.
+
+The whole recipe is checked before processing any row. As the censor, eudex, regex_replace
+& whatlang operations are set up once, each of them can only be used in one step.
+
+Examples:
+Clean up file.csv with the steps of the cleanup.yaml recipe:
+
+  $ qsv apply recipe cleanup.yaml file.csv -o cleaned.csv
+
 For more extensive examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_apply.rs.
 
 Usage:
 qsv apply operations <operations> [options] <column> [<input>]
 qsv apply emptyreplace --replacement=<string> [options] <column> [<input>]
 qsv apply datefmt [--formatstr=<string>] [options] <column> [<input>]
+qsv apply dateops <operations> [--formatstr=<string>] [options] <column> [<input>]
 qsv apply dynfmt --formatstr=<string> [options] --new-column=<name> [<input>]
 qsv apply geocode [--formatstr=<string>] [options] <column> [<input>]
+qsv apply fwdgeocode [--formatstr=<string>] [--cities=<file>] [options] <column> [<input>]
 qsv apply calcconv --formatstr=<string> [options] --new-column=<name> [<input>]
+qsv apply extract <regex> [options] <column> [<input>]
+qsv apply recipe <recipe> [options] [<input>]
 qsv apply --help
 
-The <column> argument can be a list of columns for the operations and datefmt subcommands.
+The <column> argument can be a list of columns for the operations, datefmt and dateops subcommands.
 See 'qsv select --help' for the format details.
 
 apply options:
     -c, --new-column <name>     Put the transformed values in a new column instead.
+                                For the extract subcommand, the comma-separated names of
+                                the new columns.
     -r, --rename <name>         New name for the transformed column.
     -C, --comparand=<string>    The string to compare against for replace & similarity operations.
-    -R, --replacement=<string>  The string to use for the replace & emptyreplace operations.
+                                For the diff date operation, the column of the other date.
+    -R, --replacement=<string>  The string to use for the replace & emptyreplace operations,
+                                and the fallback value of the extract subcommand when
+                                nothing matches.
+    --hmac-key <source>         Where to read the secret key of the hmac operation from -
+                                either 'env:<VAR>' for an environment variable, or
+                                'file:<path>' for a file (trailing newlines are trimmed).
+                                When not set, the QSV_HMAC_KEY environment variable is used.
+    --mask-keep <n>             The number of trailing characters the mask operation keeps.
+                                [default: 4]
     --prefer-dmy                Prefer to parse dates in dmy format. Otherwise, use mdy format.
-                                Only used with the DATEFMT subcommand.
+                                Only used with the DATEFMT, DATEOPS & RECIPE subcommands.
     --keep-zero-time            If a formatted date ends with "T00:00:00+00:00", keep the time
-                                instead of removing it. Only used with the DATEFMT subcommand.
+                                instead of removing it. Only used with the DATEFMT, DATEOPS &
+                                RECIPE subcommands.
     -f, --formatstr=<string>    This option is used by several subcommands:
 
-                                DATEFMT: The date format to use. For formats, see
+                                DATEFMT & DATEOPS: The date format to use. For formats, see
                                   https://docs.rs/chrono/latest/chrono/format/strftime/
                                   Default to ISO 8601 / RFC 3339 date & time format.
                                   [default: %+]
@@ -283,6 +503,18 @@ apply options:
                                   - 'county-country' | 'admin2-country' - Kings County, US
                                   - 'county-state-country' | 'admin2-admin1-country' - Kings County, New York US
                                   - 'country' - US
+
+                                FWDGEOCODE: the same place formats as GEOCODE, plus:
+                                  - 'location' (default) - e.g. 40.6501, -73.94958
+                                  - 'latitude' | 'lat' - 40.6501
+                                  - 'longitude' | 'lon' - -73.94958
+    --cities <file>             The Geonames cities CSV file of the fwdgeocode subcommand.
+                                When not set, the QSV_GEOCODE_CITIES environment variable
+                                is used.
+    --matches <mode>            The matches of the extract subcommand - first, join (all the
+                                matches, joined with --separator) or explode (a row per match).
+                                [default: first]
+    --separator <string>        The separator of the joined extract matches. [default: |]
     -j, --jobs <arg>            The number of jobs to run in parallel.
                                 When not set, the number of jobs is set to the number of CPUs detected.
 
@@ -296,16 +528,25 @@ Common options:
     -p, --progressbar           Show progress bars. Not valid for stdin.
 "#;
 
-use std::str::FromStr;
+use std::{fs, str::FromStr};
 
+use ahash::AHashMap;
 use cached::proc_macro::cached;
+use caseless::default_case_fold_str;
 use censor::{Censor, Sex, Zealous};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, Offset, TimeZone,
+    Timelike, Utc,
+};
+use chrono_tz::Tz;
 use cpc::{eval, units::Unit};
-use data_encoding::BASE64;
+use data_encoding::{BASE64, HEXLOWER};
+use deunicode::deunicode;
 use dynfmt::Format;
 use eudex::Hash;
 use indicatif::{ProgressBar, ProgressDrawTarget};
 use log::debug;
+use md5::{Digest, Md5};
 use once_cell::sync::OnceCell;
 use qsv_currency::Currency;
 use qsv_dateparser::parse_with_preference;
@@ -313,14 +554,17 @@ use rayon::prelude::*;
 use regex::Regex;
 use reverse_geocoder::{Locations, ReverseGeocoder};
 use serde::Deserialize;
+use sha2::Sha256;
 use strsim::{
     damerau_levenshtein, hamming, jaro_winkler, normalized_damerau_levenshtein, osa_distance,
     sorensen_dice,
 };
 use strum_macros::EnumString;
 use titlecase::titlecase;
+use unicode_normalization::UnicodeNormalization;
 use vader_sentiment::SentimentIntensityAnalyzer;
 use whatlang::detect;
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::{
     clitypes::CliError,
@@ -337,6 +581,9 @@ const BATCH_SIZE: usize = 24_000;
 #[strum(ascii_case_insensitive)]
 #[allow(non_camel_case_types)]
 enum Operations {
+    Ascii,
+    Blake3,
+    Casefold,
     Censor,
     Censor_Check,
     Censor_Count,
@@ -346,16 +593,24 @@ enum Operations {
     Encode,
     Escape,
     Eudex,
+    Hmac,
     Len,
     Lower,
     Ltrim,
+    Mask,
+    Md5,
     Mltrim,
     Mrtrim,
     Mtrim,
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
     Regex_Replace,
     Replace,
     Rtrim,
     Sentiment,
+    Sha256,
     Simdl,
     Simdln,
     Simhm,
@@ -364,12 +619,15 @@ enum Operations {
     Simsd,
     Squeeze,
     Squeeze0,
+    Strip_Invisible,
     Strip_Prefix,
     Strip_Suffix,
     Titlecase,
     Trim,
+    Unaccent,
     Upper,
     Whatlang,
+    Xxh3,
 }
 
 #[derive(Deserialize, Debug)]
@@ -378,17 +636,28 @@ struct Args {
     cmd_operations:      bool,
     arg_operations:      String,
     cmd_datefmt:         bool,
+    cmd_dateops:         bool,
     cmd_dynfmt:          bool,
     cmd_emptyreplace:    bool,
     cmd_geocode:         bool,
+    cmd_fwdgeocode:      bool,
     cmd_calcconv:        bool,
+    cmd_extract:         bool,
+    arg_regex:           String,
+    cmd_recipe:          bool,
+    arg_recipe:          String,
     arg_input:           Option<String>,
     flag_rename:         Option<String>,
     flag_comparand:      String,
     flag_replacement:    String,
+    flag_hmac_key:       Option<String>,
+    flag_mask_keep:      usize,
     flag_prefer_dmy:     bool,
     flag_keep_zero_time: bool,
     flag_formatstr:      String,
+    flag_cities:         Option<String>,
+    flag_matches:        String,
+    flag_separator:      String,
     flag_jobs:           Option<usize>,
     flag_new_column:     Option<String>,
     flag_output:         Option<String>,
@@ -400,7 +669,9 @@ struct Args {
 static CENSOR: OnceCell<Censor> = OnceCell::new();
 static LOCS: OnceCell<Locations> = OnceCell::new();
 static GEOCODER: OnceCell<ReverseGeocoder> = OnceCell::new();
+static FORWARD_GEOCODER: OnceCell<ForwardGeocoder> = OnceCell::new();
 static EUDEX_COMPARAND_HASH: OnceCell<eudex::Hash> = OnceCell::new();
+static HMAC_KEY: OnceCell<Vec<u8>> = OnceCell::new();
 static REGEX_REPLACE: OnceCell<Regex> = OnceCell::new();
 static SENTIMENT_ANALYZER: OnceCell<SentimentIntensityAnalyzer> = OnceCell::new();
 static WHATLANG_CONFIDENCE_THRESHOLD: OnceCell<f64> = OnceCell::new();
@@ -408,6 +679,9 @@ static WHATLANG_CONFIDENCE_THRESHOLD: OnceCell<f64> = OnceCell::new();
 // default confidence threshold for whatlang language detection - 90% confidence
 const DEFAULT_THRESHOLD: f64 = 0.9;
 
+// default number of trailing characters the mask operation of recipes keeps
+const DEFAULT_MASK_KEEP: usize = 4;
+
 #[inline]
 pub fn replace_column_value(
     record: &csv::StringRecord,
@@ -448,8 +722,41 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    let extractor = if args.cmd_extract {
+        if sel.len() != 1 {
+            return fail!("extract subcommand requires a single column.");
+        }
+        Some(Extractor::new(
+            &args.arg_regex,
+            &args.flag_matches,
+            &args.flag_separator,
+            &args.flag_replacement,
+        )?)
+    } else {
+        None
+    };
+
+    let recipe = if args.cmd_recipe {
+        Some(Recipe::from_path(
+            &args.arg_recipe,
+            rdr.byte_headers()?,
+            rconfig.no_headers,
+            &args.flag_hmac_key,
+        )?)
+    } else {
+        None
+    };
+
     if !rconfig.no_headers {
-        if let Some(new_column) = &args.flag_new_column {
+        if let Some(ref extractor) = extractor {
+            for name in extractor.column_names(&headers[column_index], &args.flag_new_column)? {
+                headers.push_field(&name);
+            }
+        } else if let Some(ref recipe) = recipe {
+            for name in &recipe.new_columns {
+                headers.push_field(name);
+            }
+        } else if let Some(new_column) = &args.flag_new_column {
             headers.push_field(new_column);
         }
         wtr.write_record(&headers)?;
@@ -488,13 +795,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     enum ApplySubCmd {
         Operations,
         DateFmt,
+        DateOps,
         DynFmt,
         Geocode,
+        FwdGeocode,
         EmptyReplace,
         CalcConv,
+        Extract,
+        Recipe,
     }
 
     let mut ops_vec: Vec<Operations> = Vec::new();
+    let mut dateops_vec: Vec<DateOp> = Vec::new();
 
     let apply_cmd = if args.cmd_operations {
         match validate_operations(
@@ -502,6 +814,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             &args.flag_comparand,
             &args.flag_replacement,
             &args.flag_new_column,
+            &args.flag_hmac_key,
         ) {
             Ok(operations_vec) => ops_vec = operations_vec,
             Err(e) => return Err(e),
@@ -509,14 +822,41 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         ApplySubCmd::Operations
     } else if args.cmd_geocode {
         ApplySubCmd::Geocode
+    } else if args.cmd_fwdgeocode {
+        let cities = match args.flag_cities {
+            Some(ref cities) => cities.clone(),
+            None => match std::env::var("QSV_GEOCODE_CITIES") {
+                Ok(cities) => cities,
+                Err(_) => {
+                    return fail!(
+                        "fwdgeocode requires a Geonames cities file, set with --cities or the \
+                         QSV_GEOCODE_CITIES environment variable."
+                    )
+                }
+            },
+        };
+        let _ = FORWARD_GEOCODER.set(ForwardGeocoder::from_path(&cities)?);
+        ApplySubCmd::FwdGeocode
     } else if args.cmd_datefmt {
         ApplySubCmd::DateFmt
+    } else if args.cmd_dateops {
+        dateops_vec = validate_dateops(
+            &args.arg_operations,
+            &args.flag_comparand,
+            rdr.byte_headers()?,
+            rconfig.no_headers,
+        )?;
+        ApplySubCmd::DateOps
     } else if args.cmd_dynfmt {
         ApplySubCmd::DynFmt
     } else if args.cmd_emptyreplace {
         ApplySubCmd::EmptyReplace
     } else if args.cmd_calcconv {
         ApplySubCmd::CalcConv
+    } else if args.cmd_extract {
+        ApplySubCmd::Extract
+    } else if args.cmd_recipe {
+        ApplySubCmd::Recipe
     } else {
         return fail!("Unknown apply subcommand.");
     };
@@ -575,6 +915,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             .par_iter()
             .map(|record_item| {
                 let mut record = record_item.clone();
+                // the rows of the extract explode mode, written instead of the record
+                let mut exploded_records = None;
                 match apply_cmd {
                     ApplySubCmd::Geocode => {
                         let mut cell = record[column_index].to_owned();
@@ -590,6 +932,20 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             record = replace_column_value(&record, column_index, &cell);
                         }
                     }
+                    ApplySubCmd::FwdGeocode => {
+                        let mut cell = record[column_index].to_owned();
+                        if !cell.is_empty() {
+                            let search_result = forward_search_cached(&cell, &args.flag_formatstr);
+                            if let Some(geocoded_result) = search_result {
+                                cell = geocoded_result;
+                            }
+                        }
+                        if args.flag_new_column.is_some() {
+                            record.push_field(&cell);
+                        } else {
+                            record = replace_column_value(&record, column_index, &cell);
+                        }
+                    }
                     ApplySubCmd::Operations => {
                         for col_index in sel.iter() {
                             let mut cell = record[*col_index].to_owned();
@@ -598,6 +954,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                                 &mut cell,
                                 &args.flag_comparand,
                                 &args.flag_replacement,
+                                args.flag_mask_keep,
                             );
                             if args.flag_new_column.is_some() {
                                 record.push_field(&cell);
@@ -622,16 +979,34 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             let mut cell = record[*col_index].to_owned();
                             if !cell.is_empty() {
                                 let parsed_date = parse_with_preference(&cell, prefer_dmy);
-                                if let Ok(format_date) = parsed_date {
-                                    let formatted_date =
-                                        format_date.format(&args.flag_formatstr).to_string();
-                                    if !args.flag_keep_zero_time
-                                        && formatted_date.ends_with("T00:00:00+00:00")
-                                    {
-                                        cell = formatted_date[..10].to_string();
-                                    } else {
-                                        cell = formatted_date;
-                                    }
+                                if let Ok(date) = parsed_date {
+                                    cell = format_date(
+                                        &date,
+                                        &args.flag_formatstr,
+                                        args.flag_keep_zero_time,
+                                    );
+                                }
+                            }
+                            if args.flag_new_column.is_some() {
+                                record.push_field(&cell);
+                            } else {
+                                record = replace_column_value(&record, *col_index, &cell);
+                            }
+                        }
+                    }
+                    ApplySubCmd::DateOps => {
+                        for col_index in sel.iter() {
+                            let mut cell = record[*col_index].to_owned();
+                            if !cell.is_empty() {
+                                if let Some(result) = apply_dateops(
+                                    &dateops_vec,
+                                    &cell,
+                                    &record,
+                                    prefer_dmy,
+                                    &args.flag_formatstr,
+                                    args.flag_keep_zero_time,
+                                ) {
+                                    cell = result;
                                 }
                             }
                             if args.flag_new_column.is_some() {
@@ -702,15 +1077,51 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             record = replace_column_value(&record, column_index, &result);
                         }
                     }
+                    ApplySubCmd::Extract => {
+                        // extractor is always set for the extract subcommand
+                        let extractor = extractor.as_ref().unwrap();
+                        let cell = &record_item[column_index];
+                        match extractor.mode {
+                            ExtractMode::Explode => {
+                                exploded_records = Some(
+                                    extractor
+                                        .explode(cell)
+                                        .into_iter()
+                                        .map(|values| {
+                                            let mut exploded_record = record.clone();
+                                            exploded_record.extend(values);
+                                            exploded_record
+                                        })
+                                        .collect::<Vec<csv::StringRecord>>(),
+                                );
+                            }
+                            _ => record.extend(extractor.extract(cell)),
+                        }
+                    }
+                    ApplySubCmd::Recipe => {
+                        // recipe is always set for the recipe subcommand
+                        recipe.as_ref().unwrap().apply(
+                            &mut record,
+                            prefer_dmy,
+                            args.flag_keep_zero_time,
+                        );
+                    }
                 }
 
-                record
+                (record, exploded_records)
             })
             .collect_into_vec(&mut batch_results);
 
         // rayon collect() guarantees original order, so we can just append results each batch
-        for result_record in &batch_results {
-            wtr.write_record(result_record)?;
+        for (result_record, exploded_records) in &batch_results {
+            match exploded_records {
+                Some(records) => {
+                    for record in records {
+                        wtr.write_record(record)?;
+                    }
+                }
+                None => wtr.write_record(result_record)?,
+            }
         }
 
         if show_progress {
@@ -723,6 +1134,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if show_progress {
         if args.cmd_geocode {
             util::update_cache_info!(progress, SEARCH_CACHED);
+        } else if args.cmd_fwdgeocode {
+            util::update_cache_info!(progress, FORWARD_SEARCH_CACHED);
         }
         util::finish_progress(&progress);
     }
@@ -736,6 +1149,7 @@ fn validate_operations(
     flag_comparand: &String,
     flag_replacement: &String,
     flag_new_column: &Option<String>,
+    flag_hmac_key: &Option<String>,
 ) -> Result<Vec<Operations>, CliError> {
     let mut censor_invokes = 0_u8;
     let mut copy_invokes = 0_u8;
@@ -792,6 +1206,12 @@ fn validate_operations(
                 }
                 eudex_invokes = eudex_invokes.saturating_add(1);
             }
+            Operations::Hmac => {
+                if HMAC_KEY.get().is_none() {
+                    let key = util::read_hmac_key(flag_hmac_key)?;
+                    let _ = HMAC_KEY.set(key);
+                }
+            }
             Operations::Mtrim | Operations::Mltrim | Operations::Mrtrim => {
                 if flag_comparand.is_empty() {
                     return fail!("--comparand (-C) is required for match trim operations.");
@@ -917,12 +1337,62 @@ fn validate_operations(
     Ok(ops_vec) // no validation errors
 }
 
+// zero-width, bidirectional & other invisible formatting characters, and control characters
+// other than whitespace ones
+fn is_invisible(c: char) -> bool {
+    (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        || matches!(
+            c,
+            '\u{00AD}'
+                | '\u{180E}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{206F}'
+                | '\u{FEFF}'
+        )
+}
+
+// mask the letters & digits of the cell, except for its last keep characters
+fn mask(cell: &str, keep: usize) -> String {
+    let mask_len = cell.chars().count().saturating_sub(keep);
+    cell.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i >= mask_len {
+                c
+            } else if c.is_ascii_digit() {
+                '9'
+            } else if c.is_uppercase() {
+                'X'
+            } else if c.is_alphabetic() {
+                'x'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+// the characters of the Combining Diacritical Marks blocks - the base one, its Extended
+// & Supplement blocks, and the Combining Half Marks
+const fn is_diacritical_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036f}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
 #[inline]
 fn apply_operations(
     ops_vec: &Vec<Operations>,
     cell: &mut String,
     comparand: &str,
     replacement: &str,
+    mask_keep: usize,
 ) {
     for op in ops_vec {
         match op {
@@ -943,6 +1413,37 @@ fn apply_operations(
                 let squeezer: &'static Regex = regex_once_cell!(r"\s+");
                 *cell = squeezer.replace_all(cell, "").to_string();
             }
+            Operations::Nfc => {
+                *cell = cell.nfc().collect();
+            }
+            Operations::Nfd => {
+                *cell = cell.nfd().collect();
+            }
+            Operations::Nfkc => {
+                *cell = cell.nfkc().collect();
+            }
+            Operations::Nfkd => {
+                *cell = cell.nfkd().collect();
+            }
+            Operations::Unaccent => {
+                // decompose, drop the combining diacritical marks, then recompose.
+                // the other combining marks (e.g. the vowel signs of Devanagari, Thai or
+                // Arabic) are kept, as they are part of the letters
+                *cell = cell
+                    .nfd()
+                    .filter(|c| !is_diacritical_mark(*c))
+                    .nfc()
+                    .collect();
+            }
+            Operations::Ascii => {
+                *cell = deunicode(cell);
+            }
+            Operations::Casefold => {
+                *cell = default_case_fold_str(cell);
+            }
+            Operations::Strip_Invisible => {
+                cell.retain(|c| !is_invisible(c));
+            }
             Operations::Trim => {
                 *cell = String::from(cell.trim());
             }
@@ -975,6 +1476,24 @@ fn apply_operations(
             Operations::Escape => {
                 *cell = cell.escape_default().to_string();
             }
+            Operations::Md5 => {
+                *cell = HEXLOWER.encode(&Md5::digest(cell.as_bytes()));
+            }
+            Operations::Sha256 => {
+                *cell = HEXLOWER.encode(&Sha256::digest(cell.as_bytes()));
+            }
+            Operations::Blake3 => {
+                *cell = blake3::hash(cell.as_bytes()).to_hex().to_string();
+            }
+            Operations::Xxh3 => {
+                *cell = format!("{:016x}", xxh3_64(cell.as_bytes()));
+            }
+            Operations::Hmac => {
+                *cell = util::hmac_sha256_hex(HMAC_KEY.get().unwrap(), cell);
+            }
+            Operations::Mask => {
+                *cell = mask(cell, mask_keep);
+            }
             Operations::Strip_Prefix => {
                 if let Some(stripped) = cell.strip_prefix(comparand) {
                     *cell = String::from(stripped);
@@ -1080,6 +1599,686 @@ fn apply_operations(
     }
 }
 
+// a calendar unit, to truncate dates to & to compute date differences in
+#[derive(Clone, Copy)]
+enum DateUnit {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateUnit {
+    fn parse(unit: &str) -> Option<DateUnit> {
+        // both singular & plural units are accepted
+        Some(match unit.trim().to_lowercase().trim_end_matches('s') {
+            "year" => DateUnit::Year,
+            "quarter" => DateUnit::Quarter,
+            "month" => DateUnit::Month,
+            "week" => DateUnit::Week,
+            "day" => DateUnit::Day,
+            "hour" => DateUnit::Hour,
+            "minute" => DateUnit::Minute,
+            "second" => DateUnit::Second,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum DatePart {
+    Year,
+    Quarter,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Weekday,
+    Isoweek,
+    Isoyear,
+    Dayofyear,
+    Epoch,
+}
+
+// a calendar-aware duration, e.g. "1y6mo" or "2w 3d 12h"
+#[derive(Clone, Copy, Default)]
+struct DateShift {
+    months:  u32,
+    days:    i64,
+    seconds: i64,
+}
+
+impl DateShift {
+    fn parse(s: &str) -> Option<DateShift> {
+        let duration_re: &'static Regex =
+            regex_once_cell!(r"(?i)^\s*(?:[0-9]+\s*(?:y|mo|w|d|h|m|s)\s*)+$");
+        if !duration_re.is_match(s) {
+            return None;
+        }
+        let part_re: &'static Regex = regex_once_cell!(r"(?i)([0-9]+)\s*(mo|y|w|d|h|m|s)");
+        let mut shift = DateShift::default();
+        for caps in part_re.captures_iter(s) {
+            let n = caps[1].parse::<u32>().ok()?;
+            match caps[2].to_lowercase().as_str() {
+                "y" => shift.months = shift.months.checked_add(n.checked_mul(12)?)?,
+                "mo" => shift.months = shift.months.checked_add(n)?,
+                "w" => shift.days += i64::from(n) * 7,
+                "d" => shift.days += i64::from(n),
+                "h" => shift.seconds += i64::from(n) * 3_600,
+                "m" => shift.seconds += i64::from(n) * 60,
+                _ => shift.seconds += i64::from(n),
+            }
+        }
+        Some(shift)
+    }
+}
+
+enum DateOp {
+    ToTz(Tz),
+    FromTz(Tz),
+    Add(DateShift),
+    Sub(DateShift),
+    Trunc(DateUnit),
+    Part(DatePart),
+    // the difference with the date of the column, in the unit
+    Diff(DateUnit, usize),
+}
+
+// validate the dateops operations & prepare the DateOp vec
+fn validate_dateops(
+    operations: &str,
+    flag_comparand: &str,
+    headers: &csv::ByteRecord,
+    no_headers: bool,
+) -> Result<Vec<DateOp>, CliError> {
+    let operations: Vec<&str> = operations.split(',').map(str::trim).collect();
+    let mut ops_vec: Vec<DateOp> = Vec::with_capacity(operations.len());
+
+    for (i, op) in operations.iter().enumerate() {
+        let (name, arg) = match op.split_once('=') {
+            Some((name, arg)) => (name.trim().to_lowercase(), Some(arg.trim())),
+            None => (op.to_lowercase(), None),
+        };
+        let date_op = match (name.as_str(), arg) {
+            ("to_tz" | "from_tz", Some(arg)) => {
+                let Ok(tz) = arg.parse::<Tz>() else {
+                    return fail_clierror!(
+                        "Unknown timezone '{arg}' in the '{op}' date operation. Use an IANA \
+                         timezone name, e.g. America/New_York."
+                    );
+                };
+                if name == "to_tz" {
+                    DateOp::ToTz(tz)
+                } else {
+                    DateOp::FromTz(tz)
+                }
+            }
+            ("add" | "sub", Some(arg)) => {
+                let Some(shift) = DateShift::parse(arg) else {
+                    return fail_clierror!(
+                        "Invalid duration '{arg}' in the '{op}' date operation, e.g. 1y6mo or \
+                         2w3d12h."
+                    );
+                };
+                if name == "add" {
+                    DateOp::Add(shift)
+                } else {
+                    DateOp::Sub(shift)
+                }
+            }
+            ("trunc", Some(arg)) => match DateUnit::parse(arg) {
+                Some(unit) => DateOp::Trunc(unit),
+                None => {
+                    return fail_clierror!(
+                        "Invalid unit '{arg}' in the '{op}' date operation. Use year, quarter, \
+                         month, week, day, hour, minute or second."
+                    )
+                }
+            },
+            ("diff", Some(arg)) => {
+                let Some(unit) = DateUnit::parse(arg) else {
+                    return fail_clierror!(
+                        "Invalid unit '{arg}' in the '{op}' date operation. Use years, quarters, \
+                         months, weeks, days, hours, minutes or seconds."
+                    );
+                };
+                if flag_comparand.is_empty() {
+                    return fail!(
+                        "--comparand (-C) must be the other date column of the diff operation."
+                    );
+                }
+                let sel = SelectColumns::parse(flag_comparand)?.selection(headers, !no_headers)?;
+                if sel.len() != 1 {
+                    return fail!("--comparand (-C) must select a single date column.");
+                }
+                DateOp::Diff(unit, sel[0])
+            }
+            ("to_tz" | "from_tz" | "add" | "sub" | "trunc" | "diff", None) => {
+                return fail_clierror!(
+                    "The '{name}' date operation requires an argument, e.g. {name}=<arg>."
+                );
+            }
+            (part, None) => DateOp::Part(match part {
+                "year" => DatePart::Year,
+                "quarter" => DatePart::Quarter,
+                "month" => DatePart::Month,
+                "day" => DatePart::Day,
+                "hour" => DatePart::Hour,
+                "minute" => DatePart::Minute,
+                "second" => DatePart::Second,
+                "weekday" => DatePart::Weekday,
+                "isoweek" => DatePart::Isoweek,
+                "isoyear" => DatePart::Isoyear,
+                "dayofyear" => DatePart::Dayofyear,
+                "epoch" => DatePart::Epoch,
+                _ => return fail_clierror!("Unknown '{op}' date operation"),
+            }),
+            (_, Some(_)) => return fail_clierror!("Unknown '{op}' date operation"),
+        };
+        // parts & differences aren't dates anymore, so nothing can follow them
+        if matches!(date_op, DateOp::Part(_) | DateOp::Diff(..)) && i + 1 < operations.len() {
+            return fail_clierror!("The '{op}' date operation must be the last one.");
+        }
+        ops_vec.push(date_op);
+    }
+    Ok(ops_vec)
+}
+
+// shift the date by the duration, with the months & days in local calendar time
+fn shift_date(date: DateTime<Tz>, shift: DateShift, add: bool) -> Option<DateTime<Tz>> {
+    let mut local = date.naive_local();
+    let months = Months::new(shift.months);
+    let days = Duration::days(shift.days);
+    local = if add {
+        local.checked_add_months(months)?.checked_add_signed(days)?
+    } else {
+        local.checked_sub_months(months)?.checked_sub_signed(days)?
+    };
+    let date = local_date(date.timezone(), &local)?;
+    let seconds = Duration::seconds(shift.seconds);
+    if add {
+        date.checked_add_signed(seconds)
+    } else {
+        date.checked_sub_signed(seconds)
+    }
+}
+
+// truncate the date to the start of its unit, in local time. Weeks start on Monday.
+fn truncate_date(date: DateTime<Tz>, unit: DateUnit) -> Option<DateTime<Tz>> {
+    let local = date.naive_local();
+    let day = local.date();
+    let truncated = match unit {
+        DateUnit::Year => NaiveDate::from_ymd_opt(day.year(), 1, 1)?.and_hms_opt(0, 0, 0)?,
+        DateUnit::Quarter => NaiveDate::from_ymd_opt(day.year(), day.month0() / 3 * 3 + 1, 1)?
+            .and_hms_opt(0, 0, 0)?,
+        DateUnit::Month => {
+            NaiveDate::from_ymd_opt(day.year(), day.month(), 1)?.and_hms_opt(0, 0, 0)?
+        }
+        DateUnit::Week => day
+            .checked_sub_signed(Duration::days(day.weekday().num_days_from_monday().into()))?
+            .and_hms_opt(0, 0, 0)?,
+        DateUnit::Day => day.and_hms_opt(0, 0, 0)?,
+        DateUnit::Hour => day.and_hms_opt(local.hour(), 0, 0)?,
+        DateUnit::Minute => day.and_hms_opt(local.hour(), local.minute(), 0)?,
+        DateUnit::Second => day.and_hms_opt(local.hour(), local.minute(), local.second())?,
+    };
+    local_date(date.timezone(), &truncated)
+}
+
+// the date of the local time in the timezone. Ambiguous local times get the earliest offset,
+// & the ones skipped by a DST gap are resolved forward, by the length of the gap (e.g. 02:30
+// becomes 03:30 when the clocks go from 02:00 to 03:00), with the offset of the day before.
+fn local_date(tz: Tz, local: &NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(local) {
+        LocalResult::Single(date) | LocalResult::Ambiguous(date, _) => Some(date),
+        LocalResult::None => {
+            let before = tz
+                .from_local_datetime(&local.checked_sub_signed(Duration::days(1))?)
+                .earliest()?;
+            let offset = Duration::seconds(before.offset().fix().local_minus_utc().into());
+            Some(tz.from_utc_datetime(&local.checked_sub_signed(offset)?))
+        }
+    }
+}
+
+fn date_part(date: DateTime<Tz>, part: DatePart) -> String {
+    match part {
+        DatePart::Year => date.year().to_string(),
+        DatePart::Quarter => (date.month0() / 3 + 1).to_string(),
+        DatePart::Month => date.month().to_string(),
+        DatePart::Day => date.day().to_string(),
+        DatePart::Hour => date.hour().to_string(),
+        DatePart::Minute => date.minute().to_string(),
+        DatePart::Second => date.second().to_string(),
+        DatePart::Weekday => date.weekday().number_from_monday().to_string(),
+        DatePart::Isoweek => date.iso_week().week().to_string(),
+        DatePart::Isoyear => date.iso_week().year().to_string(),
+        DatePart::Dayofyear => date.ordinal().to_string(),
+        DatePart::Epoch => date.timestamp().to_string(),
+    }
+}
+
+// the number of whole units from the start to the end date, negative if end is before start
+fn date_diff(start: DateTime<Utc>, end: DateTime<Utc>, unit: DateUnit) -> i64 {
+    let months = || {
+        let mut months = i64::from(end.year() - start.year()) * 12 + i64::from(end.month())
+            - i64::from(start.month());
+        // an incomplete last month doesn't count
+        let start_rest = (start.day(), start.time());
+        let end_rest = (end.day(), end.time());
+        if months > 0 && end_rest < start_rest {
+            months -= 1;
+        } else if months < 0 && end_rest > start_rest {
+            months += 1;
+        }
+        months
+    };
+    let duration = end - start;
+    match unit {
+        DateUnit::Year => months() / 12,
+        DateUnit::Quarter => months() / 3,
+        DateUnit::Month => months(),
+        DateUnit::Week => duration.num_weeks(),
+        DateUnit::Day => duration.num_days(),
+        DateUnit::Hour => duration.num_hours(),
+        DateUnit::Minute => duration.num_minutes(),
+        DateUnit::Second => duration.num_seconds(),
+    }
+}
+
+// apply the date operations to the cell. None when the cell is not a date.
+fn apply_dateops(
+    ops_vec: &[DateOp],
+    cell: &str,
+    record: &csv::StringRecord,
+    prefer_dmy: bool,
+    formatstr: &str,
+    keep_zero_time: bool,
+) -> Option<String> {
+    let mut date = parse_with_preference(cell, prefer_dmy)
+        .ok()?
+        .with_timezone(&Tz::UTC);
+    for op in ops_vec {
+        match op {
+            DateOp::ToTz(tz) => date = date.with_timezone(tz),
+            DateOp::FromTz(tz) => date = local_date(*tz, &date.naive_local())?,
+            DateOp::Add(shift) => date = shift_date(date, *shift, true)?,
+            DateOp::Sub(shift) => date = shift_date(date, *shift, false)?,
+            DateOp::Trunc(unit) => date = truncate_date(date, *unit)?,
+            DateOp::Part(part) => return Some(date_part(date, *part)),
+            DateOp::Diff(unit, column) => {
+                let other = record.get(*column).unwrap_or_default();
+                let Ok(start) = parse_with_preference(other, prefer_dmy) else {
+                    return Some(String::new());
+                };
+                return Some(date_diff(start, date.with_timezone(&Utc), *unit).to_string());
+            }
+        }
+    }
+    Some(format_date(&date, formatstr, keep_zero_time))
+}
+
+// format the date, dropping the time of dates at midnight UTC unless keep_zero_time is set
+fn format_date<T: TimeZone>(date: &DateTime<T>, formatstr: &str, keep_zero_time: bool) -> String
+where
+    T::Offset: std::fmt::Display,
+{
+    let formatted = date.format(formatstr).to_string();
+    if !keep_zero_time && formatted.ends_with("T00:00:00+00:00") {
+        formatted[..10].to_string()
+    } else {
+        formatted
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExtractMode {
+    First,
+    Join,
+    Explode,
+}
+
+// the regex & capture groups of the extract subcommand
+struct Extractor {
+    re:        Regex,
+    // the capture groups to extract, or the whole match when the regex has none
+    groups:    Vec<usize>,
+    mode:      ExtractMode,
+    separator: String,
+    fallback:  String,
+}
+
+impl Extractor {
+    fn new(regex: &str, matches: &str, separator: &str, fallback: &str) -> CliResult<Extractor> {
+        let re = match Regex::new(regex) {
+            Ok(re) => re,
+            Err(e) => return fail_clierror!("extract regex error: {e}"),
+        };
+        let groups = if re.captures_len() > 1 {
+            (1..re.captures_len()).collect()
+        } else {
+            vec![0]
+        };
+        let mode = match matches.to_lowercase().as_str() {
+            "first" => ExtractMode::First,
+            "join" => ExtractMode::Join,
+            "explode" => ExtractMode::Explode,
+            _ => {
+                return fail_clierror!(
+                    "Invalid --matches mode: {matches}. Use first, join or explode."
+                )
+            }
+        };
+        Ok(Extractor {
+            re,
+            groups,
+            mode,
+            separator: separator.to_string(),
+            fallback: fallback.to_string(),
+        })
+    }
+
+    // the names of the new columns - the --new-column names, or the names of the capture
+    // groups, or the column name followed by the group number otherwise
+    fn column_names(&self, column: &str, new_column: &Option<String>) -> CliResult<Vec<String>> {
+        if let Some(new_column) = new_column {
+            let names = util::ColumnNameParser::new(new_column).parse()?;
+            if names.len() != self.groups.len() {
+                return fail_clierror!(
+                    "--new-column must have {} comma-separated name(s), one per extracted group.",
+                    self.groups.len()
+                );
+            }
+            return Ok(names);
+        }
+        let group_names: Vec<Option<&str>> = self.re.capture_names().collect();
+        Ok(self
+            .groups
+            .iter()
+            .map(|group| match group_names[*group] {
+                Some(name) => name.to_string(),
+                None if *group == 0 => format!("{column}_match"),
+                None => format!("{column}_{group}"),
+            })
+            .collect())
+    }
+
+    fn values(&self, caps: &regex::Captures) -> Vec<String> {
+        self.groups
+            .iter()
+            .map(|group| caps.get(*group).map_or("", |m| m.as_str()).to_string())
+            .collect()
+    }
+
+    // the values of the first match, or of all the matches joined with the separator
+    fn extract(&self, cell: &str) -> Vec<String> {
+        let mut matches = self.re.captures_iter(cell).map(|caps| self.values(&caps));
+        if self.mode == ExtractMode::First {
+            return matches
+                .next()
+                .unwrap_or_else(|| vec![self.fallback.clone(); self.groups.len()]);
+        }
+        let matches: Vec<Vec<String>> = matches.collect();
+        if matches.is_empty() {
+            return vec![self.fallback.clone(); self.groups.len()];
+        }
+        (0..self.groups.len())
+            .map(|i| {
+                matches
+                    .iter()
+                    .map(|values| values[i].as_str())
+                    .collect::<Vec<&str>>()
+                    .join(&self.separator)
+            })
+            .collect()
+    }
+
+    // the values of each match, or the fallback values when there's no match
+    fn explode(&self, cell: &str) -> Vec<Vec<String>> {
+        let matches: Vec<Vec<String>> = self
+            .re
+            .captures_iter(cell)
+            .map(|caps| self.values(&caps))
+            .collect();
+        if matches.is_empty() {
+            vec![vec![self.fallback.clone(); self.groups.len()]]
+        } else {
+            matches
+        }
+    }
+}
+
+// the operations set up once per process, so they can only be used in one recipe step
+const SINGLE_STEP_OPERATIONS: [&str; 4] = ["censor", "eudex", "regex_replace", "whatlang"];
+
+fn default_recipe_formatstr() -> String {
+    "%+".to_string()
+}
+
+const fn default_recipe_mask_keep() -> usize {
+    DEFAULT_MASK_KEEP
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeFile {
+    steps: Vec<RecipeStepDef>,
+}
+
+// a recipe step, as in the recipe file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeStepDef {
+    columns:      String,
+    operations:   Option<String>,
+    datefmt:      Option<String>,
+    dateops:      Option<String>,
+    emptyreplace: Option<String>,
+    #[serde(default)]
+    comparand:    String,
+    #[serde(default)]
+    replacement:  String,
+    #[serde(default = "default_recipe_formatstr")]
+    formatstr:    String,
+    #[serde(default = "default_recipe_mask_keep")]
+    mask_keep:    usize,
+    new_column:   Option<String>,
+}
+
+enum RecipeAction {
+    Operations(Vec<Operations>),
+    DateFmt(String),
+    DateOps(Vec<DateOp>),
+    EmptyReplace(String),
+}
+
+struct RecipeStep {
+    columns:     Vec<usize>,
+    action:      RecipeAction,
+    comparand:   String,
+    replacement: String,
+    formatstr:   String,
+    mask_keep:   usize,
+    new_column:  Option<String>,
+}
+
+// the validated steps of the recipe subcommand
+struct Recipe {
+    steps:       Vec<RecipeStep>,
+    // the names of the columns added by the steps, in order
+    new_columns: Vec<String>,
+}
+
+impl Recipe {
+    fn from_path(
+        path: &str,
+        headers: &csv::ByteRecord,
+        no_headers: bool,
+        flag_hmac_key: &Option<String>,
+    ) -> CliResult<Recipe> {
+        let recipe = match fs::read_to_string(path) {
+            Ok(recipe) => recipe,
+            Err(e) => return fail_clierror!("Cannot read the recipe file {path}: {e}"),
+        };
+        let recipe: RecipeFile = match serde_yaml::from_str(&recipe) {
+            Ok(recipe) => recipe,
+            Err(e) => return fail_clierror!("Cannot parse the recipe file {path}: {e}"),
+        };
+        if recipe.steps.is_empty() {
+            return fail!("The recipe file has no steps.");
+        }
+
+        // the columns added by a step can be selected by the following ones
+        let mut headers = headers.clone();
+        let mut new_columns = Vec::new();
+        let mut single_step_ops: Vec<&str> = Vec::new();
+        let mut steps = Vec::with_capacity(recipe.steps.len());
+        for (i, step_def) in recipe.steps.into_iter().enumerate() {
+            let context = format!("recipe step {} (columns: {})", i + 1, step_def.columns);
+            let step = match RecipeStep::new(
+                step_def,
+                &headers,
+                no_headers,
+                flag_hmac_key,
+                &mut single_step_ops,
+            ) {
+                Ok(step) => step,
+                Err(e) => return fail_clierror!("{context}: {e}"),
+            };
+            if let Some(ref name) = step.new_column {
+                headers.push_field(name.as_bytes());
+                new_columns.push(name.clone());
+            }
+            steps.push(step);
+        }
+        Ok(Recipe { steps, new_columns })
+    }
+
+    // run the steps in order, each one on the results of the previous ones
+    fn apply(&self, record: &mut csv::StringRecord, prefer_dmy: bool, keep_zero_time: bool) {
+        for step in &self.steps {
+            for col_index in &step.columns {
+                let mut cell = record[*col_index].to_owned();
+                match step.action {
+                    RecipeAction::Operations(ref ops_vec) => {
+                        apply_operations(
+                            ops_vec,
+                            &mut cell,
+                            &step.comparand,
+                            &step.replacement,
+                            step.mask_keep,
+                        );
+                    }
+                    RecipeAction::DateFmt(ref formatstr) => {
+                        if !cell.is_empty() {
+                            if let Ok(date) = parse_with_preference(&cell, prefer_dmy) {
+                                cell = format_date(&date, formatstr, keep_zero_time);
+                            }
+                        }
+                    }
+                    RecipeAction::DateOps(ref dateops_vec) => {
+                        if !cell.is_empty() {
+                            if let Some(result) = apply_dateops(
+                                dateops_vec,
+                                &cell,
+                                record,
+                                prefer_dmy,
+                                &step.formatstr,
+                                keep_zero_time,
+                            ) {
+                                cell = result;
+                            }
+                        }
+                    }
+                    RecipeAction::EmptyReplace(ref replacement) => {
+                        if cell.trim().is_empty() {
+                            cell = replacement.clone();
+                        }
+                    }
+                }
+                if step.new_column.is_some() {
+                    record.push_field(&cell);
+                } else {
+                    *record = replace_column_value(record, *col_index, &cell);
+                }
+            }
+        }
+    }
+}
+
+impl RecipeStep {
+    fn new(
+        step_def: RecipeStepDef,
+        headers: &csv::ByteRecord,
+        no_headers: bool,
+        flag_hmac_key: &Option<String>,
+        single_step_ops: &mut Vec<&'static str>,
+    ) -> CliResult<RecipeStep> {
+        let columns = SelectColumns::parse(&step_def.columns)?.selection(headers, !no_headers)?;
+        if step_def.new_column.is_some() && columns.len() != 1 {
+            return fail!("new_column requires a single column.");
+        }
+
+        let action = match (
+            step_def.operations,
+            step_def.datefmt,
+            step_def.dateops,
+            step_def.emptyreplace,
+        ) {
+            (Some(operations), None, None, None) => {
+                let operations = operations.to_lowercase();
+                let operations: Vec<&str> = operations.split(',').map(str::trim).collect();
+                for name in SINGLE_STEP_OPERATIONS {
+                    if operations.iter().any(|op| op.starts_with(name)) {
+                        if single_step_ops.contains(&name) {
+                            return fail_clierror!(
+                                "The {name} operations can only be used in one recipe step."
+                            );
+                        }
+                        single_step_ops.push(name);
+                    }
+                }
+                RecipeAction::Operations(validate_operations(
+                    &operations,
+                    &step_def.comparand,
+                    &step_def.replacement,
+                    &step_def.new_column,
+                    flag_hmac_key,
+                )?)
+            }
+            (None, Some(formatstr), None, None) => RecipeAction::DateFmt(formatstr),
+            (None, None, Some(dateops), None) => RecipeAction::DateOps(validate_dateops(
+                &dateops,
+                &step_def.comparand,
+                headers,
+                no_headers,
+            )?),
+            (None, None, None, Some(replacement)) => RecipeAction::EmptyReplace(replacement),
+            _ => {
+                return fail!(
+                    "Each step must have exactly one of operations, datefmt, dateops or \
+                     emptyreplace."
+                )
+            }
+        };
+
+        Ok(RecipeStep {
+            columns: columns.to_vec(),
+            action,
+            comparand: step_def.comparand,
+            replacement: step_def.replacement,
+            formatstr: step_def.formatstr,
+            mask_keep: step_def.mask_keep,
+            new_column: step_def.new_column,
+        })
+    }
+}
+
 #[cached(
     key = "String",
     convert = r#"{ format!("{}", cell) }"#,
@@ -1101,45 +2300,236 @@ fn search_cached(cell: &str, formatstr: &str) -> Option<String> {
         if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&long) {
             let search_result = geocoder.search((lat, long));
             search_result.map(|locdetails| {
-                #[allow(clippy::match_same_arms)]
-                match formatstr {
-                    "%+" | "city-state" => format!(
-                        "{name}, {admin1}",
-                        name = locdetails.record.name,
-                        admin1 = locdetails.record.admin1,
-                    ),
-                    "city-country" => format!(
-                        "{name}, {cc}",
-                        name = locdetails.record.name,
-                        cc = locdetails.record.cc
-                    ),
-                    "city-state-country" | "city-admin1-country" => format!(
-                        "{name}, {admin1} {cc}",
-                        name = locdetails.record.name,
-                        admin1 = locdetails.record.admin1,
-                        cc = locdetails.record.cc
-                    ),
-                    "city" => locdetails.record.name.to_string(),
-                    "county" | "admin2" => locdetails.record.admin2.to_string(),
-                    "state" | "admin1" => locdetails.record.admin1.to_string(),
-                    "county-country" | "admin2-country" => format!(
-                        "{admin2}, {cc}",
-                        admin2 = locdetails.record.admin2,
-                        cc = locdetails.record.cc
-                    ),
-                    "county-state-country" | "admin2-admin1-country" => format!(
-                        "{admin2}, {admin1} {cc}",
-                        admin2 = locdetails.record.admin2,
-                        admin1 = locdetails.record.admin1,
-                        cc = locdetails.record.cc
-                    ),
-                    "country" => locdetails.record.cc.to_string(),
-                    #[allow(clippy::match_same_arms)]
-                    _ => locdetails.record.name.to_string(),
-                }
+                format_place(
+                    formatstr,
+                    &locdetails.record.name,
+                    &locdetails.record.admin1,
+                    &locdetails.record.admin2,
+                    &locdetails.record.cc,
+                )
             })
         } else {
             None
         }
     })
 }
+
+// format a geocoded place according to the geocode --formatstr
+fn format_place(formatstr: &str, name: &str, admin1: &str, admin2: &str, cc: &str) -> String {
+    #[allow(clippy::match_same_arms)]
+    match formatstr {
+        "%+" | "city-state" => format!("{name}, {admin1}"),
+        "city-country" => format!("{name}, {cc}"),
+        "city-state-country" | "city-admin1-country" => format!("{name}, {admin1} {cc}"),
+        "city" => name.to_string(),
+        "county" | "admin2" => admin2.to_string(),
+        "state" | "admin1" => admin1.to_string(),
+        "county-country" | "admin2-country" => format!("{admin2}, {cc}"),
+        "county-state-country" | "admin2-admin1-country" => format!("{admin2}, {admin1} {cc}"),
+        "country" => cc.to_string(),
+        #[allow(clippy::match_same_arms)]
+        _ => name.to_string(),
+    }
+}
+
+// the US state abbreviations, to disambiguate US places by their admin1 name
+const US_STATES: [(&str, &str); 51] = [
+    ("al", "Alabama"),
+    ("ak", "Alaska"),
+    ("az", "Arizona"),
+    ("ar", "Arkansas"),
+    ("ca", "California"),
+    ("co", "Colorado"),
+    ("ct", "Connecticut"),
+    ("de", "Delaware"),
+    ("dc", "Washington, D.C."),
+    ("fl", "Florida"),
+    ("ga", "Georgia"),
+    ("hi", "Hawaii"),
+    ("id", "Idaho"),
+    ("il", "Illinois"),
+    ("in", "Indiana"),
+    ("ia", "Iowa"),
+    ("ks", "Kansas"),
+    ("ky", "Kentucky"),
+    ("la", "Louisiana"),
+    ("me", "Maine"),
+    ("md", "Maryland"),
+    ("ma", "Massachusetts"),
+    ("mi", "Michigan"),
+    ("mn", "Minnesota"),
+    ("ms", "Mississippi"),
+    ("mo", "Missouri"),
+    ("mt", "Montana"),
+    ("ne", "Nebraska"),
+    ("nv", "Nevada"),
+    ("nh", "New Hampshire"),
+    ("nj", "New Jersey"),
+    ("nm", "New Mexico"),
+    ("ny", "New York"),
+    ("nc", "North Carolina"),
+    ("nd", "North Dakota"),
+    ("oh", "Ohio"),
+    ("ok", "Oklahoma"),
+    ("or", "Oregon"),
+    ("pa", "Pennsylvania"),
+    ("ri", "Rhode Island"),
+    ("sc", "South Carolina"),
+    ("sd", "South Dakota"),
+    ("tn", "Tennessee"),
+    ("tx", "Texas"),
+    ("ut", "Utah"),
+    ("vt", "Vermont"),
+    ("va", "Virginia"),
+    ("wa", "Washington"),
+    ("wv", "West Virginia"),
+    ("wi", "Wisconsin"),
+    ("wy", "Wyoming"),
+];
+
+// minimum Jaro-Winkler similarity of a fuzzy city name match
+const FUZZY_THRESHOLD: f64 = 0.9;
+
+#[derive(Deserialize)]
+struct Place {
+    lat:    f64,
+    lon:    f64,
+    name:   String,
+    admin1: String,
+    admin2: String,
+    cc:     String,
+}
+
+impl Place {
+    fn matches_qualifier(&self, qualifier: &str) -> bool {
+        qualifier == normalize_place_name(&self.cc)
+            || qualifier == normalize_place_name(&self.admin1)
+            || qualifier == normalize_place_name(&self.admin2)
+            || (self.cc == "US"
+                && US_STATES
+                    .iter()
+                    .any(|(abbr, state)| *abbr == qualifier && *state == self.admin1))
+    }
+}
+
+// the places of a Geonames cities file, indexed by their normalized names
+struct ForwardGeocoder {
+    places: Vec<Place>,
+    names:  AHashMap<String, Vec<usize>>,
+}
+
+impl ForwardGeocoder {
+    fn from_path(path: &str) -> CliResult<ForwardGeocoder> {
+        let mut rdr = match csv::Reader::from_path(path) {
+            Ok(rdr) => rdr,
+            Err(e) => return fail_clierror!("Cannot read the Geonames cities file {path}: {e}"),
+        };
+        let mut places = Vec::new();
+        let mut names: AHashMap<String, Vec<usize>> = AHashMap::new();
+        for place in rdr.deserialize() {
+            let place: Place = match place {
+                Ok(place) => place,
+                Err(e) => {
+                    return fail_clierror!("Invalid Geonames cities file {path}: {e}");
+                }
+            };
+            names
+                .entry(normalize_place_name(&place.name))
+                .or_default()
+                .push(places.len());
+            places.push(place);
+        }
+        Ok(ForwardGeocoder { places, names })
+    }
+
+    // the place of the place name. As the cities data has neither the population nor the
+    // capitals to rank the places with the same name, ambiguous place names aren't resolved.
+    fn search(&self, place_name: &str) -> Option<&Place> {
+        let mut parts = place_name.split(',').map(normalize_place_name);
+        let name = parts.next()?;
+        let qualifiers: Vec<String> = parts.filter(|part| !part.is_empty()).collect();
+
+        if let Some(candidates) = self.names.get(&name) {
+            return self.only_match(candidates, &qualifiers).flatten();
+        }
+
+        // rank all the similar names, so less similar names matching the qualifiers
+        // are used when the most similar ones don't
+        let mut similar_names: Vec<(f64, &Vec<usize>)> = self
+            .names
+            .iter()
+            .filter_map(|(candidate_name, candidates)| {
+                let similarity = jaro_winkler(&name, candidate_name);
+                (similarity >= FUZZY_THRESHOLD).then_some((similarity, candidates))
+            })
+            .collect();
+        similar_names.sort_unstable_by(|(a, _), (b, _)| b.total_cmp(a));
+        // the equally similar names are searched together, so a tie is ambiguous
+        let mut start = 0;
+        while start < similar_names.len() {
+            let similarity = similar_names[start].0;
+            let end = similar_names[start..]
+                .iter()
+                .position(|(other, _)| other.total_cmp(&similarity).is_ne())
+                .map_or(similar_names.len(), |len| start + len);
+            let candidates = similar_names[start..end]
+                .iter()
+                .flat_map(|(_, candidates)| candidates.iter());
+            if let Some(place) = self.only_match(candidates, &qualifiers) {
+                return place;
+            }
+            start = end;
+        }
+        None
+    }
+
+    // Some(Some(place)) when only one of the candidates matches the qualifiers, Some(None)
+    // when several of them do, and None when none of them does
+    fn only_match<'a>(
+        &'a self,
+        candidates: impl IntoIterator<Item = &'a usize>,
+        qualifiers: &[String],
+    ) -> Option<Option<&'a Place>> {
+        let mut matches = candidates
+            .into_iter()
+            .map(|i| &self.places[*i])
+            .filter(|place| {
+                qualifiers
+                    .iter()
+                    .all(|qualifier| place.matches_qualifier(qualifier))
+            });
+        let place = matches.next()?;
+        Some(matches.next().is_none().then_some(place))
+    }
+}
+
+// lowercase, with punctuation replaced by spaces & whitespace squeezed
+fn normalize_place_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{ format!("{}", cell) }"#,
+    option = true,
+    sync_writes = false
+)]
+fn forward_search_cached(cell: &str, formatstr: &str) -> Option<String> {
+    let geocoder = FORWARD_GEOCODER.get()?;
+    geocoder.search(cell).map(|place| match formatstr {
+        "%+" | "location" => format!("{}, {}", place.lat, place.lon),
+        "latitude" | "lat" => place.lat.to_string(),
+        "longitude" | "lon" => place.lon.to_string(),
+        _ => format_place(
+            formatstr,
+            &place.name,
+            &place.admin1,
+            &place.admin2,
+            &place.cc,
+        ),
+    })
+}
@@ -0,0 +1,571 @@
+static USAGE: &str = r#"
+Bins the values of numeric or date columns into buckets, adding a "<column>_bin" column
+with the bin label of each value. Or with --summary, outputs a histogram table of the bins:
+
+    field,bin,lower,upper,count,percentage
+
+The buckets can be:
+  * equal-width (the default) - --bins buckets of the same width between the min & the max
+    of the column. The min & max are taken from the stats cache if there is a valid one
+    (see `qsv stats --help`), so the input is not scanned an extra time for them.
+  * quantile (--quantile) - --bins buckets with about the same number of values each.
+  * custom (--edges) - buckets between the given ascending edges.
+
+Each bucket includes its lower edge & excludes its upper edge, except for the last bucket,
+which includes both, e.g. the edges 0,10,20 make the buckets [0, 10) & [10, 20].
+Empty values, values of another type and values outside custom edges get an empty label,
+and are not counted in the summary.
+
+The data type of each column is inferred like `qsv stats --infer-dates` does. Date & datetime
+columns are binned by their timestamps, and custom edges of a date column must be dates.
+
+As the input is read more than once, it cannot be stdin.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_bin.rs.
+
+Usage:
+    qsv bin [options] [--] <selection> <input>
+    qsv bin --help
+
+bin options:
+    -b, --bins <n>         The number of equal-width or quantile buckets.
+                           [default: 10]
+    -q, --quantile         Make buckets with about the same number of values,
+                           instead of buckets of the same width.
+    -e, --edges <list>     Make buckets between these comma-separated edges,
+                           e.g. "0,18,65,120". At least two ascending edges
+                           are required.
+    -s, --summary          Output a histogram table of the buckets, instead of
+                           adding the bin label columns.
+    --bars                 Output the histogram as a table with columns in
+                           alignment & Unicode bars, like the "table" command.
+                           Implies --summary.
+    --bar-width <n>        The width of the longest bar with --bars.
+                           [default: 40]
+    --prefer-dmy           Parse dates in dmy format. Otherwise, use mdy format.
+    -j, --jobs <arg>       The number of jobs to run in parallel when computing
+                           the stats of an indexed input.
+                           When not set, the number of jobs is set to the
+                           number of CPUs detected.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use qsv_dateparser::parse_with_preference;
+use serde::Deserialize;
+use tabwriter::TabWriter;
+
+use crate::{
+    cmd::stats::{round_num, StatsCache},
+    config::{Config, Delimiter},
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_selection:   SelectColumns,
+    arg_input:       Option<String>,
+    flag_bins:       usize,
+    flag_quantile:   bool,
+    flag_edges:      Option<String>,
+    flag_summary:    bool,
+    flag_bars:       bool,
+    flag_bar_width:  usize,
+    flag_prefer_dmy: bool,
+    flag_jobs:       Option<usize>,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+/// the type, min & max of a column, as `qsv stats` shows them
+struct ColumnStats {
+    typ: String,
+    min: String,
+    max: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BinKind {
+    Number,
+    Date,
+}
+
+impl BinKind {
+    pub fn from_type(typ: &str) -> Option<BinKind> {
+        match typ {
+            "Integer" | "Float" => Some(BinKind::Number),
+            "Date" | "DateTime" => Some(BinKind::Date),
+            _ => None,
+        }
+    }
+
+    /// parses a value to a number, or to a timestamp for dates
+    #[allow(clippy::cast_precision_loss)]
+    pub fn parse(self, value: &[u8], prefer_dmy: bool) -> Option<f64> {
+        let value = std::str::from_utf8(value).ok()?.trim();
+        if value.is_empty() {
+            return None;
+        }
+        match self {
+            BinKind::Number => value.parse::<f64>().ok().filter(|n| n.is_finite()),
+            BinKind::Date => parse_with_preference(value, prefer_dmy)
+                .ok()
+                .map(|dt| dt.timestamp() as f64),
+        }
+    }
+
+    /// parses a min or max of `qsv stats`, which shows dates like "2022-12-31 00:00:00 UTC"
+    #[allow(clippy::cast_precision_loss)]
+    pub fn parse_stat(self, stat: &str) -> Option<f64> {
+        match self {
+            BinKind::Number => stat.parse::<f64>().ok(),
+            BinKind::Date => {
+                NaiveDateTime::parse_from_str(stat.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M:%S%.f")
+                    .ok()
+                    .map(|dt| dt.timestamp() as f64)
+            }
+        }
+    }
+
+    fn format(self, edge: f64) -> String {
+        match self {
+            BinKind::Number => round_num(edge, 4),
+            BinKind::Date => match NaiveDateTime::from_timestamp_opt(edge.round() as i64, 0) {
+                Some(dt) if dt.time() == NaiveTime::MIN => dt.format("%Y-%m-%d").to_string(),
+                Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+                None => round_num(edge, 0),
+            },
+        }
+    }
+}
+
+/// the buckets of a column, between `edges`
+pub struct Binner {
+    pub kind:   BinKind,
+    edges:      Vec<f64>,
+    pub labels: Vec<String>,
+}
+
+impl Binner {
+    pub fn new(kind: BinKind, edges: Vec<f64>) -> Binner {
+        let nbins = edges.len() - 1;
+        let labels = edges
+            .windows(2)
+            .enumerate()
+            .map(|(i, edge)| {
+                let upper_bracket = if i == nbins - 1 { ']' } else { ')' };
+                format!(
+                    "[{}, {}{upper_bracket}",
+                    kind.format(edge[0]),
+                    kind.format(edge[1])
+                )
+            })
+            .collect();
+        Binner {
+            kind,
+            edges,
+            labels,
+        }
+    }
+
+    #[inline]
+    pub fn nbins(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// returns the bucket of `value`, if it's between the edges
+    #[inline]
+    pub fn bin(&self, value: f64) -> Option<usize> {
+        if value < self.edges[0] || value > self.edges[self.edges.len() - 1] {
+            return None;
+        }
+        let i = self.edges.partition_point(|edge| *edge <= value);
+        // the last bucket includes its upper edge
+        Some((i - 1).min(self.nbins() - 1))
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.arg_input.as_deref().map_or(true, |input| input == "-") {
+        return fail!("qsv bin cannot read from stdin, as it reads its input more than once.");
+    }
+    if args.flag_edges.is_none() && args.flag_bins == 0 {
+        return fail!("--bins must be greater than 0.");
+    }
+    if args.flag_edges.is_some() && args.flag_quantile {
+        return fail!("--edges cannot be used with --quantile.");
+    }
+
+    let rconfig = args.rconfig();
+    let prefer_dmy = args.flag_prefer_dmy || rconfig.get_dmy_preference();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    let column_stats = args.column_stats(&sel)?;
+    let mut kinds = Vec::with_capacity(sel.len());
+    for (col, stats) in sel.iter().zip(column_stats.iter()) {
+        match BinKind::from_type(&stats.typ) {
+            Some(kind) => kinds.push(kind),
+            None => {
+                return fail_clierror!(
+                    "Column {} is of type {}, only numeric & date columns can be binned.",
+                    column_name(&headers, *col, rconfig.no_headers),
+                    stats.typ
+                )
+            }
+        }
+    }
+
+    let binners = if let Some(ref edges) = args.flag_edges {
+        kinds
+            .iter()
+            .map(|kind| custom_edges(*kind, edges, prefer_dmy).map(|e| Binner::new(*kind, e)))
+            .collect::<CliResult<Vec<_>>>()?
+    } else if args.flag_quantile {
+        args.quantile_binners(&sel, &kinds, prefer_dmy)?
+    } else {
+        kinds
+            .iter()
+            .zip(column_stats.iter())
+            .map(
+                |(kind, stats)| match (kind.parse_stat(&stats.min), kind.parse_stat(&stats.max)) {
+                    (Some(min), Some(max)) => Ok(Binner::new(
+                        *kind,
+                        equal_width_edges(min, max, args.flag_bins),
+                    )),
+                    _ => fail_clierror!(
+                        "Cannot parse the min/max ({}/{}) of the column.",
+                        stats.min,
+                        stats.max
+                    ),
+                },
+            )
+            .collect::<CliResult<Vec<_>>>()?
+    };
+
+    if args.flag_summary || args.flag_bars {
+        let counts = args.bin_counts(&sel, &binners, prefer_dmy)?;
+        let names: Vec<String> = sel
+            .iter()
+            .map(|col| column_name(&headers, *col, rconfig.no_headers))
+            .collect();
+        if args.flag_bars {
+            args.write_bars(&names, &binners, &counts)
+        } else {
+            args.write_summary(&names, &binners, &counts)
+        }
+    } else {
+        args.write_bin_labels(&headers, &sel, &binners, prefer_dmy)
+    }
+}
+
+fn column_name(headers: &csv::ByteRecord, col: usize, no_headers: bool) -> String {
+    if no_headers {
+        (col + 1).to_string()
+    } else {
+        String::from_utf8_lossy(&headers[col]).to_string()
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn equal_width_edges(min: f64, max: f64, bins: usize) -> Vec<f64> {
+    // all the values are the same, so there's only one bucket
+    if max <= min {
+        return vec![min, max];
+    }
+    let width = (max - min) / bins as f64;
+    let mut edges: Vec<f64> = (0..bins).map(|i| (i as f64).mul_add(width, min)).collect();
+    edges.push(max);
+    edges
+}
+
+fn custom_edges(kind: BinKind, edges: &str, prefer_dmy: bool) -> CliResult<Vec<f64>> {
+    let mut parsed = Vec::new();
+    for edge in edges.split(',') {
+        let Some(e) = kind.parse(edge.as_bytes(), prefer_dmy) else {
+            return fail_clierror!("Invalid edge: {edge}");
+        };
+        parsed.push(e);
+    }
+    if parsed.len() < 2 || parsed.windows(2).any(|e| e[0] >= e[1]) {
+        return fail!("--edges must have at least two edges, in ascending order.");
+    }
+    Ok(parsed)
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.arg_selection.clone())
+    }
+
+    /// gets the type, min & max of the selected columns from the stats cache
+    /// if there is a valid one, otherwise computes them with `qsv stats`
+    fn column_stats(&self, sel: &Selection) -> CliResult<Vec<ColumnStats>> {
+        let rconfig = self.rconfig();
+        if let Some(cache) = StatsCache::load(&self.arg_input) {
+            if cache.same_dialect(rconfig.no_headers, rconfig.get_delimiter()) {
+                if let (Some(typ_idx), Some(min_idx), Some(max_idx)) = (
+                    cache.stat_index("type"),
+                    cache.stat_index("min"),
+                    cache.stat_index("max"),
+                ) {
+                    let cached: Option<Vec<ColumnStats>> = sel
+                        .iter()
+                        .map(|col| {
+                            let stats = cache.stats.get(*col)?;
+                            // the cache may not have inferred dates for the column
+                            BinKind::from_type(stats.get(typ_idx)?)?;
+                            Some(ColumnStats {
+                                typ: stats.get(typ_idx)?.clone(),
+                                min: stats.get(min_idx)?.clone(),
+                                max: stats.get(max_idx)?.clone(),
+                            })
+                        })
+                        .collect();
+                    if let Some(cached) = cached {
+                        log::info!("using stats cache");
+                        return Ok(cached);
+                    }
+                }
+            }
+        }
+
+        let stats_args = crate::cmd::stats::Args {
+            arg_input:            self.arg_input.clone(),
+            flag_select:          self.arg_selection.clone(),
+            flag_groupby:         None,
+            flag_everything:      false,
+            flag_mode:            false,
+            flag_cardinality:     false,
+            flag_median:          false,
+            flag_quartiles:       false,
+            flag_nulls:           false,
+            flag_round:           4,
+            flag_infer_dates:     true,
+            flag_dates_whitelist: "all".to_string(),
+            flag_prefer_dmy:      self.flag_prefer_dmy,
+            flag_cache:           false,
+            flag_jobs:            Some(util::njobs(self.flag_jobs)),
+            flag_output:          None,
+            flag_no_headers:      self.flag_no_headers,
+            flag_delimiter:       self.flag_delimiter,
+        };
+        let (_, stats) = match stats_args.rconfig().indexed()? {
+            Some(idx) if util::njobs(self.flag_jobs) > 1 => {
+                stats_args.parallel_stats(&stats_args.flag_dates_whitelist, &idx)
+            }
+            _ => stats_args.sequential_stats(&stats_args.flag_dates_whitelist),
+        }?;
+
+        let stat_headers = stats_args.stat_headers();
+        // the stats records don't have the leading "field" column
+        let stat_index = |stat: &str| stat_headers.iter().position(|h| h == stat).unwrap() - 1;
+        let (typ_idx, min_idx, max_idx) =
+            (stat_index("type"), stat_index("min"), stat_index("max"));
+        Ok(stats_args
+            .stats_to_records(stats)
+            .iter()
+            .map(|record| ColumnStats {
+                typ: record[typ_idx].to_string(),
+                min: record[min_idx].to_string(),
+                max: record[max_idx].to_string(),
+            })
+            .collect())
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    /// collects the sorted values of each selected column to compute their quantiles
+    fn quantile_binners(
+        &self,
+        sel: &Selection,
+        kinds: &[BinKind],
+        prefer_dmy: bool,
+    ) -> CliResult<Vec<Binner>> {
+        let mut values: Vec<Vec<f64>> = vec![Vec::new(); sel.len()];
+        let mut rdr = self.rconfig().reader()?;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (i, field) in sel.select(&record).enumerate() {
+                if let Some(value) = kinds[i].parse(field, prefer_dmy) {
+                    values[i].push(value);
+                }
+            }
+        }
+
+        let mut binners = Vec::with_capacity(sel.len());
+        for (kind, mut values) in kinds.iter().zip(values.into_iter()) {
+            if values.is_empty() {
+                return fail!("Cannot compute the quantiles of a column without values.");
+            }
+            values.sort_unstable_by(f64::total_cmp);
+            let last = values.len() - 1;
+            let mut edges: Vec<f64> = (0..=self.flag_bins)
+                .map(|i| {
+                    let rank = (last * i) as f64 / self.flag_bins as f64;
+                    values[rank.round() as usize]
+                })
+                .collect();
+            // skewed data can have the same value at several quantiles
+            edges.dedup();
+            if edges.len() == 1 {
+                edges.push(edges[0]);
+            }
+            binners.push(Binner::new(*kind, edges));
+        }
+        Ok(binners)
+    }
+
+    fn bin_counts(
+        &self,
+        sel: &Selection,
+        binners: &[Binner],
+        prefer_dmy: bool,
+    ) -> CliResult<Vec<Vec<u64>>> {
+        let mut counts: Vec<Vec<u64>> = binners.iter().map(|b| vec![0; b.nbins()]).collect();
+        let mut rdr = self.rconfig().reader()?;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (i, field) in sel.select(&record).enumerate() {
+                let binner = &binners[i];
+                if let Some(bin) = binner
+                    .kind
+                    .parse(field, prefer_dmy)
+                    .and_then(|v| binner.bin(v))
+                {
+                    counts[i][bin] += 1;
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    fn write_summary(
+        &self,
+        names: &[String],
+        binners: &[Binner],
+        counts: &[Vec<u64>],
+    ) -> CliResult<()> {
+        let mut wtr = Config::new(&self.flag_output).writer()?;
+        wtr.write_record(["field", "bin", "lower", "upper", "count", "percentage"])?;
+        for ((name, binner), counts) in names.iter().zip(binners.iter()).zip(counts.iter()) {
+            let total: u64 = counts.iter().sum();
+            for (bin, count) in counts.iter().enumerate() {
+                wtr.write_record([
+                    name,
+                    &binner.labels[bin],
+                    &binner.kind.format(binner.edges[bin]),
+                    &binner.kind.format(binner.edges[bin + 1]),
+                    &count.to_string(),
+                    &percentage(*count, total),
+                ])?;
+            }
+        }
+        Ok(wtr.flush()?)
+    }
+
+    fn write_bars(
+        &self,
+        names: &[String],
+        binners: &[Binner],
+        counts: &[Vec<u64>],
+    ) -> CliResult<()> {
+        let wconfig = Config::new(&self.flag_output).delimiter(Some(Delimiter(b'\t')));
+        let tw = TabWriter::new(wconfig.io_writer()?).padding(2);
+        let mut wtr = wconfig.from_writer(tw);
+        for ((name, binner), counts) in names.iter().zip(binners.iter()).zip(counts.iter()) {
+            let total: u64 = counts.iter().sum();
+            let max_count = counts.iter().max().copied().unwrap_or_default();
+            for (bin, count) in counts.iter().enumerate() {
+                wtr.write_record([
+                    name,
+                    &binner.labels[bin],
+                    &count.to_string(),
+                    &format!("{}%", percentage(*count, total)),
+                    &bar(*count, max_count, self.flag_bar_width),
+                ])?;
+            }
+        }
+        Ok(wtr.flush()?)
+    }
+
+    fn write_bin_labels(
+        &self,
+        headers: &csv::ByteRecord,
+        sel: &Selection,
+        binners: &[Binner],
+        prefer_dmy: bool,
+    ) -> CliResult<()> {
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
+        let mut wtr = Config::new(&self.flag_output).writer()?;
+
+        if !rconfig.no_headers {
+            let mut headers = headers.clone();
+            for col in sel.iter() {
+                let name = format!("{}_bin", column_name(&headers, *col, false));
+                headers.push_field(name.as_bytes());
+            }
+            wtr.write_byte_record(&headers)?;
+        }
+
+        let mut record = csv::ByteRecord::new();
+        let mut labels: Vec<&str> = Vec::with_capacity(sel.len());
+        while rdr.read_byte_record(&mut record)? {
+            labels.clear();
+            labels.extend(
+                sel.select(&record)
+                    .zip(binners.iter())
+                    .map(|(field, binner)| {
+                        binner
+                            .kind
+                            .parse(field, prefer_dmy)
+                            .and_then(|v| binner.bin(v))
+                            .map_or("", |bin| binner.labels[bin].as_str())
+                    }),
+            );
+            for label in &labels {
+                record.push_field(label.as_bytes());
+            }
+            wtr.write_byte_record(&record)?;
+        }
+        Ok(wtr.flush()?)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn percentage(count: u64, total: u64) -> String {
+    if total == 0 {
+        return "0".to_string();
+    }
+    round_num(count as f64 * 100.0 / total as f64, 4)
+}
+
+#[allow(clippy::cast_precision_loss)]
+/// renders a horizontal bar with eighth-block precision, the longest being `width` long
+pub fn bar(count: u64, max_count: u64, width: usize) -> String {
+    const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    if max_count == 0 {
+        return String::new();
+    }
+    let eighths = (count as f64 / max_count as f64 * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(PARTIAL_BLOCKS[eighths % 8]);
+    }
+    bar
+}
@@ -0,0 +1,679 @@
+static USAGE: &str = r#"
+Checks a CSV against dataset-level data quality rules declared in a YAML file, and reports
+whether each rule passed or failed. Unlike the JSON Schema of `validate`, which checks one
+record at a time, the rules can check expectations about the whole dataset.
+
+The rules file has a list of rules, each with a type, an optional name & the options of its
+type. For example:
+
+    rules:
+      - type: row_count
+        min: 1
+        max: 1000000
+      - name: unique ids
+        type: unique
+        columns: [id]
+      - type: null_rate
+        column: email
+        max: 0.05
+      - type: values_in
+        column: status
+        values: [open, closed, pending]
+      - type: monotonic
+        column: created
+        direction: increasing
+      - type: compare
+        left: end
+        op: ">="
+        right: start
+      - type: regex
+        column: zip
+        pattern: '^\d{5}(-\d{4})?$'
+
+The rule types are:
+  * row_count - the number of rows is between min & max. Both are optional.
+  * unique - the values of the columns, or their combinations when there are several
+    columns, are unique.
+  * null_rate - the share of empty values of the column is at most max (0 to 1).
+  * values_in - the values of the column are in the values list. Set ignore_case to true
+    to compare them case-insensitively.
+  * monotonic - the values of the column are increasing (the default) or decreasing,
+    per direction. Set strict to true to disallow consecutive equal values.
+  * compare - the value of the left column compares to the value of the right column, or
+    to a constant value instead of right, with the op operator (==, !=, <, <=, > or >=).
+  * regex - the values of the column match the regex pattern. Use ^ & $ to match the
+    whole value. See https://docs.rs/regex/latest/regex/#syntax for the syntax.
+
+Columns can be selected by name, or by 1-based index (see 'qsv select --help').
+
+Empty values are skipped by all the rules, except null_rate. The monotonic & compare rules
+compare values as numbers if both are numbers, as dates if both are dates, and as strings
+otherwise.
+
+The report has a row per rule:
+
+    rule,type,columns,status,checked,failed,details
+
+where checked is the number of values (or rows) the rule checked, failed the number of them
+that failed it, & details has the first rows that failed it.
+
+Returns exit code 0 when all the rules passed, and exit code 1 otherwise.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_check.rs.
+
+Usage:
+    qsv check [options] <rules> [<input>]
+    qsv check --help
+
+check options:
+    --json                 Output the report as JSON.
+    --pretty-json          Same as --json, but pretty printed.
+    --prefer-dmy           Parse dates in dmy format. Otherwise, use mdy format.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write the report to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers, and columns can only be selected by index.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{cmp::Ordering, fs, io::Write};
+
+use ahash::AHashSet;
+use qsv_dateparser::parse_with_preference;
+use regex::bytes::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_rules:        String,
+    arg_input:        Option<String>,
+    flag_json:        bool,
+    flag_pretty_json: bool,
+    flag_prefer_dmy:  bool,
+    flag_output:      Option<String>,
+    flag_no_headers:  bool,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+/// the number of failed rows listed in the details of a rule
+const MAX_FAILED_ROWS: usize = 5;
+
+#[derive(Deserialize)]
+struct RulesFile {
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+struct RuleSpec {
+    name: Option<String>,
+    #[serde(flatten)]
+    rule: Rule,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Rule {
+    RowCount {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    Unique {
+        columns: Vec<String>,
+    },
+    NullRate {
+        column: String,
+        max:    f64,
+    },
+    ValuesIn {
+        column:      String,
+        values:      Vec<serde_yaml::Value>,
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    Monotonic {
+        column:    String,
+        #[serde(default)]
+        direction: Direction,
+        #[serde(default)]
+        strict:    bool,
+    },
+    Compare {
+        left:  String,
+        op:    Op,
+        right: Option<String>,
+        value: Option<serde_yaml::Value>,
+    },
+    Regex {
+        column:  String,
+        pattern: String,
+    },
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Direction {
+    #[default]
+    Increasing,
+    Decreasing,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+enum Op {
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+}
+
+impl Op {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+
+    const fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+}
+
+enum Operand {
+    Column(usize),
+    Value(Vec<u8>),
+}
+
+/// a rule, with its columns resolved to their indices & its state while reading the rows
+enum Checker {
+    RowCount {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    Unique {
+        cols: Vec<usize>,
+        seen: AHashSet<Vec<Vec<u8>>>,
+    },
+    NullRate {
+        col: usize,
+        max: f64,
+    },
+    ValuesIn {
+        col:         usize,
+        values:      AHashSet<Vec<u8>>,
+        ignore_case: bool,
+    },
+    Monotonic {
+        col:       usize,
+        direction: Direction,
+        strict:    bool,
+        prev:      Option<Vec<u8>>,
+    },
+    Compare {
+        left:  usize,
+        op:    Op,
+        right: Operand,
+    },
+    Regex {
+        col:   usize,
+        regex: Regex,
+    },
+}
+
+struct Check {
+    name:        String,
+    typ:         &'static str,
+    columns:     String,
+    checker:     Checker,
+    checked:     u64,
+    failed:      u64,
+    failed_rows: Vec<u64>,
+}
+
+struct Outcome {
+    passed:  bool,
+    details: String,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rules = match fs::read_to_string(&args.arg_rules) {
+        Ok(rules) => rules,
+        Err(e) => return fail_clierror!("Cannot read the rules file {}: {e}", args.arg_rules),
+    };
+    let rules: RulesFile = match serde_yaml::from_str(&rules) {
+        Ok(rules) => rules,
+        Err(e) => return fail_clierror!("Cannot parse the rules file {}: {e}", args.arg_rules),
+    };
+    if rules.rules.is_empty() {
+        return fail!("The rules file has no rules.");
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let prefer_dmy = args.flag_prefer_dmy || rconfig.get_dmy_preference();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut checks = rules
+        .rules
+        .into_iter()
+        .map(|spec| Check::new(spec, &headers, args.flag_no_headers))
+        .collect::<CliResult<Vec<_>>>()?;
+
+    let mut row_count = 0_u64;
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        row_count += 1;
+        for check in &mut checks {
+            check.check_row(row_count, &record, prefer_dmy);
+        }
+    }
+
+    let outcomes: Vec<Outcome> = checks
+        .iter_mut()
+        .map(|check| check.finish(row_count))
+        .collect();
+
+    if args.flag_json || args.flag_pretty_json {
+        let report: Vec<serde_json::Value> = checks
+            .iter()
+            .zip(outcomes.iter())
+            .map(|(check, outcome)| {
+                json!({
+                    "rule": check.name,
+                    "type": check.typ,
+                    "columns": check.columns,
+                    "status": if outcome.passed { "pass" } else { "fail" },
+                    "checked": check.checked,
+                    "failed": check.failed,
+                    "failed_rows": check.failed_rows,
+                    "details": outcome.details,
+                })
+            })
+            .collect();
+        let json = if args.flag_pretty_json {
+            serde_json::to_string_pretty(&report)
+        } else {
+            serde_json::to_string(&report)
+        };
+        let Ok(json) = json else {
+            return fail!("Cannot serialize the check report to JSON.");
+        };
+        let mut wtr = Config::new(&args.flag_output).io_writer()?;
+        writeln!(wtr, "{json}")?;
+        wtr.flush()?;
+    } else {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        wtr.write_record([
+            "rule", "type", "columns", "status", "checked", "failed", "details",
+        ])?;
+        for (check, outcome) in checks.iter().zip(outcomes.iter()) {
+            wtr.write_record([
+                check.name.as_str(),
+                check.typ,
+                check.columns.as_str(),
+                if outcome.passed { "pass" } else { "fail" },
+                &check.checked.to_string(),
+                &check.failed.to_string(),
+                outcome.details.as_str(),
+            ])?;
+        }
+        wtr.flush()?;
+    }
+
+    let failed = outcomes.iter().filter(|outcome| !outcome.passed).count();
+    if failed > 0 {
+        return fail_clierror!("{failed} of {} rules failed.", outcomes.len());
+    }
+    Ok(())
+}
+
+impl Check {
+    fn new(spec: RuleSpec, headers: &csv::ByteRecord, no_headers: bool) -> CliResult<Check> {
+        let column = |name: &str| column_index(name, headers, no_headers);
+        let (typ, columns, checker) = match spec.rule {
+            Rule::RowCount { min, max } => {
+                ("row_count", String::new(), Checker::RowCount { min, max })
+            }
+            Rule::Unique { columns } => {
+                if columns.is_empty() {
+                    return fail!("The unique rule needs at least one column.");
+                }
+                let cols = columns
+                    .iter()
+                    .map(|name| column(name))
+                    .collect::<CliResult<Vec<_>>>()?;
+                (
+                    "unique",
+                    columns.join(","),
+                    Checker::Unique {
+                        cols,
+                        seen: AHashSet::new(),
+                    },
+                )
+            }
+            Rule::NullRate { column: name, max } => {
+                if !(0.0..=1.0).contains(&max) {
+                    return fail_clierror!("The max null rate of {name} must be between 0 & 1.");
+                }
+                (
+                    "null_rate",
+                    name.clone(),
+                    Checker::NullRate {
+                        col: column(&name)?,
+                        max,
+                    },
+                )
+            }
+            Rule::ValuesIn {
+                column: name,
+                values,
+                ignore_case,
+            } => {
+                let values = values
+                    .iter()
+                    .map(|value| {
+                        let value = yaml_scalar(value)?;
+                        Ok(if ignore_case {
+                            value.to_lowercase().into_bytes()
+                        } else {
+                            value.into_bytes()
+                        })
+                    })
+                    .collect::<CliResult<AHashSet<_>>>()?;
+                (
+                    "values_in",
+                    name.clone(),
+                    Checker::ValuesIn {
+                        col: column(&name)?,
+                        values,
+                        ignore_case,
+                    },
+                )
+            }
+            Rule::Monotonic {
+                column: name,
+                direction,
+                strict,
+            } => (
+                "monotonic",
+                name.clone(),
+                Checker::Monotonic {
+                    col: column(&name)?,
+                    direction,
+                    strict,
+                    prev: None,
+                },
+            ),
+            Rule::Compare {
+                left,
+                op,
+                right,
+                value,
+            } => {
+                let (right, right_name) = match (right, value) {
+                    (Some(right), None) => (Operand::Column(column(&right)?), right),
+                    (None, Some(value)) => {
+                        let value = yaml_scalar(&value)?;
+                        (Operand::Value(value.clone().into_bytes()), value)
+                    }
+                    _ => {
+                        return fail_clierror!(
+                            "The compare rule of {left} needs either a right column or a value."
+                        )
+                    }
+                };
+                let columns = format!("{left} {} {right_name}", op.symbol());
+                (
+                    "compare",
+                    columns,
+                    Checker::Compare {
+                        left: column(&left)?,
+                        op,
+                        right,
+                    },
+                )
+            }
+            Rule::Regex {
+                column: name,
+                pattern,
+            } => {
+                let regex = match Regex::new(&pattern) {
+                    Ok(regex) => regex,
+                    Err(e) => return fail_clierror!("Invalid regex {pattern}: {e}"),
+                };
+                (
+                    "regex",
+                    name.clone(),
+                    Checker::Regex {
+                        col: column(&name)?,
+                        regex,
+                    },
+                )
+            }
+        };
+
+        let name = spec.name.unwrap_or_else(|| {
+            if columns.is_empty() {
+                typ.to_string()
+            } else {
+                format!("{typ}({columns})")
+            }
+        });
+        Ok(Check {
+            name,
+            typ,
+            columns,
+            checker,
+            checked: 0,
+            failed: 0,
+            failed_rows: Vec::new(),
+        })
+    }
+
+    fn check_row(&mut self, row_number: u64, record: &csv::ByteRecord, prefer_dmy: bool) {
+        let field = |col: usize| record.get(col).unwrap_or_default();
+        let failed = match self.checker {
+            Checker::RowCount { .. } => return,
+            Checker::Unique {
+                ref cols,
+                ref mut seen,
+            } => {
+                let key: Vec<Vec<u8>> = cols.iter().map(|&col| field(col).to_vec()).collect();
+                if key.iter().all(Vec::is_empty) {
+                    return;
+                }
+                !seen.insert(key)
+            }
+            Checker::NullRate { col, .. } => field(col).is_empty(),
+            Checker::ValuesIn {
+                col,
+                ref values,
+                ignore_case,
+            } => {
+                let value = field(col);
+                if value.is_empty() {
+                    return;
+                }
+                if ignore_case {
+                    !values.contains(&String::from_utf8_lossy(value).to_lowercase().into_bytes())
+                } else {
+                    !values.contains(value)
+                }
+            }
+            Checker::Monotonic {
+                col,
+                direction,
+                strict,
+                ref mut prev,
+            } => {
+                let value = field(col);
+                if value.is_empty() {
+                    return;
+                }
+                let failed = prev.as_ref().map_or(false, |prev| {
+                    match (compare_values(prev, value, prefer_dmy), direction) {
+                        (Ordering::Equal, _) => strict,
+                        (Ordering::Greater, Direction::Increasing)
+                        | (Ordering::Less, Direction::Decreasing) => true,
+                        _ => false,
+                    }
+                });
+                *prev = Some(value.to_vec());
+                failed
+            }
+            Checker::Compare {
+                left,
+                op,
+                ref right,
+            } => {
+                let left = field(left);
+                let right = match right {
+                    Operand::Column(col) => field(*col),
+                    Operand::Value(value) => value,
+                };
+                if left.is_empty() || right.is_empty() {
+                    return;
+                }
+                !op.holds(compare_values(left, right, prefer_dmy))
+            }
+            Checker::Regex { col, ref regex } => {
+                let value = field(col);
+                if value.is_empty() {
+                    return;
+                }
+                !regex.is_match(value)
+            }
+        };
+
+        self.checked += 1;
+        if failed {
+            self.failed += 1;
+            // the nulls of null_rate are not failures of their own rows
+            if self.failed_rows.len() < MAX_FAILED_ROWS
+                && !matches!(self.checker, Checker::NullRate { .. })
+            {
+                self.failed_rows.push(row_number);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(&mut self, row_count: u64) -> Outcome {
+        match self.checker {
+            Checker::RowCount { min, max } => {
+                self.checked = row_count;
+                let passed = min.map_or(true, |min| row_count >= min)
+                    && max.map_or(true, |max| row_count <= max);
+                if !passed {
+                    self.failed = 1;
+                }
+                let expected = match (min, max) {
+                    (Some(min), Some(max)) => format!("between {min} & {max}"),
+                    (Some(min), None) => format!("at least {min}"),
+                    (None, Some(max)) => format!("at most {max}"),
+                    (None, None) => "any number of".to_string(),
+                };
+                Outcome {
+                    passed,
+                    details: format!("{row_count} rows, expected {expected} rows"),
+                }
+            }
+            Checker::NullRate { max, .. } => {
+                let rate = if self.checked == 0 {
+                    0.0
+                } else {
+                    self.failed as f64 / self.checked as f64
+                };
+                Outcome {
+                    passed:  rate <= max,
+                    details: format!(
+                        "null rate {}, expected at most {max}",
+                        crate::cmd::stats::round_num(rate, 4)
+                    ),
+                }
+            }
+            _ if self.failed == 0 => Outcome {
+                passed:  true,
+                details: String::new(),
+            },
+            _ => {
+                let rows: Vec<String> = self.failed_rows.iter().map(u64::to_string).collect();
+                Outcome {
+                    passed:  false,
+                    details: format!("first failed rows: {}", rows.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+/// the index of a single column, selected by its exact name, or by its 1-based index
+/// when the name is a number. Unlike select, names like start-date aren't ranges.
+fn column_index(name: &str, headers: &csv::ByteRecord, no_headers: bool) -> CliResult<usize> {
+    if !no_headers {
+        if let Some(i) = headers.iter().position(|header| header == name.as_bytes()) {
+            return Ok(i);
+        }
+    }
+    match name.parse::<usize>() {
+        Ok(i) if i >= 1 && i <= headers.len() => Ok(i - 1),
+        _ => fail_clierror!("The rule column {name} is neither a column name nor a 1-based index."),
+    }
+}
+
+/// the string of a YAML scalar, e.g. of the values of values_in, which may be numbers
+fn yaml_scalar(value: &serde_yaml::Value) -> CliResult<String> {
+    match value {
+        serde_yaml::Value::String(s) => Ok(s.clone()),
+        serde_yaml::Value::Number(n) => Ok(n.to_string()),
+        serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+        _ => fail_clierror!("Rule values must be strings, numbers or booleans, not {value:?}."),
+    }
+}
+
+/// compares two values as numbers if both are numbers, as dates if both are dates,
+/// and as strings otherwise
+fn compare_values(a: &[u8], b: &[u8], prefer_dmy: bool) -> Ordering {
+    let (a_str, b_str) = (String::from_utf8_lossy(a), String::from_utf8_lossy(b));
+    if let (Ok(a), Ok(b)) = (a_str.parse::<f64>(), b_str.parse::<f64>()) {
+        return a.total_cmp(&b);
+    }
+    if let (Ok(a), Ok(b)) = (
+        parse_with_preference(&a_str, prefer_dmy),
+        parse_with_preference(&b_str, prefer_dmy),
+    ) {
+        return a.cmp(&b);
+    }
+    a.cmp(b)
+}
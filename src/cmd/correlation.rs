@@ -0,0 +1,457 @@
+static USAGE: &str = r#"
+Computes the pairwise Pearson correlation & covariance of numeric columns, and optionally
+their Spearman rank correlation.
+
+By default, the output is in long format, with a row for each pair of columns:
+
+    x,y,n,pearson,covariance
+
+where n is the number of rows where both columns have a value. With --spearman, there's
+an additional spearman column. With --matrix, the output is a square matrix of one of the
+statistics instead, e.g. with --matrix pearson:
+
+    field,a,b,c
+    a,1,0.5,-0.25
+    ...
+
+The covariance is the sample covariance. The correlation of columns with a variance of 0
+is undefined, and left empty.
+
+The data types of the columns are inferred like `qsv stats` does, and only Integer & Float
+columns are correlated. The other selected columns are ignored.
+
+The Pearson correlation & covariance are computed in one pass over the data. The Spearman
+correlation needs an additional pass to rank the values, and memory proportional to the
+number of values. Values are ranked among all the values of their column, so with NULLs,
+the ranks of a pair of columns can differ slightly from ranking only their complete rows.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_correlation.rs.
+
+Usage:
+    qsv correlation [options] [<input>]
+    qsv correlation --help
+
+correlation options:
+    -s, --select <arg>     Select a subset of columns to correlate.
+                           See 'qsv select --help' for the format details.
+                           This is provided here because piping 'qsv select'
+                           into 'qsv correlation' will disable the use of indexing.
+    --spearman             Also compute the Spearman rank correlation.
+    --matrix <stat>        Output a matrix of this statistic, instead of the long
+                           format. One of "pearson", "spearman" or "covariance".
+    --round <decimals>     Round the statistics to <decimals> places.
+                           Rounding is done following Midpoint Nearest Even
+                           (aka "Bankers Rounding") rule.
+                           [default: 4]
+    -j, --jobs <arg>       The number of jobs to run in parallel.
+                           This works only when the given CSV has an index.
+                           Note that a file handle is opened for each job.
+                           When not set, the number of jobs is set to the
+                           number of CPUs detected.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will NOT be interpreted
+                           as column names. i.e., They will be included
+                           in the correlation, and the columns will be
+                           named with 1-based indices.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{fs, str, sync::Arc};
+
+use serde::Deserialize;
+use stats::{merge_all, Commute};
+use threadpool::ThreadPool;
+
+use crate::{
+    cmd::stats::{
+        round_num,
+        FieldType::{self, TFloat, TInteger, TNull},
+    },
+    config::{Config, Delimiter},
+    index::Indexed,
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Clone, Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_select:     SelectColumns,
+    flag_spearman:   bool,
+    flag_matrix:     Option<MatrixStat>,
+    flag_round:      u8,
+    flag_jobs:       Option<usize>,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum MatrixStat {
+    Pearson,
+    Spearman,
+    Covariance,
+}
+
+/// the sorted values of each column, to rank values for the Spearman correlation
+type Ranks = Arc<Vec<Vec<f64>>>;
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let spearman = args.flag_spearman || args.flag_matrix == Some(MatrixStat::Spearman);
+    if spearman && args.arg_input.as_deref().map_or(true, |input| input == "-") {
+        return fail!(
+            "The Spearman correlation cannot be computed from stdin, as it needs two passes."
+        );
+    }
+
+    let rconfig = args.rconfig();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let names: Vec<String> = sel
+        .iter()
+        .map(|&col| {
+            if rconfig.no_headers {
+                (col + 1).to_string()
+            } else {
+                String::from_utf8_lossy(&headers[col]).to_string()
+            }
+        })
+        .collect();
+
+    let ranks = if spearman {
+        Some(args.ranks(&sel)?)
+    } else {
+        None
+    };
+    let corr = match rconfig.indexed()? {
+        Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => {
+            args.parallel_correlation(&sel, ranks, idx)
+        }
+        _ => args.sequential_correlation(&sel, ranks),
+    }?;
+
+    let numeric: Vec<usize> = (0..sel.len())
+        .filter(|&i| matches!(corr.types[i], TInteger | TFloat))
+        .collect();
+    if numeric.len() < 2 {
+        return fail!("At least two numeric columns are required to compute correlations.");
+    }
+    for (i, name) in names.iter().enumerate() {
+        if !numeric.contains(&i) {
+            log::info!("ignoring non-numeric column {name}");
+        }
+    }
+
+    let round =
+        |stat: Option<f64>| stat.map_or_else(String::new, |s| round_num(s, args.flag_round));
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if let Some(stat) = args.flag_matrix {
+        let mut header = vec!["field".to_string()];
+        header.extend(numeric.iter().map(|&i| names[i].clone()));
+        wtr.write_record(&header)?;
+        for &i in &numeric {
+            let mut row = vec![names[i].clone()];
+            row.extend(numeric.iter().map(|&j| {
+                round(match stat {
+                    MatrixStat::Pearson => corr.pair(i, j).pearson(),
+                    MatrixStat::Spearman => corr.ranked_pair(i, j).pearson(),
+                    MatrixStat::Covariance => corr.pair(i, j).covariance(),
+                })
+            }));
+            wtr.write_record(&row)?;
+        }
+    } else {
+        let mut header = vec!["x", "y", "n", "pearson", "covariance"];
+        if spearman {
+            header.push("spearman");
+        }
+        wtr.write_record(&header)?;
+        for (a, &i) in numeric.iter().enumerate() {
+            for &j in &numeric[a + 1..] {
+                let pair = corr.pair(i, j);
+                let mut row = vec![
+                    names[i].clone(),
+                    names[j].clone(),
+                    pair.n.to_string(),
+                    round(pair.pearson()),
+                    round(pair.covariance()),
+                ];
+                if spearman {
+                    row.push(round(corr.ranked_pair(i, j).pearson()));
+                }
+                wtr.write_record(&row)?;
+            }
+        }
+    }
+    Ok(wtr.flush()?)
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.flag_select.clone())
+    }
+
+    /// collects & sorts the values of each selected column
+    fn ranks(&self, sel: &Selection) -> CliResult<Ranks> {
+        let mut values: Vec<Vec<f64>> = vec![Vec::new(); sel.len()];
+        let mut rdr = self.rconfig().reader()?;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (i, field) in sel.select(&record).enumerate() {
+                if let (_, Some(value)) = numeric_value(field) {
+                    values[i].push(value);
+                }
+            }
+        }
+        for column in &mut values {
+            column.sort_unstable_by(f64::total_cmp);
+        }
+        Ok(Arc::new(values))
+    }
+
+    fn sequential_correlation(
+        &self,
+        sel: &Selection,
+        ranks: Option<Ranks>,
+    ) -> CliResult<Correlation> {
+        let mut rdr = self.rconfig().reader()?;
+        self.compute(sel, ranks.as_deref(), rdr.byte_records())
+    }
+
+    fn parallel_correlation(
+        &self,
+        sel: &Selection,
+        ranks: Option<Ranks>,
+        idx: &mut Indexed<fs::File, fs::File>,
+    ) -> CliResult<Correlation> {
+        if idx.count() == 0 {
+            return self.sequential_correlation(sel, ranks);
+        }
+
+        let chunk_size = util::chunk_size(idx.count() as usize, util::njobs(self.flag_jobs));
+        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+
+        let pool = ThreadPool::new(util::njobs(self.flag_jobs));
+        let (send, recv) = channel::bounded(0);
+        for i in 0..nchunks {
+            let (send, args, sel, ranks) = (send.clone(), self.clone(), sel.clone(), ranks.clone());
+            pool.execute(move || {
+                let mut idx = args.rconfig().indexed().unwrap().unwrap();
+                idx.seek((i * chunk_size) as u64).unwrap();
+                let it = idx.byte_records().take(chunk_size);
+                send.send(args.compute(&sel, ranks.as_deref(), it).unwrap())
+                    .unwrap();
+            });
+        }
+        drop(send);
+        Ok(merge_all(recv.iter()).unwrap())
+    }
+
+    fn compute<I>(
+        &self,
+        sel: &Selection,
+        ranks: Option<&Vec<Vec<f64>>>,
+        it: I,
+    ) -> CliResult<Correlation>
+    where
+        I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+    {
+        let mut corr = Correlation::new(sel.len(), ranks.is_some());
+        let mut values: Vec<Option<f64>> = Vec::with_capacity(sel.len());
+        let mut ranked: Vec<Option<f64>> = Vec::with_capacity(sel.len());
+        for row in it {
+            let row = row?;
+            values.clear();
+            for (i, field) in sel.select(&row).enumerate() {
+                let (typ, value) = numeric_value(field);
+                corr.types[i].merge(typ);
+                values.push(value);
+            }
+            corr.add(&values, false);
+
+            if let Some(ranks) = ranks {
+                ranked.clear();
+                ranked.extend(
+                    values
+                        .iter()
+                        .zip(ranks.iter())
+                        .map(|(value, sorted)| value.map(|v| rank(sorted, v))),
+                );
+                corr.add(&ranked, true);
+            }
+        }
+        Ok(corr)
+    }
+}
+
+/// infers the type of a value like `qsv stats` does, and parses it if it's numeric
+#[inline]
+fn numeric_value(field: &[u8]) -> (FieldType, Option<f64>) {
+    let typ = FieldType::from_sample(false, field);
+    let value = match typ {
+        TInteger | TFloat => {
+            // from_sample already checked that it's a valid number
+            unsafe { str::from_utf8_unchecked(field) }
+                .parse::<f64>()
+                .ok()
+        }
+        _ => None,
+    };
+    (typ, value)
+}
+
+/// the 1-based rank of `value` among the `sorted` values, averaging the ranks of ties
+#[inline]
+#[allow(clippy::cast_precision_loss)]
+fn rank(sorted: &[f64], value: f64) -> f64 {
+    let lower = sorted.partition_point(|v| *v < value);
+    let upper = sorted.partition_point(|v| *v <= value);
+    (lower + 1 + upper) as f64 / 2.0
+}
+
+/// The co-moments of every pair of columns (including each column with itself),
+/// & the inferred column types. With ranks, also the co-moments of the ranks.
+#[derive(Clone)]
+struct Correlation {
+    ncols:  usize,
+    types:  Vec<FieldType>,
+    pairs:  Vec<PairStats>,
+    ranked: Vec<PairStats>,
+}
+
+impl Correlation {
+    fn new(ncols: usize, ranked: bool) -> Correlation {
+        let npairs = ncols * (ncols + 1) / 2;
+        Correlation {
+            ncols,
+            types: vec![TNull; ncols],
+            pairs: vec![PairStats::default(); npairs],
+            ranked: if ranked {
+                vec![PairStats::default(); npairs]
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// the index of the pair (i, j) in the upper triangle of the matrix
+    #[inline]
+    fn pair_index(&self, i: usize, j: usize) -> usize {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        i * self.ncols - i * (i + 1) / 2 + j
+    }
+
+    #[inline]
+    fn add(&mut self, values: &[Option<f64>], ranked: bool) {
+        for i in 0..self.ncols {
+            let Some(x) = values[i] else { continue };
+            for (j, y) in values.iter().enumerate().skip(i) {
+                let Some(y) = y else { continue };
+                let p = self.pair_index(i, j);
+                if ranked {
+                    self.ranked[p].add(x, *y);
+                } else {
+                    self.pairs[p].add(x, *y);
+                }
+            }
+        }
+    }
+
+    fn pair(&self, i: usize, j: usize) -> &PairStats {
+        &self.pairs[self.pair_index(i, j)]
+    }
+
+    fn ranked_pair(&self, i: usize, j: usize) -> &PairStats {
+        &self.ranked[self.pair_index(i, j)]
+    }
+}
+
+impl Commute for Correlation {
+    fn merge(&mut self, other: Correlation) {
+        for (typ, other) in self.types.iter_mut().zip(other.types) {
+            typ.merge(other);
+        }
+        self.pairs.merge(other.pairs);
+        self.ranked.merge(other.ranked);
+    }
+}
+
+/// Streaming means & co-moments of a pair of columns, using Welford's algorithm,
+/// merged with the pairwise formulas of Chan et al.
+/// https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Covariance
+#[derive(Clone, Default)]
+struct PairStats {
+    n:      u64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x:   f64,
+    m2_y:   f64,
+    c_xy:   f64,
+}
+
+#[allow(clippy::cast_precision_loss)]
+impl PairStats {
+    #[inline]
+    fn add(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        let n = self.n as f64;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / n;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    fn covariance(&self) -> Option<f64> {
+        if self.n < 2 {
+            return None;
+        }
+        Some(self.c_xy / (self.n - 1) as f64)
+    }
+
+    fn pearson(&self) -> Option<f64> {
+        let denominator = (self.m2_x * self.m2_y).sqrt();
+        if self.n < 2 || denominator <= 0.0 {
+            return None;
+        }
+        // clamp rounding errors, e.g. 1.0000000000000002
+        Some((self.c_xy / denominator).clamp(-1.0, 1.0))
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+impl Commute for PairStats {
+    fn merge(&mut self, other: PairStats) {
+        if other.n == 0 {
+            return;
+        }
+        if self.n == 0 {
+            *self = other;
+            return;
+        }
+        let (na, nb) = (self.n as f64, other.n as f64);
+        let n = na + nb;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        let weight = na * nb / n;
+
+        self.mean_x += dx * nb / n;
+        self.mean_y += dy * nb / n;
+        self.m2_x += other.m2_x + dx * dx * weight;
+        self.m2_y += other.m2_y + dy * dy * weight;
+        self.c_xy += other.c_xy + dx * dy * weight;
+        self.n += other.n;
+    }
+}
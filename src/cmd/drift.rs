@@ -0,0 +1,555 @@
+static USAGE: &str = r#"
+Compares the distributions of the columns of two versions of a dataset - a baseline & a
+current one - to detect data drift. For each column, it reports:
+
+  * the null rates of the two files & their delta.
+  * the cardinalities (the number of distinct non-empty values) & their delta.
+  * for numeric columns, the two-sample Kolmogorov-Smirnov (KS) statistic - the largest
+    distance between the cumulative distributions of the values - & its asymptotic p-value.
+  * for the other, categorical columns, the population stability index (PSI) of the shares
+    of the values, & the value whose share changed the most.
+
+A column is numeric when all its non-empty values are numbers in both files. The shares of
+the values of a column are relative to its non-empty values, and its null rate is relative
+to all the rows.
+
+The columns of the current file are matched to the baseline columns by name, or by position
+with --no-headers.
+
+The results have a row per column:
+
+    field,type,baseline_rows,current_rows,baseline_null_rate,current_null_rate,
+    null_rate_delta,baseline_cardinality,current_cardinality,cardinality_delta,
+    ks_statistic,ks_pvalue,psi,max_share_delta,max_share_value,drift
+
+Or with --json, an array of objects with the same properties, & the share changes of the
+--top values of categorical columns.
+
+The drift column lists the metrics that exceed the thresholds that are set, separated by
+semicolons. When any column drifted, the results are still output, but the exit code is 1,
+and 0 otherwise, so drift can gate a pipeline, like with `validate` & `sortcheck`.
+As a rule of thumb, a PSI below 0.1 is no significant change, while above 0.25 it's a
+significant one.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_drift.rs.
+
+Usage:
+    qsv drift [options] <baseline> <current>
+    qsv drift --help
+
+drift options:
+    -s, --select <arg>         Select the baseline columns to compare.
+                               See 'qsv select --help' for the format details.
+    --ks-threshold <n>         Flag numeric columns whose KS statistic is above <n>,
+                               between 0 & 1.
+    --psi-threshold <n>        Flag categorical columns whose PSI is above <n>.
+    --share-threshold <n>      Flag categorical columns where the share of a value
+                               changed by more than <n>, between 0 & 1.
+    --null-threshold <n>       Flag columns whose null rate changed by more than <n>,
+                               between 0 & 1.
+    --top <n>                  The number of values with the largest share changes
+                               to list per categorical column with --json.
+                               [default: 5]
+    --round <n>                Round the metrics to <n> decimal places.
+                               [default: 4]
+    --json                     Output the results as JSON.
+    --pretty-json              Same as --json, but pretty printed.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers, and the columns are matched by position.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{io::Write, str};
+
+use ahash::AHashMap;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use stats::Commute;
+
+use crate::{
+    cmd::stats::{
+        round_num,
+        FieldType::{self, TFloat, TInteger, TNull},
+    },
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_baseline:         String,
+    arg_current:          String,
+    flag_select:          SelectColumns,
+    flag_ks_threshold:    Option<f64>,
+    flag_psi_threshold:   Option<f64>,
+    flag_share_threshold: Option<f64>,
+    flag_null_threshold:  Option<f64>,
+    flag_top:             usize,
+    flag_round:           u8,
+    flag_json:            bool,
+    flag_pretty_json:     bool,
+    flag_output:          Option<String>,
+    flag_no_headers:      bool,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+/// the share used instead of 0 for values missing in one of the files,
+/// as the PSI of a share of 0 is infinite
+const PSI_EPSILON: f64 = 0.0001;
+
+/// the distribution of the values of a column in one of the files
+#[derive(Default)]
+struct Distribution {
+    rows:   u64,
+    nulls:  u64,
+    typ:    FieldType,
+    /// the numeric values, as long as all the values are numbers
+    values: Vec<f64>,
+    counts: AHashMap<Vec<u8>, u64>,
+}
+
+impl Distribution {
+    fn add(&mut self, field: &[u8]) {
+        self.rows += 1;
+        if field.is_empty() {
+            self.nulls += 1;
+            return;
+        }
+        self.typ.merge(FieldType::from_sample(false, field));
+        if matches!(self.typ, TInteger | TFloat) {
+            // from_sample already checked that it's a valid number
+            let value = unsafe { str::from_utf8_unchecked(field) }.parse::<f64>();
+            if let Ok(value) = value {
+                if !value.is_nan() {
+                    self.values.push(value);
+                }
+            }
+        } else if !self.values.is_empty() {
+            // the column is not numeric, so its values are not needed anymore
+            self.values = Vec::new();
+        }
+        match self.counts.get_mut(field) {
+            Some(count) => *count += 1,
+            None => {
+                self.counts.insert(field.to_vec(), 1);
+            }
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self.typ, TNull | TInteger | TFloat)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn null_rate(&self) -> f64 {
+        if self.rows == 0 {
+            return 0.0;
+        }
+        self.nulls as f64 / self.rows as f64
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn share(&self, value: &[u8]) -> f64 {
+        let non_nulls = self.rows - self.nulls;
+        if non_nulls == 0 {
+            return 0.0;
+        }
+        self.counts.get(value).copied().unwrap_or_default() as f64 / non_nulls as f64
+    }
+}
+
+struct ShareChange {
+    value:          String,
+    baseline_share: f64,
+    current_share:  f64,
+}
+
+impl ShareChange {
+    fn delta(&self) -> f64 {
+        self.current_share - self.baseline_share
+    }
+}
+
+/// the drift metrics of a column
+struct ColumnDrift {
+    field:                String,
+    numeric:              bool,
+    baseline_rows:        u64,
+    current_rows:         u64,
+    baseline_null_rate:   f64,
+    current_null_rate:    f64,
+    baseline_cardinality: u64,
+    current_cardinality:  u64,
+    /// the KS statistic & its p-value, for numeric columns
+    ks:                   Option<(f64, f64)>,
+    psi:                  Option<f64>,
+    /// sorted by descending absolute delta, for categorical columns
+    share_changes:        Vec<ShareChange>,
+    /// the metrics exceeding their thresholds
+    drift:                Vec<&'static str>,
+}
+
+impl ColumnDrift {
+    fn new(field: String, mut baseline: Distribution, mut current: Distribution) -> ColumnDrift {
+        let numeric = baseline.is_numeric() && current.is_numeric();
+        let (ks, psi, share_changes) = if numeric {
+            let ks = if baseline.values.is_empty() || current.values.is_empty() {
+                None
+            } else {
+                Some(ks_test(
+                    std::mem::take(&mut baseline.values),
+                    std::mem::take(&mut current.values),
+                ))
+            };
+            (ks, None, vec![])
+        } else {
+            let share_changes = share_changes(&baseline, &current);
+            let psi = if baseline.rows == baseline.nulls || current.rows == current.nulls {
+                None
+            } else {
+                Some(psi(&share_changes))
+            };
+            (None, psi, share_changes)
+        };
+
+        ColumnDrift {
+            field,
+            numeric,
+            baseline_rows: baseline.rows,
+            current_rows: current.rows,
+            baseline_null_rate: baseline.null_rate(),
+            current_null_rate: current.null_rate(),
+            baseline_cardinality: baseline.counts.len() as u64,
+            current_cardinality: current.counts.len() as u64,
+            ks,
+            psi,
+            share_changes,
+            drift: vec![],
+        }
+    }
+
+    fn null_rate_delta(&self) -> f64 {
+        self.current_null_rate - self.baseline_null_rate
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    const fn cardinality_delta(&self) -> i64 {
+        self.current_cardinality as i64 - self.baseline_cardinality as i64
+    }
+
+    fn flag_drift(&mut self, args: &Args) {
+        let exceeds = |value: Option<f64>, threshold: Option<f64>| match (value, threshold) {
+            (Some(value), Some(threshold)) => value > threshold,
+            _ => false,
+        };
+        if exceeds(self.ks.map(|(d, _)| d), args.flag_ks_threshold) {
+            self.drift.push("ks");
+        }
+        if exceeds(self.psi, args.flag_psi_threshold) {
+            self.drift.push("psi");
+        }
+        let max_share_delta = self.share_changes.first().map(|c| c.delta().abs());
+        if exceeds(max_share_delta, args.flag_share_threshold) {
+            self.drift.push("share");
+        }
+        if exceeds(Some(self.null_rate_delta().abs()), args.flag_null_threshold) {
+            self.drift.push("null_rate");
+        }
+    }
+
+    fn to_record(&self, places: u8) -> Vec<String> {
+        let round = |value: f64| round_num(value, places);
+        let max_share_change = self.share_changes.first();
+        vec![
+            self.field.clone(),
+            self.column_type().to_string(),
+            self.baseline_rows.to_string(),
+            self.current_rows.to_string(),
+            round(self.baseline_null_rate),
+            round(self.current_null_rate),
+            round(self.null_rate_delta()),
+            self.baseline_cardinality.to_string(),
+            self.current_cardinality.to_string(),
+            self.cardinality_delta().to_string(),
+            self.ks.map(|(d, _)| round(d)).unwrap_or_default(),
+            self.ks.map(|(_, p)| round(p)).unwrap_or_default(),
+            self.psi.map(round).unwrap_or_default(),
+            max_share_change
+                .map(|c| round(c.delta()))
+                .unwrap_or_default(),
+            max_share_change
+                .map(|c| c.value.clone())
+                .unwrap_or_default(),
+            self.drift.join(";"),
+        ]
+    }
+
+    fn to_json(&self, places: u8, top: usize) -> Value {
+        let round = |value: f64| rounded(value, places);
+        let max_share_change = self.share_changes.first();
+        let share_changes: Vec<Value> = self
+            .share_changes
+            .iter()
+            .take(top)
+            .map(|c| {
+                json!({
+                    "value": c.value,
+                    "baseline_share": round(c.baseline_share),
+                    "current_share": round(c.current_share),
+                    "delta": round(c.delta()),
+                })
+            })
+            .collect();
+        json!({
+            "field": self.field,
+            "type": self.column_type(),
+            "baseline_rows": self.baseline_rows,
+            "current_rows": self.current_rows,
+            "baseline_null_rate": round(self.baseline_null_rate),
+            "current_null_rate": round(self.current_null_rate),
+            "null_rate_delta": round(self.null_rate_delta()),
+            "baseline_cardinality": self.baseline_cardinality,
+            "current_cardinality": self.current_cardinality,
+            "cardinality_delta": self.cardinality_delta(),
+            "ks_statistic": self.ks.map(|(d, _)| round(d)),
+            "ks_pvalue": self.ks.map(|(_, p)| round(p)),
+            "psi": self.psi.map(round),
+            "max_share_delta": max_share_change.map(|c| round(c.delta())),
+            "max_share_value": max_share_change.map(|c| c.value.clone()),
+            "share_changes": share_changes,
+            "drift": self.drift,
+        })
+    }
+
+    const fn column_type(&self) -> &'static str {
+        if self.numeric {
+            "numeric"
+        } else {
+            "categorical"
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let baseline_conf = Config::new(&Some(args.arg_baseline.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select.clone());
+    let current_conf = Config::new(&Some(args.arg_current.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut baseline_rdr = baseline_conf.reader()?;
+    let mut current_rdr = current_conf.reader()?;
+    let baseline_headers = baseline_rdr.byte_headers()?.clone();
+    let current_headers = current_rdr.byte_headers()?.clone();
+    let sel = baseline_conf.selection(&baseline_headers)?;
+
+    // the names of the selected columns, & their indices in the current file
+    let mut fields = Vec::with_capacity(sel.len());
+    let mut current_cols = Vec::with_capacity(sel.len());
+    for &col in sel.iter() {
+        let current_col = if args.flag_no_headers {
+            fields.push((col + 1).to_string());
+            (col < current_headers.len()).then_some(col)
+        } else {
+            let field = &baseline_headers[col];
+            fields.push(String::from_utf8_lossy(field).to_string());
+            current_headers.iter().position(|h| h == field)
+        };
+        let Some(current_col) = current_col else {
+            return fail_clierror!(
+                "Column {} is not in {}.",
+                fields[fields.len() - 1],
+                args.arg_current
+            );
+        };
+        current_cols.push(current_col);
+    }
+
+    let baseline = distributions(&mut baseline_rdr, &sel)?;
+    let current = distributions(&mut current_rdr, &current_cols)?;
+
+    let mut drifts: Vec<ColumnDrift> = fields
+        .into_iter()
+        .zip(baseline.into_iter().zip(current.into_iter()))
+        .map(|(field, (baseline, current))| ColumnDrift::new(field, baseline, current))
+        .collect();
+    for drift in &mut drifts {
+        drift.flag_drift(&args);
+    }
+
+    if args.flag_json || args.flag_pretty_json {
+        let results = Value::Array(
+            drifts
+                .iter()
+                .map(|drift| drift.to_json(args.flag_round, args.flag_top))
+                .collect(),
+        );
+        let json = if args.flag_pretty_json {
+            serde_json::to_string_pretty(&results)
+        } else {
+            serde_json::to_string(&results)
+        };
+        let Ok(json) = json else {
+            return fail!("Cannot serialize the drift results to JSON.");
+        };
+        let mut wtr = Config::new(&args.flag_output).io_writer()?;
+        writeln!(wtr, "{json}")?;
+        wtr.flush()?;
+    } else {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        wtr.write_record([
+            "field",
+            "type",
+            "baseline_rows",
+            "current_rows",
+            "baseline_null_rate",
+            "current_null_rate",
+            "null_rate_delta",
+            "baseline_cardinality",
+            "current_cardinality",
+            "cardinality_delta",
+            "ks_statistic",
+            "ks_pvalue",
+            "psi",
+            "max_share_delta",
+            "max_share_value",
+            "drift",
+        ])?;
+        for drift in &drifts {
+            wtr.write_record(drift.to_record(args.flag_round))?;
+        }
+        wtr.flush()?;
+    }
+
+    let drifted: Vec<&str> = drifts
+        .iter()
+        .filter(|drift| !drift.drift.is_empty())
+        .map(|drift| drift.field.as_str())
+        .collect();
+    if !drifted.is_empty() {
+        return fail_clierror!(
+            "Drift detected in {} of {} columns: {}",
+            drifted.len(),
+            drifts.len(),
+            drifted.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// reads the distributions of the `cols` columns of a file
+fn distributions<R: std::io::Read>(
+    rdr: &mut csv::Reader<R>,
+    cols: &[usize],
+) -> CliResult<Vec<Distribution>> {
+    let mut dists: Vec<Distribution> = cols.iter().map(|_| Distribution::default()).collect();
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        for (dist, &col) in dists.iter_mut().zip(cols.iter()) {
+            dist.add(record.get(col).unwrap_or_default());
+        }
+    }
+    Ok(dists)
+}
+
+/// the changes of the shares of all the values of both files
+fn share_changes(baseline: &Distribution, current: &Distribution) -> Vec<ShareChange> {
+    let mut values: Vec<&Vec<u8>> = baseline.counts.keys().collect();
+    values.extend(
+        current
+            .counts
+            .keys()
+            .filter(|value| !baseline.counts.contains_key(*value)),
+    );
+    let mut changes: Vec<ShareChange> = values
+        .into_iter()
+        .map(|value| ShareChange {
+            value:          String::from_utf8_lossy(value).to_string(),
+            baseline_share: baseline.share(value),
+            current_share:  current.share(value),
+        })
+        .collect();
+    changes.sort_unstable_by(|c1, c2| {
+        c2.delta()
+            .abs()
+            .total_cmp(&c1.delta().abs())
+            .then_with(|| c1.value.cmp(&c2.value))
+    });
+    changes
+}
+
+/// the population stability index, the sum over all the values of
+/// (current share - baseline share) * ln(current share / baseline share)
+fn psi(share_changes: &[ShareChange]) -> f64 {
+    share_changes
+        .iter()
+        .map(|c| {
+            let baseline = c.baseline_share.max(PSI_EPSILON);
+            let current = c.current_share.max(PSI_EPSILON);
+            (current - baseline) * (current / baseline).ln()
+        })
+        .sum()
+}
+
+/// the two-sample Kolmogorov-Smirnov statistic & its asymptotic p-value
+#[allow(clippy::cast_precision_loss)]
+fn ks_test(mut x: Vec<f64>, mut y: Vec<f64>) -> (f64, f64) {
+    x.sort_unstable_by(f64::total_cmp);
+    y.sort_unstable_by(f64::total_cmp);
+    let (n, m) = (x.len() as f64, y.len() as f64);
+
+    let (mut i, mut j) = (0, 0);
+    let mut d: f64 = 0.0;
+    while i < x.len() && j < y.len() {
+        let value = x[i].min(y[j]);
+        while i < x.len() && x[i] <= value {
+            i += 1;
+        }
+        while j < y.len() && y[j] <= value {
+            j += 1;
+        }
+        d = d.max((i as f64 / n - j as f64 / m).abs());
+    }
+
+    // the effective number of samples, with the small sample correction of Stephens (1970)
+    let ne = (n * m / (n + m)).sqrt();
+    let lambda = (ne + 0.12 + 0.11 / ne) * d;
+    (d, kolmogorov_q(lambda))
+}
+
+/// the complementary cumulative Kolmogorov distribution,
+/// 2 * sum of (-1)^(k-1) * e^(-2k²λ²) for k >= 1
+#[allow(clippy::cast_precision_loss)]
+fn kolmogorov_q(lambda: f64) -> f64 {
+    // the series converges too slowly to be summed for small lambdas, where it's ~1
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    let mut sign = 2.0;
+    for k in 1..=100 {
+        let k = k as f64;
+        let term = sign * (-2.0 * k * k * lambda * lambda).exp();
+        sum += term;
+        if term.abs() <= 1e-10 * sum.abs() {
+            break;
+        }
+        sign = -sign;
+    }
+    sum.clamp(0.0, 1.0)
+}
+
+fn rounded(value: f64, places: u8) -> f64 {
+    let factor = 10_f64.powi(i32::from(places));
+    (value * factor).round() / factor
+}
@@ -12,6 +12,34 @@ respectively.
 Since this computes an exact frequency table, memory proportional to the
 cardinality of each column is required.
 
+If the input has a valid stats cache with cardinality (see `qsv stats --help`),
+columns that the cache shows to have all unique values (e.g. ID columns) are not
+tabulated in full. Only the first N values are kept, as they all have a count of 1.
+
+For high-cardinality columns (e.g. IDs) that would need too much memory, use --approx to
+compute an approximate table of the most frequent values with bounded memory, using the
+Space-Saving algorithm. In that mode, the frequency table has an additional column:
+
+    field,value,count,max_error
+
+where a value's true count is between count - max_error and count. The values whose true
+count is greater than 1/--approx-capacity of the rows are guaranteed to be in the table.
+
+With --combine, the distinct combinations of values of all the selected columns are
+counted instead of the values of each column (e.g. --select state,city --combine). The
+frequency table then has a column for each selected column:
+
+    state,city,count,percentage,cumulative_percentage
+
+With --weight, the values of a numeric column are summed instead of counting rows
+(e.g. the population of each state), and the count column is named "weight":
+
+    field,value,weight,percentage,cumulative_percentage
+
+Rows with an empty or non-numeric weight are ignored. Percentages are of the total
+count (or weight) of the column, or of the combinations, after excluding NULLs with
+--no-nulls, and cumulative percentages add up the rows in the sort order.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_frequency.rs.
 
 Usage:
@@ -30,6 +58,18 @@ frequency options:
     -a, --asc              Sort the frequency tables in ascending order by
                            count. The default is descending order.
     --no-nulls             Don't include NULLs in the frequency table.
+    --approx               Compute an approximate frequency table of the most
+                           frequent values, using memory bounded by
+                           --approx-capacity instead of the cardinality.
+    --approx-capacity <n>  The number of values tracked per column with --approx.
+                           Larger capacities are more accurate, but use more
+                           memory. Must be at least --limit.
+                           [default: 10000]
+    --combine              Count the distinct combinations of values of the
+                           selected columns, instead of each column's values.
+                           With --no-nulls, combinations with a NULL are skipped.
+    --weight <col>         Sum the values of this numeric column instead of
+                           counting rows.
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            This works better when the given CSV data has
                            an index already created. Note that a file handle
@@ -48,13 +88,15 @@ Common options:
                            Must be a single character. (default: ,)
 "#;
 
-use std::{fs, io};
+use std::{collections::BTreeSet, fs, io};
 
+use ahash::AHashMap;
 use serde::Deserialize;
-use stats::{merge_all, Frequencies};
+use stats::{merge_all, Commute, Frequencies};
 use threadpool::ThreadPool;
 
 use crate::{
+    cmd::stats::{round_num, StatsCache},
     config::{Config, Delimiter},
     index::Indexed,
     select::{SelectColumns, Selection},
@@ -63,15 +105,19 @@ use crate::{
 
 #[derive(Clone, Deserialize)]
 pub struct Args {
-    pub arg_input:       Option<String>,
-    pub flag_select:     SelectColumns,
-    pub flag_limit:      usize,
-    pub flag_asc:        bool,
-    pub flag_no_nulls:   bool,
-    pub flag_jobs:       Option<usize>,
-    pub flag_output:     Option<String>,
-    pub flag_no_headers: bool,
-    pub flag_delimiter:  Option<Delimiter>,
+    pub arg_input:            Option<String>,
+    pub flag_select:          SelectColumns,
+    pub flag_limit:           usize,
+    pub flag_asc:             bool,
+    pub flag_no_nulls:        bool,
+    pub flag_approx:          bool,
+    pub flag_approx_capacity: usize,
+    pub flag_combine:         bool,
+    pub flag_weight:          Option<SelectColumns>,
+    pub flag_jobs:            Option<usize>,
+    pub flag_output:          Option<String>,
+    pub flag_no_headers:      bool,
+    pub flag_delimiter:       Option<Delimiter>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -79,6 +125,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let rconfig = args.rconfig();
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
+    if args.flag_approx {
+        if args.flag_combine || args.flag_weight.is_some() {
+            return fail!("--approx cannot be used with --combine or --weight.");
+        }
+        return run_approx(&args, &mut wtr);
+    }
+    if args.flag_combine || args.flag_weight.is_some() {
+        return run_tally(&args, &mut wtr);
+    }
     let (headers, tables) = match args.rconfig().indexed()? {
         Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_ftables(idx),
         _ => args.sequential_ftables(),
@@ -101,6 +156,125 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+fn run_approx(args: &Args, wtr: &mut csv::Writer<Box<dyn io::Write>>) -> CliResult<()> {
+    if args.flag_approx_capacity == 0 || args.flag_approx_capacity < args.flag_limit {
+        return fail_clierror!(
+            "--approx-capacity ({}) must be greater than 0, and at least --limit ({}).",
+            args.flag_approx_capacity,
+            args.flag_limit
+        );
+    }
+    let rconfig = args.rconfig();
+    let (headers, tables) = match rconfig.indexed()? {
+        Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_topk_tables(idx),
+        _ => args.sequential_topk_tables(),
+    }?;
+
+    wtr.write_record(vec!["field", "value", "count", "max_error"])?;
+    let head_tables = headers.into_iter().zip(tables.into_iter());
+    for (i, (header, topk)) in head_tables.enumerate() {
+        let header = if rconfig.no_headers {
+            (i + 1).to_string().into_bytes()
+        } else {
+            header.to_vec()
+        };
+        let mut counters = if args.flag_asc {
+            topk.least_frequent()
+        } else {
+            topk.most_frequent()
+        };
+        if args.flag_limit > 0 {
+            counters.truncate(args.flag_limit);
+        }
+        for counter in counters {
+            let value = if counter.value.is_empty() {
+                &b"(NULL)"[..]
+            } else {
+                &counter.value
+            };
+            let count = counter.count.to_string();
+            let max_error = counter.error.to_string();
+            let row = vec![&*header, value, count.as_bytes(), max_error.as_bytes()];
+            wtr.write_record(row)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_tally(args: &Args, wtr: &mut csv::Writer<Box<dyn io::Write>>) -> CliResult<()> {
+    let rconfig = args.rconfig();
+    let (headers, tables) = match rconfig.indexed()? {
+        Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_tally_tables(idx),
+        _ => args.sequential_tally_tables(),
+    }?;
+    let headers: Vec<ByteString> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            if rconfig.no_headers {
+                (i + 1).to_string().into_bytes()
+            } else {
+                header.to_vec()
+            }
+        })
+        .collect();
+    let tally_header = if args.flag_weight.is_some() {
+        "weight"
+    } else {
+        "count"
+    };
+
+    let mut header_row: Vec<&[u8]> = if args.flag_combine {
+        headers.iter().map(Vec::as_slice).collect()
+    } else {
+        vec![b"field", b"value"]
+    };
+    header_row.extend([
+        tally_header.as_bytes(),
+        b"percentage",
+        b"cumulative_percentage",
+    ]);
+    wtr.write_record(header_row)?;
+
+    for (i, table) in tables.iter().enumerate() {
+        let total = table.total();
+        let mut cumulative = 0.0;
+        for (values, tally) in args.tallies(table) {
+            let tally_str = if args.flag_weight.is_some() {
+                round_num(tally, 4)
+            } else {
+                (tally as u64).to_string()
+            };
+            let (pct, cumulative_pct) = if total.abs() < f64::EPSILON {
+                (0.0, 0.0)
+            } else {
+                cumulative += tally;
+                (tally * 100.0 / total, cumulative * 100.0 / total)
+            };
+            let (pct, cumulative_pct) = (round_num(pct, 4), round_num(cumulative_pct, 4));
+
+            let mut row: Vec<&[u8]> = Vec::with_capacity(values.len() + 4);
+            if !args.flag_combine {
+                row.push(&headers[i]);
+            }
+            row.extend(values.iter().map(|value| {
+                if value.is_empty() {
+                    &b"(NULL)"[..]
+                } else {
+                    value.as_slice()
+                }
+            }));
+            row.extend([
+                tally_str.as_bytes(),
+                pct.as_bytes(),
+                cumulative_pct.as_bytes(),
+            ]);
+            wtr.write_record(row)?;
+        }
+    }
+    Ok(())
+}
+
 type ByteString = Vec<u8>;
 type Headers = csv::ByteRecord;
 type FTable = Frequencies<Vec<u8>>;
@@ -139,7 +313,11 @@ impl Args {
     pub fn sequential_ftables(&self) -> CliResult<(Headers, FTables)> {
         let mut rdr = self.rconfig().reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
-        Ok((headers, self.ftables(&sel, rdr.byte_records())?))
+        let all_unique = self.all_unique_columns(&sel);
+        Ok((
+            headers,
+            self.ftables(&sel, &all_unique, rdr.byte_records())?,
+        ))
     }
 
     pub fn parallel_ftables(
@@ -153,34 +331,84 @@ impl Args {
             return Ok((headers, vec![]));
         }
 
+        let all_unique = self.all_unique_columns(&sel);
+
         let chunk_size = util::chunk_size(idx.count() as usize, util::njobs(self.flag_jobs));
         let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
 
         let pool = ThreadPool::new(util::njobs(self.flag_jobs));
         let (send, recv) = channel::bounded(0);
         for i in 0..nchunks {
-            let (send, args, sel) = (send.clone(), self.clone(), sel.clone());
+            let (send, args, sel, all_unique) =
+                (send.clone(), self.clone(), sel.clone(), all_unique.clone());
             pool.execute(move || {
                 let mut idx = args.rconfig().indexed().unwrap().unwrap();
                 idx.seek((i * chunk_size) as u64).unwrap();
                 let it = idx.byte_records().take(chunk_size);
-                send.send(args.ftables(&sel, it).unwrap()).unwrap();
+                send.send(args.ftables(&sel, &all_unique, it).unwrap())
+                    .unwrap();
             });
         }
         drop(send);
         Ok((headers, merge_all(recv.iter()).unwrap()))
     }
 
-    fn ftables<I>(&self, sel: &Selection, it: I) -> CliResult<FTables>
+    /// Uses the stats cache, if there is a valid one, to flag the selected columns
+    /// (in column order, like `ftables`) whose values are all unique. As `ftables` trims
+    /// the values, columns with padded values are never flagged, as their trimmed values
+    /// may not be unique.
+    fn all_unique_columns(&self, sel: &Selection) -> Vec<bool> {
+        let nsel = sel.normal();
+        let mut all_unique = vec![false; nsel.len()];
+        if self.flag_limit == 0 {
+            return all_unique;
+        }
+        let rconfig = self.rconfig();
+        let Some(cache) = StatsCache::load(&self.arg_input) else {
+            return all_unique;
+        };
+        if !cache.has_cardinality()
+            || !cache.same_dialect(rconfig.no_headers, rconfig.get_delimiter())
+        {
+            return all_unique;
+        }
+        let Some(cardinality_idx) = cache.stat_index("cardinality") else {
+            return all_unique;
+        };
+
+        let selected_cols = nsel
+            .iter()
+            .enumerate()
+            .filter(|(_, selected)| **selected)
+            .map(|(col, _)| col);
+        for (i, col) in selected_cols.enumerate() {
+            all_unique[i] = cache
+                .stats
+                .get(col)
+                .and_then(|stat| stat.get(cardinality_idx))
+                .and_then(|cardinality| cardinality.parse::<u64>().ok())
+                .map_or(false, |cardinality| cardinality == cache.record_count)
+                && cache.padded.get(col) == Some(&false);
+        }
+        all_unique
+    }
+
+    fn ftables<I>(&self, sel: &Selection, all_unique: &[bool], it: I) -> CliResult<FTables>
     where
         I: Iterator<Item = csv::Result<csv::ByteRecord>>,
     {
         let null = &b""[..].to_vec();
         let nsel = sel.normal();
+        let limit = self.flag_limit as u64;
         let mut tabs: Vec<_> = (0..nsel.len()).map(|_| Frequencies::new()).collect();
         for row in it {
             let row = row?;
             for (i, field) in nsel.select(row.into_iter()).enumerate() {
+                // every value of an all unique column has a count of 1,
+                // so we only need to keep the first `limit` values
+                if all_unique[i] && tabs[i].cardinality() >= limit {
+                    continue;
+                }
                 let field = trim(field.to_vec());
                 if !field.is_empty() {
                     tabs[i].add(field);
@@ -192,6 +420,178 @@ impl Args {
         Ok(tabs)
     }
 
+    pub fn sequential_topk_tables(&self) -> CliResult<(Headers, Vec<TopK>)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+        Ok((headers, self.topk_tables(&sel, rdr.byte_records())?))
+    }
+
+    pub fn parallel_topk_tables(
+        &self,
+        idx: &mut Indexed<fs::File, fs::File>,
+    ) -> CliResult<(Headers, Vec<TopK>)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        if idx.count() == 0 {
+            return Ok((headers, vec![]));
+        }
+
+        let chunk_size = util::chunk_size(idx.count() as usize, util::njobs(self.flag_jobs));
+        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+
+        let pool = ThreadPool::new(util::njobs(self.flag_jobs));
+        let (send, recv) = channel::bounded(0);
+        for i in 0..nchunks {
+            let (send, args, sel) = (send.clone(), self.clone(), sel.clone());
+            pool.execute(move || {
+                let mut idx = args.rconfig().indexed().unwrap().unwrap();
+                idx.seek((i * chunk_size) as u64).unwrap();
+                let it = idx.byte_records().take(chunk_size);
+                send.send(args.topk_tables(&sel, it).unwrap()).unwrap();
+            });
+        }
+        drop(send);
+        Ok((headers, merge_all(recv.iter()).unwrap()))
+    }
+
+    fn topk_tables<I>(&self, sel: &Selection, it: I) -> CliResult<Vec<TopK>>
+    where
+        I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+    {
+        let nsel = sel.normal();
+        let mut tabs: Vec<_> = (0..nsel.len())
+            .map(|_| TopK::new(self.flag_approx_capacity))
+            .collect();
+        for row in it {
+            let row = row?;
+            for (i, field) in nsel.select(row.into_iter()).enumerate() {
+                let field = trim(field.to_vec());
+                if !field.is_empty() || !self.flag_no_nulls {
+                    tabs[i].add(field);
+                }
+            }
+        }
+        Ok(tabs)
+    }
+
+    fn tallies<'a>(&self, table: &'a Tally) -> Vec<(&'a Vec<ByteString>, f64)> {
+        let mut tallies: Vec<_> = table
+            .0
+            .iter()
+            .map(|(values, tally)| (values, *tally))
+            .collect();
+        if self.flag_asc {
+            tallies.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        } else {
+            tallies.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        }
+        if self.flag_limit > 0 {
+            tallies.truncate(self.flag_limit);
+        }
+        tallies
+    }
+
+    pub fn sequential_tally_tables(&self) -> CliResult<(Headers, Vec<Tally>)> {
+        let mut rdr = self.rconfig().reader()?;
+        let weight_idx = self.weight_index(&rdr.byte_headers()?.clone())?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+        Ok((
+            headers,
+            self.tally_tables(&sel, weight_idx, rdr.byte_records())?,
+        ))
+    }
+
+    pub fn parallel_tally_tables(
+        &self,
+        idx: &mut Indexed<fs::File, fs::File>,
+    ) -> CliResult<(Headers, Vec<Tally>)> {
+        let mut rdr = self.rconfig().reader()?;
+        let weight_idx = self.weight_index(&rdr.byte_headers()?.clone())?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        if idx.count() == 0 {
+            return Ok((headers, vec![]));
+        }
+
+        let chunk_size = util::chunk_size(idx.count() as usize, util::njobs(self.flag_jobs));
+        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+
+        let pool = ThreadPool::new(util::njobs(self.flag_jobs));
+        let (send, recv) = channel::bounded(0);
+        for i in 0..nchunks {
+            let (send, args, sel) = (send.clone(), self.clone(), sel.clone());
+            pool.execute(move || {
+                let mut idx = args.rconfig().indexed().unwrap().unwrap();
+                idx.seek((i * chunk_size) as u64).unwrap();
+                let it = idx.byte_records().take(chunk_size);
+                send.send(args.tally_tables(&sel, weight_idx, it).unwrap())
+                    .unwrap();
+            });
+        }
+        drop(send);
+        Ok((headers, merge_all(recv.iter()).unwrap()))
+    }
+
+    /// the index of the --weight column, which must be a single column
+    fn weight_index(&self, headers: &csv::ByteRecord) -> CliResult<Option<usize>> {
+        let Some(ref weight) = self.flag_weight else {
+            return Ok(None);
+        };
+        let sel = weight.selection(headers, !self.flag_no_headers)?;
+        if sel.len() != 1 {
+            return fail!("--weight must select exactly one column.");
+        }
+        Ok(Some(sel[0]))
+    }
+
+    /// Counts (or sums the weights of) the values of each selected column, in
+    /// selection order, or the combinations of values of all of them with --combine.
+    fn tally_tables<I>(
+        &self,
+        sel: &Selection,
+        weight_idx: Option<usize>,
+        it: I,
+    ) -> CliResult<Vec<Tally>>
+    where
+        I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+    {
+        let ntables = if self.flag_combine { 1 } else { sel.len() };
+        let mut tabs = vec![Tally::default(); ntables];
+        for row in it {
+            let row = row?;
+            let weight = match weight_idx {
+                Some(i) => {
+                    let weight = row
+                        .get(i)
+                        .and_then(|w| std::str::from_utf8(w).ok())
+                        .and_then(|w| w.trim().parse::<f64>().ok())
+                        .filter(|w| w.is_finite());
+                    match weight {
+                        Some(weight) => weight,
+                        None => continue,
+                    }
+                }
+                None => 1.0,
+            };
+            if self.flag_combine {
+                let values: Vec<ByteString> = sel.select(&row).map(|f| trim(f.to_vec())).collect();
+                if self.flag_no_nulls && values.iter().any(Vec::is_empty) {
+                    continue;
+                }
+                tabs[0].add(values, weight);
+            } else {
+                for (i, field) in sel.select(&row).enumerate() {
+                    let field = trim(field.to_vec());
+                    if !field.is_empty() || !self.flag_no_nulls {
+                        tabs[i].add(vec![field], weight);
+                    }
+                }
+            }
+        }
+        Ok(tabs)
+    }
+
     fn sel_headers<R: io::Read>(
         &self,
         rdr: &mut csv::Reader<R>,
@@ -208,3 +608,159 @@ fn trim(bs: ByteString) -> ByteString {
     let s = unsafe { String::from_utf8_unchecked(bs) };
     s.trim().as_bytes().to_vec()
 }
+
+/// `Tally` sums the weights (1 per row when there's no --weight) of each distinct
+/// value, or combination of values with --combine.
+#[derive(Clone, Default)]
+pub struct Tally(AHashMap<Vec<ByteString>, f64>);
+
+impl Tally {
+    #[inline]
+    fn add(&mut self, values: Vec<ByteString>, weight: f64) {
+        *self.0.entry(values).or_insert(0.0) += weight;
+    }
+
+    fn total(&self) -> f64 {
+        self.0.values().sum()
+    }
+}
+
+impl Commute for Tally {
+    fn merge(&mut self, other: Tally) {
+        for (values, weight) in other.0 {
+            self.add(values, weight);
+        }
+    }
+}
+
+/// `TopK` approximates the most frequent values of a column in memory bounded by
+/// its capacity, using the Space-Saving algorithm by Metwally, Agrawal & El Abbadi.
+/// https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf
+///
+/// Once all the counters are taken, a new value evicts the value with the smallest
+/// count & takes over its count, which becomes the new value's max error.
+#[derive(Clone)]
+pub struct TopK {
+    capacity: usize,
+    counters: Vec<TopKCounter>,
+    index:    AHashMap<ByteString, usize>,
+    // (count, counter index) of all the counters, to find the smallest count quickly
+    order:    BTreeSet<(u64, usize)>,
+}
+
+#[derive(Clone)]
+pub struct TopKCounter {
+    pub value: ByteString,
+    pub count: u64,
+    pub error: u64,
+}
+
+impl TopK {
+    fn new(capacity: usize) -> TopK {
+        TopK {
+            capacity,
+            counters: Vec::new(),
+            index: AHashMap::new(),
+            order: BTreeSet::new(),
+        }
+    }
+
+    fn from_counters(capacity: usize, mut counters: Vec<TopKCounter>) -> TopK {
+        counters.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        counters.truncate(capacity);
+        let mut topk = TopK::new(capacity);
+        for (i, counter) in counters.iter().enumerate() {
+            topk.index.insert(counter.value.clone(), i);
+            topk.order.insert((counter.count, i));
+        }
+        topk.counters = counters;
+        topk
+    }
+
+    #[inline]
+    fn add(&mut self, value: ByteString) {
+        if let Some(&i) = self.index.get(&value) {
+            let counter = &mut self.counters[i];
+            self.order.remove(&(counter.count, i));
+            counter.count += 1;
+            self.order.insert((counter.count, i));
+        } else if self.counters.len() < self.capacity {
+            let i = self.counters.len();
+            self.index.insert(value.clone(), i);
+            self.counters.push(TopKCounter {
+                value,
+                count: 1,
+                error: 0,
+            });
+            self.order.insert((1, i));
+        } else {
+            // capacity is at least 1, so there is always a smallest counter
+            let (min_count, i) = *self.order.iter().next().unwrap();
+            self.order.remove(&(min_count, i));
+            let counter = &mut self.counters[i];
+            self.index.remove(&counter.value);
+            self.index.insert(value.clone(), i);
+            *counter = TopKCounter {
+                value,
+                count: min_count + 1,
+                error: min_count,
+            };
+            self.order.insert((min_count + 1, i));
+        }
+    }
+
+    /// the largest count a value that's not tracked could have
+    fn min_count(&self) -> u64 {
+        if self.counters.len() < self.capacity {
+            0
+        } else {
+            self.order.iter().next().map_or(0, |(count, _)| *count)
+        }
+    }
+
+    fn most_frequent(&self) -> Vec<&TopKCounter> {
+        let mut counters: Vec<_> = self.counters.iter().collect();
+        counters.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        counters
+    }
+
+    fn least_frequent(&self) -> Vec<&TopKCounter> {
+        let mut counters: Vec<_> = self.counters.iter().collect();
+        counters.sort_unstable_by(|a, b| a.count.cmp(&b.count).then_with(|| a.value.cmp(&b.value)));
+        counters
+    }
+}
+
+impl Commute for TopK {
+    /// Merges two summaries as described in "Mergeable Summaries" by Agarwal et al.
+    /// A value missing from one summary may have occurred up to that summary's
+    /// smallest count times, which is added to the value's count & max error.
+    fn merge(&mut self, other: TopK) {
+        let self_min = self.min_count();
+        let other_min = other.min_count();
+
+        let mut merged: AHashMap<ByteString, TopKCounter> =
+            AHashMap::with_capacity(self.counters.len() + other.counters.len());
+        for mut counter in self.counters.drain(..) {
+            counter.count += other_min;
+            counter.error += other_min;
+            merged.insert(counter.value.clone(), counter);
+        }
+        for counter in other.counters {
+            if let Some(m) = merged.get_mut(&counter.value) {
+                m.count = m.count - other_min + counter.count;
+                m.error = m.error - other_min + counter.error;
+            } else {
+                merged.insert(
+                    counter.value.clone(),
+                    TopKCounter {
+                        value: counter.value,
+                        count: counter.count + self_min,
+                        error: counter.error + self_min,
+                    },
+                );
+            }
+        }
+        *self = TopK::from_counters(self.capacity, merged.into_values().collect());
+    }
+}
@@ -0,0 +1,562 @@
+static USAGE: &str = r#"
+Geospatial utilities for the WGS84 locations of a CSV.
+
+The <location> of the rows is a selection of either:
+
+  * one column, with "latitude, longitude" values (as with `apply geocode`), or
+    WKT "POINT (longitude latitude)" values.
+  * two columns: the latitude column, then the longitude column. e.g. lat,lon
+
+See 'qsv select --help' for the selection format details.
+
+The subcommands are:
+
+distance: Appends the distance between the <location> & either the location in the
+  --to columns, or the fixed --point, in --unit. The distance is computed with the
+  haversine formula on a sphere by default, or with the more accurate but slower Vincenty
+  formula on the WGS84 ellipsoid.
+
+  $ qsv geo distance pickup_lat,pickup_lon --to dropoff_lat,dropoff_lon trips.csv
+  $ qsv geo distance location --point "40.7128, -74.0060" --unit mi stores.csv
+
+bbox: Only keeps the rows located in the --bbox bounding box, set as "west,south,east,north"
+  i.e. "min_lon,min_lat,max_lon,max_lat", as in GeoJSON. A box with west > east crosses
+  the antimeridian.
+
+  $ qsv geo bbox location --bbox "-74.26,40.49,-73.70,40.92" data.csv
+
+radius: Only keeps the rows within the --radius distance of the --point, in --unit.
+
+  $ qsv geo radius lat,lon --point "48.8566, 2.3522" --radius 10 data.csv
+
+geohash: Appends the geohash of the <location>, with --precision characters.
+  With --decode, the <location> is a geohash column instead, & the latitude & longitude
+  of the center of the geohash cells are appended.
+
+  $ qsv geo geohash lat,lon --precision 7 data.csv
+  $ qsv geo geohash --decode geohash data.csv
+
+parse: Splits the <location> column into latitude & longitude columns.
+
+  $ qsv geo parse location data.csv
+
+Rows without a valid location get empty values, & are considered outside of the --bbox
+or --radius.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_geo.rs.
+
+Usage:
+    qsv geo distance [options] (--to <columns> | --point <point>) <location> [<input>]
+    qsv geo bbox [options] --bbox <box> <location> [<input>]
+    qsv geo radius [options] --point <point> --radius <distance> <location> [<input>]
+    qsv geo geohash [options] <location> [<input>]
+    qsv geo parse [options] <location> [<input>]
+    qsv geo --help
+
+geo options:
+    --to <columns>             distance: the other location, in the same format as <location>.
+    --point <point>            distance & radius: the fixed "latitude, longitude" location.
+    --method <method>          distance & radius: the formula, haversine or vincenty.
+                               [default: haversine]
+    --unit <unit>              The unit of the distances: km, m, mi or nmi.
+                               [default: km]
+    --radius <distance>        radius: the maximum distance to the --point, in --unit.
+    --bbox <box>               bbox: the "west,south,east,north" bounding box.
+    --precision <chars>        geohash: the number of characters of the geohashes,
+                               from 1 to 12. [default: 9]
+    --decode                   geohash: decode the geohashes of the <location> column.
+    -c, --new-column <name>    The name of the new column, or the comma-separated names of
+                               the new latitude & longitude columns for decode & parse.
+                               Default: distance, geohash or latitude,longitude.
+    --round <decimals>         Round the distances & decoded coordinates to <decimals> places.
+                               [default: 6]
+    -v, --invert               bbox & radius: only keep the rows outside of the area.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers. Columns are then selected by their
+                               1-based index.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    cmd::stats::round_num,
+    config::{Config, Delimiter},
+    regex_once_cell,
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    cmd_distance:    bool,
+    cmd_bbox:        bool,
+    cmd_radius:      bool,
+    cmd_geohash:     bool,
+    cmd_parse:       bool,
+    arg_location:    SelectColumns,
+    arg_input:       Option<String>,
+    flag_to:         Option<SelectColumns>,
+    flag_point:      Option<String>,
+    flag_method:     String,
+    flag_unit:       String,
+    flag_radius:     Option<f64>,
+    flag_bbox:       Option<String>,
+    flag_precision:  usize,
+    flag_decode:     bool,
+    flag_new_column: Option<String>,
+    flag_round:      u8,
+    flag_invert:     bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+// the mean radius of the Earth, in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+// the WGS84 ellipsoid semi-major axis, in meters, & flattening
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+const GEOHASH_BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Clone, Copy)]
+struct Point {
+    lat: f64,
+    lon: f64,
+}
+
+impl Point {
+    fn new(lat: f64, lon: f64) -> Option<Point> {
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Some(Point { lat, lon })
+        } else {
+            None
+        }
+    }
+
+    /// parses "latitude, longitude" & WKT "POINT (longitude latitude)" locations
+    fn parse(s: &str) -> Option<Point> {
+        let wkt_re: &'static Regex = regex_once_cell!(
+            r"(?i)^\s*POINT\s*\(\s*([+-]?[0-9.]+(?:e[+-]?[0-9]+)?)\s+([+-]?[0-9.]+(?:e[+-]?[0-9]+)?)\s*\)\s*$"
+        );
+        let latlon_re: &'static Regex = regex_once_cell!(
+            r"^\s*\(?\s*([+-]?[0-9]+\.?[0-9]*|[+-]?\.[0-9]+)\s*,\s*([+-]?[0-9]+\.?[0-9]*|[+-]?\.[0-9]+)\s*\)?\s*$"
+        );
+        if let Some(caps) = wkt_re.captures(s) {
+            return Point::new(caps[2].parse().ok()?, caps[1].parse().ok()?);
+        }
+        let caps = latlon_re.captures(s)?;
+        Point::new(caps[1].parse().ok()?, caps[2].parse().ok()?)
+    }
+}
+
+/// the columns of a location
+enum Location {
+    Single(usize),
+    Pair(usize, usize),
+}
+
+impl Location {
+    fn new(select: SelectColumns, headers: &csv::ByteRecord, no_headers: bool) -> CliResult<Self> {
+        let sel = select.selection(headers, !no_headers)?;
+        match *sel {
+            [column] => Ok(Location::Single(column)),
+            [lat, lon] => Ok(Location::Pair(lat, lon)),
+            _ => fail!(
+                "A location must select either one column, or the latitude & longitude columns."
+            ),
+        }
+    }
+
+    fn point(&self, record: &csv::StringRecord) -> Option<Point> {
+        match self {
+            Location::Single(i) => Point::parse(record.get(*i)?),
+            Location::Pair(lat, lon) => Point::new(
+                record.get(*lat)?.trim().parse().ok()?,
+                record.get(*lon)?.trim().parse().ok()?,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Method {
+    Haversine,
+    Vincenty,
+}
+
+impl Method {
+    /// the distance between the points, in meters
+    fn distance(self, from: Point, to: Point) -> f64 {
+        match self {
+            Method::Haversine => haversine(from, to),
+            // Vincenty's formula doesn't converge for nearly antipodal points
+            Method::Vincenty => vincenty(from, to).unwrap_or_else(|| haversine(from, to)),
+        }
+    }
+}
+
+/// a "west,south,east,north" bounding box
+struct BBox {
+    west:  f64,
+    south: f64,
+    east:  f64,
+    north: f64,
+}
+
+impl BBox {
+    fn parse(s: &str) -> Option<BBox> {
+        let bounds: Vec<f64> = s
+            .split(',')
+            .map(|bound| bound.trim().parse::<f64>().ok())
+            .collect::<Option<_>>()?;
+        let [west, south, east, north] = bounds[..] else {
+            return None;
+        };
+        let valid_lon = |lon: f64| (-180.0..=180.0).contains(&lon);
+        let valid_lat = |lat: f64| (-90.0..=90.0).contains(&lat);
+        if !(valid_lon(west) && valid_lon(east) && valid_lat(south) && valid_lat(north))
+            || south > north
+        {
+            return None;
+        }
+        Some(BBox {
+            west,
+            south,
+            east,
+            north,
+        })
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let in_lon = if self.west <= self.east {
+            self.west <= point.lon && point.lon <= self.east
+        } else {
+            // the box crosses the antimeridian
+            self.west <= point.lon || point.lon <= self.east
+        };
+        in_lon && self.south <= point.lat && point.lat <= self.north
+    }
+}
+
+/// what to compute for each row
+enum Task {
+    Distance(Option<Location>, Option<Point>),
+    BBox(BBox),
+    Radius(Point, f64),
+    Geohash(usize),
+    Decode,
+    Parse,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let unit = match args.flag_unit.to_lowercase().as_str() {
+        "m" => 1.0,
+        "km" => 1_000.0,
+        "mi" => 1_609.344,
+        "nmi" => 1_852.0,
+        _ => return fail_clierror!("Invalid --unit: {}. Use km, m, mi or nmi.", args.flag_unit),
+    };
+    let method = match args.flag_method.to_lowercase().as_str() {
+        "haversine" => Method::Haversine,
+        "vincenty" => Method::Vincenty,
+        _ => {
+            return fail_clierror!(
+                "Invalid --method: {}. Use haversine or vincenty.",
+                args.flag_method
+            )
+        }
+    };
+    let point = match args.flag_point {
+        Some(ref point) => match Point::parse(point) {
+            Some(point) => Some(point),
+            None => {
+                return fail_clierror!(
+                    "Invalid --point: {point}. Use the \"latitude, longitude\" format."
+                )
+            }
+        },
+        None => None,
+    };
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let byte_headers = rdr.byte_headers()?.clone();
+
+    let location = Location::new(args.arg_location, &byte_headers, rconfig.no_headers)?;
+    let task = if args.cmd_distance {
+        let to = match args.flag_to {
+            Some(to) => Some(Location::new(to, &byte_headers, rconfig.no_headers)?),
+            None => None,
+        };
+        Task::Distance(to, point)
+    } else if args.cmd_bbox {
+        let bbox = args.flag_bbox.unwrap_or_default();
+        match BBox::parse(&bbox) {
+            Some(bbox) => Task::BBox(bbox),
+            None => {
+                return fail_clierror!(
+                    "Invalid --bbox: {bbox}. Use the \"west,south,east,north\" format."
+                )
+            }
+        }
+    } else if args.cmd_radius {
+        match (point, args.flag_radius) {
+            (Some(point), Some(radius)) if radius >= 0.0 => Task::Radius(point, radius * unit),
+            _ => return fail!("--radius must be a positive distance."),
+        }
+    } else if args.cmd_geohash {
+        if args.flag_decode {
+            if matches!(location, Location::Pair(..)) {
+                return fail!("--decode requires a single geohash column.");
+            }
+            Task::Decode
+        } else if (1..=12).contains(&args.flag_precision) {
+            Task::Geohash(args.flag_precision)
+        } else {
+            return fail!("--precision must be between 1 and 12.");
+        }
+    } else if args.cmd_parse {
+        Task::Parse
+    } else {
+        unreachable!();
+    };
+
+    let new_columns: Vec<String> = match task {
+        Task::BBox(_) | Task::Radius(..) => Vec::new(),
+        Task::Distance(..) | Task::Geohash(_) => vec![args.flag_new_column.unwrap_or_else(|| {
+            if args.cmd_distance {
+                "distance".to_string()
+            } else {
+                "geohash".to_string()
+            }
+        })],
+        Task::Decode | Task::Parse => {
+            let names = args
+                .flag_new_column
+                .unwrap_or_else(|| "latitude,longitude".to_string());
+            let names: Vec<String> = names.split(',').map(|s| s.trim().to_string()).collect();
+            if names.len() != 2 {
+                return fail!(
+                    "--new-column must be the names of the latitude & longitude columns, e.g. \
+                     lat,lon"
+                );
+            }
+            names
+        }
+    };
+
+    if !rconfig.no_headers {
+        let mut headers = rdr.headers()?.clone();
+        for name in &new_columns {
+            headers.push_field(name);
+        }
+        wtr.write_record(&headers)?;
+    }
+
+    let round = |value: f64| round_num(value, args.flag_round);
+    let mut record = csv::StringRecord::new();
+    while rdr.read_record(&mut record)? {
+        match task {
+            Task::Distance(ref to, point) => {
+                let to = match to {
+                    Some(to) => to.point(&record),
+                    None => point,
+                };
+                let distance = match (location.point(&record), to) {
+                    (Some(from), Some(to)) => round(method.distance(from, to) / unit),
+                    _ => String::new(),
+                };
+                record.push_field(&distance);
+            }
+            Task::BBox(ref bbox) => {
+                let inside = location.point(&record).map_or(false, |p| bbox.contains(p));
+                if inside == args.flag_invert {
+                    continue;
+                }
+            }
+            Task::Radius(point, radius) => {
+                let inside = location
+                    .point(&record)
+                    .map_or(false, |p| method.distance(p, point) <= radius);
+                if inside == args.flag_invert {
+                    continue;
+                }
+            }
+            Task::Geohash(precision) => {
+                let geohash = location
+                    .point(&record)
+                    .map(|point| geohash_encode(point, precision))
+                    .unwrap_or_default();
+                record.push_field(&geohash);
+            }
+            Task::Decode => {
+                let center = match location {
+                    Location::Single(i) => geohash_decode(&record[i]),
+                    Location::Pair(..) => None,
+                };
+                let (lat, lon) = center
+                    .map(|point| (round(point.lat), round(point.lon)))
+                    .unwrap_or_default();
+                record.push_field(&lat);
+                record.push_field(&lon);
+            }
+            Task::Parse => {
+                let (lat, lon) = location
+                    .point(&record)
+                    .map(|point| (point.lat.to_string(), point.lon.to_string()))
+                    .unwrap_or_default();
+                record.push_field(&lat);
+                record.push_field(&lon);
+            }
+        }
+        wtr.write_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
+
+fn haversine(from: Point, to: Point) -> f64 {
+    let (lat1, lat2) = (from.lat.to_radians(), to.lat.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (to.lon - from.lon).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// the inverse Vincenty formula, None when it doesn't converge
+#[allow(clippy::many_single_char_names)]
+fn vincenty(from: Point, to: Point) -> Option<f64> {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = (to.lon - from.lon).to_radians();
+    let u1 = ((1.0 - WGS84_F) * from.lat.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * to.lat.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // coincident points
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha.powi(2);
+        // on the equator, cos2_alpha is 0
+        let cos_2sigma_m = if cos2_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        };
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+        let previous_lambda = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+        if (lambda - previous_lambda).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+            let a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let b_coef = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = b_coef
+                * sin_sigma
+                * (cos_2sigma_m
+                    + b_coef / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - b_coef / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(b * a * (sigma - delta_sigma));
+        }
+    }
+    None
+}
+
+fn geohash_encode(point: Point, precision: usize) -> String {
+    let mut lat_range = [-90.0, 90.0];
+    let mut lon_range = [-180.0, 180.0];
+    let mut geohash = String::with_capacity(precision);
+    // the bits alternate between longitude & latitude, starting with longitude
+    let mut is_lon = true;
+    let mut bits = 0;
+    let mut index = 0;
+    while geohash.len() < precision {
+        let (range, value) = if is_lon {
+            (&mut lon_range, point.lon)
+        } else {
+            (&mut lat_range, point.lat)
+        };
+        let mid = (range[0] + range[1]) / 2.0;
+        index <<= 1;
+        if value >= mid {
+            index |= 1;
+            range[0] = mid;
+        } else {
+            range[1] = mid;
+        }
+        is_lon = !is_lon;
+        bits += 1;
+        if bits == 5 {
+            geohash.push(GEOHASH_BASE32[index] as char);
+            bits = 0;
+            index = 0;
+        }
+    }
+    geohash
+}
+
+/// the center of the geohash cell
+fn geohash_decode(geohash: &str) -> Option<Point> {
+    let geohash = geohash.trim().to_ascii_lowercase();
+    if geohash.is_empty() {
+        return None;
+    }
+    let mut lat_range = [-90.0, 90.0];
+    let mut lon_range = [-180.0, 180.0];
+    let mut is_lon = true;
+    for c in geohash.bytes() {
+        let index = GEOHASH_BASE32.iter().position(|b| *b == c)?;
+        for shift in (0..5).rev() {
+            let range = if is_lon {
+                &mut lon_range
+            } else {
+                &mut lat_range
+            };
+            let mid = (range[0] + range[1]) / 2.0;
+            if (index >> shift) & 1 == 1 {
+                range[0] = mid;
+            } else {
+                range[1] = mid;
+            }
+            is_lon = !is_lon;
+        }
+    }
+    Point::new(
+        (lat_range[0] + lat_range[1]) / 2.0,
+        (lon_range[0] + lon_range[1]) / 2.0,
+    )
+}
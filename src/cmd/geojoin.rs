@@ -0,0 +1,413 @@
+static USAGE: &str = r#"
+Assigns each row of a CSV to the polygon of a GeoJSON file containing its WGS84 location
+(e.g. a district, a census tract or a custom zone), and appends the properties of the
+polygon to the row.
+
+The location of a row is either in a single column, in "latitude, longitude" format
+(as with `apply geocode`), set with --location, or in separate --lat & --lon columns.
+
+The GeoJSON file is a FeatureCollection (or a single Feature) of Polygon &
+MultiPolygon features. Holes of polygons are excluded, & features with other
+geometry types are ignored. Shapefiles can be converted to GeoJSON with
+`ogr2ogr -f GeoJSON -t_srs EPSG:4326 zones.geojson zones.shp`.
+
+The features are put in a grid spatial index of their bounding boxes, so each row is
+only tested against the few polygons near its location. When several polygons contain a
+location, the first one in the file is used.
+
+The properties to append are set with --properties, or are all the properties of the
+features otherwise. Rows with no containing polygon, or without a valid location, get
+empty properties - or are dropped, with --inner.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_geojoin.rs.
+
+Usage:
+    qsv geojoin [options] <geojson> [<input>]
+    qsv geojoin --help
+
+geojoin options:
+    -l, --location <column>    The column with the "latitude, longitude" locations.
+    --lat <column>             The latitude column. Requires --lon.
+    --lon <column>             The longitude column. Requires --lat.
+    -P, --properties <list>    The comma-separated feature properties to append.
+                               Default: all the properties of the features.
+    --prefix <string>          Prefix the names of the appended columns with <string>.
+    -i, --inner                Only output the rows located in a polygon.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers. Columns are then selected by their
+                               1-based index.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::fs;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_geojson:     String,
+    arg_input:       Option<String>,
+    flag_location:   Option<SelectColumns>,
+    flag_lat:        Option<SelectColumns>,
+    flag_lon:        Option<SelectColumns>,
+    flag_properties: Option<String>,
+    flag_prefix:     Option<String>,
+    flag_inner:      bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+// a ring of [longitude, latitude] points
+type Ring = Vec<[f64; 2]>;
+
+/// a polygon, with its exterior ring first & its holes after
+struct Polygon {
+    rings: Vec<Ring>,
+}
+
+impl Polygon {
+    fn contains(&self, point: [f64; 2]) -> bool {
+        let Some((exterior, holes)) = self.rings.split_first() else {
+            return false;
+        };
+        ring_contains(exterior, point) && !holes.iter().any(|hole| ring_contains(hole, point))
+    }
+}
+
+/// even-odd ray casting test of the point in the ring
+fn ring_contains(ring: &Ring, [x, y]: [f64; 2]) -> bool {
+    let Some(&last) = ring.last() else {
+        return false;
+    };
+    let mut inside = false;
+    let mut previous = last;
+    for &[xi, yi] in ring {
+        let [xj, yj] = previous;
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        previous = [xi, yi];
+    }
+    inside
+}
+
+#[derive(Clone, Copy)]
+struct BBox {
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+impl BBox {
+    fn contains(&self, [x, y]: [f64; 2]) -> bool {
+        x >= self.min[0] && x <= self.max[0] && y >= self.min[1] && y <= self.max[1]
+    }
+
+    fn extend(&mut self, other: &BBox) {
+        self.min = [self.min[0].min(other.min[0]), self.min[1].min(other.min[1])];
+        self.max = [self.max[0].max(other.max[0]), self.max[1].max(other.max[1])];
+    }
+}
+
+struct Feature {
+    polygons:   Vec<Polygon>,
+    bbox:       BBox,
+    properties: serde_json::Map<String, Value>,
+}
+
+impl Feature {
+    fn contains(&self, point: [f64; 2]) -> bool {
+        self.bbox.contains(point) && self.polygons.iter().any(|polygon| polygon.contains(point))
+    }
+}
+
+/// a uniform grid over the extent of the features, with the features whose
+/// bounding box overlaps each cell, in file order
+struct GridIndex {
+    extent: BBox,
+    size:   usize,
+    cells:  Vec<Vec<usize>>,
+}
+
+impl GridIndex {
+    fn new(features: &[Feature]) -> GridIndex {
+        let mut extent = match features.first() {
+            Some(feature) => feature.bbox,
+            None => BBox {
+                min: [0.0, 0.0],
+                max: [0.0, 0.0],
+            },
+        };
+        for feature in features {
+            extent.extend(&feature.bbox);
+        }
+        // about one feature per cell, with at most 256 x 256 cells
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let size = ((features.len() as f64).sqrt().ceil() as usize).clamp(1, 256);
+        let mut index = GridIndex {
+            extent,
+            size,
+            cells: vec![Vec::new(); size * size],
+        };
+        for (i, feature) in features.iter().enumerate() {
+            let (min_col, min_row) = index.cell(feature.bbox.min);
+            let (max_col, max_row) = index.cell(feature.bbox.max);
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    index.cells[row * size + col].push(i);
+                }
+            }
+        }
+        index
+    }
+
+    fn cell(&self, [x, y]: [f64; 2]) -> (usize, usize) {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let to_cell = |value: f64, min: f64, max: f64| {
+            if max <= min {
+                0
+            } else {
+                (((value - min) / (max - min) * self.size as f64) as usize).min(self.size - 1)
+            }
+        };
+        (
+            to_cell(x, self.extent.min[0], self.extent.max[0]),
+            to_cell(y, self.extent.min[1], self.extent.max[1]),
+        )
+    }
+
+    fn candidates(&self, point: [f64; 2]) -> &[usize] {
+        if !self.extent.contains(point) {
+            return &[];
+        }
+        let (col, row) = self.cell(point);
+        &self.cells[row * self.size + col]
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let features = load_features(&args.arg_geojson)?;
+    let index = GridIndex::new(&features);
+
+    let property_names: Vec<String> = match args.flag_properties {
+        Some(ref properties) => properties
+            .split(',')
+            .map(|property| property.trim().to_string())
+            .collect(),
+        None => {
+            let mut names: Vec<String> = Vec::new();
+            for feature in &features {
+                for name in feature.properties.keys() {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+            }
+            names
+        }
+    };
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let column_index = |select: SelectColumns| -> CliResult<usize> {
+        let sel = select.selection(&headers, !rconfig.no_headers)?;
+        if sel.len() != 1 {
+            return fail!("The location columns must each select a single column.");
+        }
+        Ok(sel[0])
+    };
+    let location = match (args.flag_location, args.flag_lat, args.flag_lon) {
+        (Some(location), None, None) => Location::Single(column_index(location)?),
+        (None, Some(lat), Some(lon)) => Location::Pair(column_index(lat)?, column_index(lon)?),
+        _ => return fail!("Set either the --location column, or the --lat & --lon columns."),
+    };
+
+    if !rconfig.no_headers {
+        let mut headers = rdr.headers()?.clone();
+        let prefix = args.flag_prefix.as_deref().unwrap_or_default();
+        for name in &property_names {
+            headers.push_field(&format!("{prefix}{name}"));
+        }
+        wtr.write_record(&headers)?;
+    }
+
+    let mut record = csv::StringRecord::new();
+    while rdr.read_record(&mut record)? {
+        let feature = location.point(&record).and_then(|point| {
+            index
+                .candidates(point)
+                .iter()
+                .map(|i| &features[*i])
+                .find(|feature| feature.contains(point))
+        });
+        match feature {
+            Some(feature) => {
+                for name in &property_names {
+                    let value = match feature.properties.get(name) {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(Value::Null) | None => String::new(),
+                        Some(value) => value.to_string(),
+                    };
+                    record.push_field(&value);
+                }
+            }
+            None => {
+                if args.flag_inner {
+                    continue;
+                }
+                for _ in &property_names {
+                    record.push_field("");
+                }
+            }
+        }
+        wtr.write_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
+
+enum Location {
+    Single(usize),
+    Pair(usize, usize),
+}
+
+impl Location {
+    /// the [longitude, latitude] point of the record
+    fn point(&self, record: &csv::StringRecord) -> Option<[f64; 2]> {
+        let (lat, lon) = match self {
+            Location::Single(i) => {
+                let (lat, lon) = record.get(*i)?.split_once(',')?;
+                (
+                    lat.trim().parse::<f64>().ok()?,
+                    lon.trim().parse::<f64>().ok()?,
+                )
+            }
+            Location::Pair(lat, lon) => (
+                record.get(*lat)?.trim().parse::<f64>().ok()?,
+                record.get(*lon)?.trim().parse::<f64>().ok()?,
+            ),
+        };
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Some([lon, lat])
+        } else {
+            None
+        }
+    }
+}
+
+fn load_features(path: &str) -> CliResult<Vec<Feature>> {
+    let geojson: Value = match fs::read_to_string(path) {
+        Ok(s) => match serde_json::from_str(&s) {
+            Ok(geojson) => geojson,
+            Err(e) => return fail_clierror!("Unable to parse the GeoJSON file {path}: {e}"),
+        },
+        Err(e) => return fail_clierror!("Unable to read the GeoJSON file {path}: {e}"),
+    };
+    let geojson_features = match geojson["type"].as_str() {
+        Some("FeatureCollection") => match geojson["features"].as_array() {
+            Some(features) => features.clone(),
+            None => return fail_clierror!("The GeoJSON FeatureCollection {path} has no features."),
+        },
+        Some("Feature") => vec![geojson],
+        _ => {
+            return fail_clierror!(
+                "The GeoJSON file {path} is not a FeatureCollection or a Feature."
+            )
+        }
+    };
+
+    let mut features = Vec::with_capacity(geojson_features.len());
+    for feature in geojson_features {
+        let geometry = &feature["geometry"];
+        let polygons = match geometry["type"].as_str() {
+            Some("Polygon") => parse_polygon(&geometry["coordinates"])
+                .into_iter()
+                .collect(),
+            Some("MultiPolygon") => match geometry["coordinates"].as_array() {
+                Some(polygons) => polygons.iter().filter_map(parse_polygon).collect(),
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        let Some(bbox) = bounding_box(&polygons) else {
+            continue;
+        };
+        let properties = match feature.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            _ => serde_json::Map::new(),
+        };
+        features.push(Feature {
+            polygons,
+            bbox,
+            properties,
+        });
+    }
+    if features.is_empty() {
+        return fail_clierror!("The GeoJSON file {path} has no Polygon or MultiPolygon features.");
+    }
+    Ok(features)
+}
+
+fn parse_polygon(coordinates: &Value) -> Option<Polygon> {
+    let rings: Vec<Ring> = coordinates
+        .as_array()?
+        .iter()
+        .map(|ring| {
+            ring.as_array()
+                .map(|points| {
+                    points
+                        .iter()
+                        .filter_map(|point| Some([point[0].as_f64()?, point[1].as_f64()?]))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+    if rings.first().map_or(true, |exterior| exterior.len() < 3) {
+        return None;
+    }
+    Some(Polygon { rings })
+}
+
+/// the bounding box of the exterior rings of the polygons
+fn bounding_box(polygons: &[Polygon]) -> Option<BBox> {
+    let mut points = polygons
+        .iter()
+        .filter_map(|polygon| polygon.rings.first())
+        .flatten();
+    let first = points.next()?;
+    let mut bbox = BBox {
+        min: *first,
+        max: *first,
+    };
+    for point in points {
+        bbox.extend(&BBox {
+            min: *point,
+            max: *point,
+        });
+    }
+    Some(bbox)
+}
@@ -3,10 +3,18 @@ pub mod apply;
 #[cfg(any(feature = "full", feature = "lite"))]
 pub mod behead;
 #[cfg(any(feature = "full", feature = "lite"))]
+pub mod bin;
+#[cfg(any(feature = "full", feature = "lite"))]
 pub mod cat;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod check;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod correlation;
 pub mod count;
 pub mod dedup;
 #[cfg(any(feature = "full", feature = "lite"))]
+pub mod drift;
+#[cfg(any(feature = "full", feature = "lite"))]
 pub mod enumerate;
 pub mod excel;
 pub mod exclude;
@@ -31,6 +39,10 @@ pub mod foreach;
 pub mod frequency;
 #[cfg(all(feature = "generate", not(feature = "lite")))]
 pub mod generate;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod geo;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod geojoin;
 pub mod headers;
 pub mod index;
 pub mod input;
@@ -41,7 +53,13 @@ pub mod jsonl;
 #[cfg(all(feature = "luau", not(feature = "lite")))]
 pub mod luau;
 #[cfg(any(feature = "full", feature = "lite"))]
+pub mod outliers;
+#[cfg(any(feature = "full", feature = "lite"))]
 pub mod partition;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod piiscan;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod profile;
 pub mod pseudo;
 #[cfg(all(feature = "python", not(feature = "lite")))]
 pub mod python;
@@ -53,6 +71,8 @@ pub mod safenames;
 pub mod sample;
 #[cfg(any(feature = "full", feature = "lite"))]
 pub mod schema;
+#[cfg(any(feature = "full", feature = "lite"))]
+pub mod schemadiff;
 pub mod search;
 pub mod searchset;
 pub mod select;
@@ -0,0 +1,382 @@
+static USAGE: &str = r#"
+Flags or filters the rows with outliers in the selected numeric columns.
+
+A value is an outlier when it's outside the bounds of its column, determined with one of
+these methods:
+  * iqr (the default) - the inner fences (q1 - 1.5 * IQR & q3 + 1.5 * IQR), or the outer
+    fences (q1 - 3 * IQR & q3 + 3 * IQR) with --fence outer, as `qsv stats --quartiles`
+    computes them.
+  * zscore - the mean -/+ --threshold standard deviations (default: 3).
+  * mad - the values whose modified z-score, based on the median absolute deviation (MAD),
+    is beyond --threshold (default: 3.5), as recommended by Iglewicz & Hoaglin.
+    When more than half of the values of a column are the same, its MAD is 0, so all
+    its other values are outliers.
+
+For each selected numeric column, a "<column>_outlier" column is added, which is "low" or
+"high" for outliers, and empty otherwise. With --filter, only the rows with at least one
+outlier are output (or only the rows without any outlier with --invert). Non-numeric columns
+are ignored.
+
+A summary of the outliers of each column is written to stderr, unless --quiet is set.
+
+The iqr & zscore methods take the stats from the stats cache if there is a valid one with
+them (see `qsv stats --help`), so the input is not scanned an extra time. As the input is
+read more than once otherwise, it cannot be stdin.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_outliers.rs.
+
+Usage:
+    qsv outliers [options] [<input>]
+    qsv outliers --help
+
+outliers options:
+    -s, --select <arg>     Select the columns to check for outliers.
+                           See 'qsv select --help' for the format details.
+    -m, --method <arg>     The method to find outliers with: "iqr", "zscore" or "mad".
+                           [default: iqr]
+    --fence <arg>          The fences of the iqr method: "inner" or "outer".
+                           [default: inner]
+    --threshold <arg>      The z-score threshold of the zscore & mad methods.
+                           Defaults to 3 for zscore & 3.5 for mad.
+    -f, --filter           Only output the rows with at least one outlier.
+    -v, --invert           With --filter, only output the rows without outliers.
+    -q, --quiet            Do not write the summary to stderr.
+    -j, --jobs <arg>       The number of jobs to run in parallel when computing
+                           the stats of an indexed input.
+                           When not set, the number of jobs is set to the
+                           number of CPUs detected.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::str;
+
+use serde::Deserialize;
+use stats::Commute;
+
+use crate::{
+    cmd::stats::{
+        round_num, FieldType,
+        FieldType::{TFloat, TInteger, TNull},
+        StatsCache,
+    },
+    config::{Config, Delimiter},
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_select:     SelectColumns,
+    flag_method:     Method,
+    flag_fence:      Fence,
+    flag_threshold:  Option<f64>,
+    flag_filter:     bool,
+    flag_invert:     bool,
+    flag_quiet:      bool,
+    flag_jobs:       Option<usize>,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Method {
+    Iqr,
+    Zscore,
+    Mad,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Fence {
+    Inner,
+    Outer,
+}
+
+/// the bounds outside of which the values of a numeric column are outliers
+struct Bounds {
+    lower: f64,
+    upper: f64,
+}
+
+impl Bounds {
+    #[inline]
+    fn check(&self, field: &[u8]) -> Option<&'static str> {
+        let value = str::from_utf8(field).ok()?.trim().parse::<f64>().ok()?;
+        if value < self.lower {
+            Some("low")
+        } else if value > self.upper {
+            Some("high")
+        } else {
+            None
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.arg_input.as_deref().map_or(true, |input| input == "-") {
+        return fail!("qsv outliers cannot read from stdin, as it reads its input more than once.");
+    }
+    if args.flag_invert && !args.flag_filter {
+        return fail!("--invert can only be used with --filter.");
+    }
+
+    let rconfig = args.rconfig();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let names: Vec<String> = sel
+        .iter()
+        .map(|&col| {
+            if rconfig.no_headers {
+                (col + 1).to_string()
+            } else {
+                String::from_utf8_lossy(&headers[col]).to_string()
+            }
+        })
+        .collect();
+
+    // the bounds of the numeric columns, in selection order
+    let bounds = match args.flag_method {
+        Method::Iqr | Method::Zscore => args.stats_bounds(&sel)?,
+        Method::Mad => args.mad_bounds(&sel)?,
+    };
+    if bounds.iter().all(Option::is_none) {
+        return fail!("None of the selected columns are numeric.");
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let mut headers = headers.clone();
+        for (name, _) in names.iter().zip(bounds.iter()).filter(|(_, b)| b.is_some()) {
+            headers.push_field(format!("{name}_outlier").as_bytes());
+        }
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let mut low_counts = vec![0_u64; sel.len()];
+    let mut high_counts = vec![0_u64; sel.len()];
+    let mut flags: Vec<&str> = Vec::with_capacity(sel.len());
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        flags.clear();
+        let mut has_outlier = false;
+        for (i, (field, bounds)) in sel.select(&record).zip(bounds.iter()).enumerate() {
+            let Some(bounds) = bounds else { continue };
+            let flag = bounds.check(field);
+            match flag {
+                Some("low") => low_counts[i] += 1,
+                Some(_) => high_counts[i] += 1,
+                None => {}
+            }
+            has_outlier |= flag.is_some();
+            flags.push(flag.unwrap_or_default());
+        }
+        if args.flag_filter && has_outlier == args.flag_invert {
+            continue;
+        }
+        for flag in &flags {
+            record.push_field(flag.as_bytes());
+        }
+        wtr.write_byte_record(&record)?;
+    }
+    wtr.flush()?;
+
+    if !args.flag_quiet {
+        for (i, name) in names.iter().enumerate() {
+            let Some(ref bounds) = bounds[i] else {
+                continue;
+            };
+            winfo!(
+                "{name}: {} outliers ({} low, {} high) outside [{}, {}]",
+                low_counts[i] + high_counts[i],
+                low_counts[i],
+                high_counts[i],
+                round_num(bounds.lower, 4),
+                round_num(bounds.upper, 4)
+            );
+        }
+    }
+    Ok(())
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.flag_select.clone())
+    }
+
+    /// the stats columns of the lower & upper bounds of the iqr & zscore methods
+    fn bound_stats(&self) -> [&'static str; 2] {
+        match (self.flag_method, self.flag_fence) {
+            (Method::Iqr, Fence::Inner) => ["lower_inner_fence", "upper_inner_fence"],
+            (Method::Iqr, Fence::Outer) => ["lower_outer_fence", "upper_outer_fence"],
+            _ => ["mean", "stddev"],
+        }
+    }
+
+    /// computes the bounds of the iqr & zscore methods from the stats of the selected
+    /// columns, taken from the stats cache if there is a valid one with them
+    fn stats_bounds(&self, sel: &Selection) -> CliResult<Vec<Option<Bounds>>> {
+        let [lower_stat, upper_stat] = self.bound_stats();
+        let (records, stat_names) = match self.cached_stats(sel) {
+            Some(cached) => cached,
+            None => self.compute_stats()?,
+        };
+        let stat_index = |stat: &str| stat_names.iter().position(|name| name == stat);
+        let (Some(type_idx), Some(lower_idx), Some(upper_idx)) = (
+            stat_index("type"),
+            stat_index(lower_stat),
+            stat_index(upper_stat),
+        ) else {
+            return fail!("Cannot find the stats needed to find outliers.");
+        };
+
+        let threshold = self.flag_threshold.unwrap_or(3.0);
+        Ok(records
+            .iter()
+            .map(|record| {
+                if !matches!(record.get(type_idx), Some("Integer" | "Float")) {
+                    return None;
+                }
+                let lower = record.get(lower_idx)?.parse::<f64>().ok()?;
+                let upper = record.get(upper_idx)?.parse::<f64>().ok()?;
+                Some(if self.flag_method == Method::Zscore {
+                    // lower is the mean & upper the standard deviation
+                    Bounds {
+                        lower: threshold.mul_add(-upper, lower),
+                        upper: threshold.mul_add(upper, lower),
+                    }
+                } else {
+                    Bounds { lower, upper }
+                })
+            })
+            .collect())
+    }
+
+    /// the stats records of the selected columns & the names of their stats,
+    /// from the stats cache if it has the stats needed
+    fn cached_stats(&self, sel: &Selection) -> Option<(Vec<csv::StringRecord>, Vec<String>)> {
+        let rconfig = self.rconfig();
+        let cache = StatsCache::load(&self.arg_input)?;
+        if !cache.same_dialect(rconfig.no_headers, rconfig.get_delimiter())
+            || self
+                .bound_stats()
+                .iter()
+                .any(|stat| cache.stat_index(stat).is_none())
+        {
+            return None;
+        }
+        log::info!("using stats cache");
+        let records = cache.records();
+        let records = sel
+            .iter()
+            .map(|&col| records.get(col).cloned())
+            .collect::<Option<_>>()?;
+        // the cached stats records don't have the leading "field" column
+        let stat_names = cache.headers[1..].to_vec();
+        Some((records, stat_names))
+    }
+
+    fn compute_stats(&self) -> CliResult<(Vec<csv::StringRecord>, Vec<String>)> {
+        let stats_args = crate::cmd::stats::Args {
+            arg_input:            self.arg_input.clone(),
+            flag_select:          self.flag_select.clone(),
+            flag_groupby:         None,
+            flag_everything:      false,
+            flag_mode:            false,
+            flag_cardinality:     false,
+            flag_median:          false,
+            flag_quartiles:       self.flag_method == Method::Iqr,
+            flag_nulls:           false,
+            flag_round:           10,
+            flag_infer_dates:     false,
+            flag_dates_whitelist: String::new(),
+            flag_prefer_dmy:      false,
+            flag_cache:           false,
+            flag_jobs:            Some(util::njobs(self.flag_jobs)),
+            flag_output:          None,
+            flag_no_headers:      self.flag_no_headers,
+            flag_delimiter:       self.flag_delimiter,
+        };
+        let (_, stats) = match stats_args.rconfig().indexed()? {
+            Some(idx) if util::njobs(self.flag_jobs) > 1 => {
+                stats_args.parallel_stats(&stats_args.flag_dates_whitelist, &idx)
+            }
+            _ => stats_args.sequential_stats(&stats_args.flag_dates_whitelist),
+        }?;
+
+        // the stats records don't have the leading "field" column
+        let stat_names = stats_args
+            .stat_headers()
+            .iter()
+            .skip(1)
+            .map(String::from)
+            .collect();
+        Ok((stats_args.stats_to_records(stats), stat_names))
+    }
+
+    /// computes the bounds of the mad method from the median & the median absolute
+    /// deviation of the values of each numeric column
+    fn mad_bounds(&self, sel: &Selection) -> CliResult<Vec<Option<Bounds>>> {
+        let mut types = vec![TNull; sel.len()];
+        let mut values: Vec<Vec<f64>> = vec![Vec::new(); sel.len()];
+        let mut rdr = self.rconfig().reader()?;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (i, field) in sel.select(&record).enumerate() {
+                let typ = FieldType::from_sample(false, field);
+                if matches!(typ, TInteger | TFloat) {
+                    if let Ok(value) = unsafe { str::from_utf8_unchecked(field) }.parse::<f64>() {
+                        values[i].push(value);
+                    }
+                }
+                types[i].merge(typ);
+            }
+        }
+
+        // 0.6745 is the 0.75 quantile of the standard normal distribution
+        let threshold = self.flag_threshold.unwrap_or(3.5) / 0.6745;
+        Ok(types
+            .into_iter()
+            .zip(values.into_iter())
+            .map(|(typ, mut values)| {
+                if !matches!(typ, TInteger | TFloat) {
+                    return None;
+                }
+                let median = median(&mut values)?;
+                let mut deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+                let mad = median(&mut deviations)?;
+                Some(Bounds {
+                    lower: threshold.mul_add(-mad, median),
+                    upper: threshold.mul_add(mad, median),
+                })
+            })
+            .collect())
+    }
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
@@ -0,0 +1,446 @@
+static USAGE: &str = r#"
+Scans the columns of a CSV for likely personal data (PII), to find the columns to
+mask or pseudonymise (see the `apply operations` hashing & mask operations, and the
+`pseudo` command) before sharing a dataset.
+
+A non-empty cell is detected as one of these PII types when its whole (trimmed)
+value looks like:
+
+  * email: an email address.
+  * phone: a phone number with 7 to 15 digits, and a leading + or separators
+      (spaces, dots, dashes or parentheses). Bare numbers are too ambiguous to count.
+  * credit_card: a 13 to 19 digit card number, passing the Luhn checksum.
+  * iban: an International Bank Account Number, passing the mod-97 checksum.
+  * national_id: a US Social Security Number or a UK National Insurance number.
+  * ip_address: an IPv4 or IPv6 address.
+
+All the patterns are matched in a single pass with a regex set, then validated.
+
+The report has a row per column & PII type detected:
+
+    column,pii_type,matches,scanned,match_rate,confidence
+
+where scanned is the number of non-empty cells of the column, & match_rate is
+matches / scanned. The confidence, between 0.0 & 1.0, is the match rate weighted by
+how specific the PII type pattern is (e.g. a checksum-validated card number is more
+telling than a phone number), plus a bonus when a word of the column name hints at the
+PII type (e.g. "email", "phone", "ssn"...). Words are split on punctuation, spaces &
+camelCase, so "client_ip" & "clientIp" hint at IP addresses, but "zip" doesn't.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_piiscan.rs.
+
+Usage:
+    qsv piiscan [options] [<input>]
+    qsv piiscan --help
+
+piiscan options:
+    -s, --select <arg>     Select the columns to scan. See 'qsv select -h'
+                           for the full syntax.
+    --sample <size>        Only scan a random sample of <size> rows, picked
+                           with reservoir sampling.
+    --min-rate <rate>      Only report the PII types detected in at least this
+                           rate of the scanned cells of a column. [default: 0.0]
+    --json                 Output the report as JSON.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. Columns are then reported by their
+                           1-based index.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    io::Write,
+    net::{Ipv4Addr, Ipv6Addr},
+    str,
+};
+
+use rand::Rng;
+use regex::bytes::{RegexSet, RegexSetBuilder};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_select:     SelectColumns,
+    flag_sample:     Option<usize>,
+    flag_min_rate:   f64,
+    flag_json:       bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy)]
+enum PiiType {
+    Email,
+    Phone,
+    CreditCard,
+    Iban,
+    NationalId,
+    IpAddress,
+}
+
+const PII_TYPES: [PiiType; 6] = [
+    PiiType::Email,
+    PiiType::Phone,
+    PiiType::CreditCard,
+    PiiType::Iban,
+    PiiType::NationalId,
+    PiiType::IpAddress,
+];
+
+// the regex set patterns, each with the index of its PII type in PII_TYPES
+const PATTERNS: [(&str, usize); 8] = [
+    (
+        r"^[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$",
+        0,
+    ),
+    (r"^\+?[0-9(][0-9 ().-]{5,20}[0-9]$", 1),
+    (r"^([0-9][ -]?){12,18}[0-9]$", 2),
+    (r"^[A-Z]{2}[0-9]{2}( ?[A-Z0-9]){11,30}$", 3),
+    (r"^[0-9]{3}-[0-9]{2}-[0-9]{4}$", 4),
+    (
+        r"^[A-CEGHJ-PR-TW-Z]{2} ?[0-9]{2} ?[0-9]{2} ?[0-9]{2} ?[A-D]$",
+        4,
+    ),
+    (r"^[0-9]{1,3}(\.[0-9]{1,3}){3}$", 5),
+    (r"^[0-9A-Fa-f:.]*:[0-9A-Fa-f:.]*$", 5),
+];
+
+impl PiiType {
+    const fn name(self) -> &'static str {
+        match self {
+            PiiType::Email => "email",
+            PiiType::Phone => "phone",
+            PiiType::CreditCard => "credit_card",
+            PiiType::Iban => "iban",
+            PiiType::NationalId => "national_id",
+            PiiType::IpAddress => "ip_address",
+        }
+    }
+
+    /// how telling a match is - checksum-validated & structured patterns weigh more
+    const fn weight(self) -> f64 {
+        match self {
+            PiiType::Email | PiiType::CreditCard | PiiType::Iban => 1.0,
+            PiiType::NationalId | PiiType::IpAddress => 0.9,
+            PiiType::Phone => 0.7,
+        }
+    }
+
+    /// whether a word of the column name hints at the PII type
+    fn hinted_by(self, column_name: &str) -> bool {
+        let hints: &[&str] = match self {
+            PiiType::Email => &["email", "mail"],
+            PiiType::Phone => &["phone", "telephone", "mobile", "tel", "fax", "cell"],
+            PiiType::CreditCard => &["card", "creditcard", "ccn", "pan"],
+            PiiType::Iban => &["iban", "account", "bank"],
+            PiiType::NationalId => &["ssn", "social", "national", "nino", "insurance", "tax"],
+            PiiType::IpAddress => &["ip", "ipv4", "ipv6", "ipaddr", "host"],
+        };
+        name_words(column_name)
+            .iter()
+            .any(|word| hints.contains(&word.as_str()))
+    }
+
+    /// validate a cell matched by the PII type pattern
+    fn is_valid(self, cell: &str) -> bool {
+        match self {
+            PiiType::Email => true,
+            PiiType::Phone => {
+                let digits = cell.bytes().filter(u8::is_ascii_digit).count();
+                // numbers, dates, IPv4 addresses & SSNs have the same shapes as some phones
+                (7..=15).contains(&digits)
+                    && (cell.starts_with('+') || cell.bytes().any(|b| !b.is_ascii_digit()))
+                    && cell.parse::<f64>().is_err()
+                    && cell.parse::<Ipv4Addr>().is_err()
+                    && !has_shape(cell, "9999-99-99")
+                    && !has_shape(cell, "999-99-9999")
+            }
+            PiiType::CreditCard => {
+                let digits: Vec<u8> = cell
+                    .bytes()
+                    .filter(u8::is_ascii_digit)
+                    .map(|b| b - b'0')
+                    .collect();
+                (13..=19).contains(&digits.len()) && luhn(&digits)
+            }
+            PiiType::Iban => iban_checksum(cell),
+            PiiType::NationalId => {
+                // SSN area numbers 000, 666 & 900-999 are never assigned
+                !(cell.starts_with("000") || cell.starts_with("666") || cell.starts_with('9'))
+                    || cell.as_bytes()[0].is_ascii_alphabetic()
+            }
+            PiiType::IpAddress => {
+                cell.parse::<Ipv4Addr>().is_ok() || cell.parse::<Ipv6Addr>().is_ok()
+            }
+        }
+    }
+}
+
+/// the lowercase words of a column name, split on non-alphanumeric characters & on
+/// camelCase boundaries (e.g. "clientIPAddr_v2" has the words client, ipaddr & v2)
+fn name_words(column_name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lowercase = false;
+    for c in column_name.chars() {
+        if !c.is_alphanumeric() || (prev_lowercase && c.is_uppercase()) {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+        prev_lowercase = c.is_lowercase();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// whether the cell has the shape, with 9 standing for any digit
+fn has_shape(cell: &str, shape: &str) -> bool {
+    cell.len() == shape.len()
+        && cell.bytes().zip(shape.bytes()).all(|(c, s)| {
+            if s == b'9' {
+                c.is_ascii_digit()
+            } else {
+                c == s
+            }
+        })
+}
+
+fn luhn(digits: &[u8]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| {
+            let digit = u32::from(*digit);
+            if i % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                digit
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn iban_checksum(cell: &str) -> bool {
+    let iban: Vec<u8> = cell.bytes().filter(|b| *b != b' ').collect();
+    if iban.len() < 15 || iban.len() > 34 {
+        return false;
+    }
+    // move the country code & check digits to the end, with letters as 10 to 35
+    let mut remainder = 0_u32;
+    for b in iban[4..].iter().chain(&iban[..4]) {
+        let value = match b {
+            b'0'..=b'9' => u32::from(b - b'0'),
+            b'A'..=b'Z' => u32::from(b - b'A') + 10,
+            _ => return false,
+        };
+        remainder = if value > 9 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+#[derive(Default, Clone)]
+struct ColumnScan {
+    scanned: u64,
+    matches: [u64; 6],
+}
+
+impl ColumnScan {
+    fn add(&mut self, cell: &[u8], pattern: &RegexSet) {
+        let Ok(cell) = str::from_utf8(cell) else {
+            return;
+        };
+        let cell = cell.trim();
+        if cell.is_empty() {
+            return;
+        }
+        self.scanned += 1;
+
+        let mut matched = [false; 6];
+        for i in pattern.matches(cell.as_bytes()).iter() {
+            matched[PATTERNS[i].1] = true;
+        }
+        for (i, pii_type) in PII_TYPES.iter().enumerate() {
+            if matched[i] && pii_type.is_valid(cell) {
+                self.matches[i] += 1;
+            }
+        }
+    }
+}
+
+/// a PII type detected in a column
+struct Detection<'a> {
+    column:     &'a str,
+    pii_type:   PiiType,
+    matches:    u64,
+    scanned:    u64,
+    match_rate: f64,
+    confidence: f64,
+}
+
+impl Detection<'_> {
+    fn to_record(&self) -> Vec<String> {
+        vec![
+            self.column.to_string(),
+            self.pii_type.name().to_string(),
+            self.matches.to_string(),
+            self.scanned.to_string(),
+            self.match_rate.to_string(),
+            self.confidence.to_string(),
+        ]
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "column": self.column,
+            "pii_type": self.pii_type.name(),
+            "matches": self.matches,
+            "scanned": self.scanned,
+            "match_rate": self.match_rate,
+            "confidence": self.confidence,
+        })
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let pattern: RegexSet = RegexSetBuilder::new(PATTERNS.iter().map(|(re, _)| re))
+        .unicode(false)
+        .build()?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .checkutf8(false)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    let column_names: Vec<String> = sel
+        .iter()
+        .map(|i| {
+            if rconfig.no_headers {
+                (i + 1).to_string()
+            } else {
+                String::from_utf8_lossy(&headers[*i]).to_string()
+            }
+        })
+        .collect();
+    let mut scans = vec![ColumnScan::default(); sel.len()];
+
+    if let Some(sample_size) = args.flag_sample {
+        // https://en.wikipedia.org/wiki/Reservoir_sampling
+        let mut reservoir: Vec<csv::ByteRecord> = Vec::with_capacity(sample_size);
+        let mut rng = rand::thread_rng();
+        for (i, record) in rdr.byte_records().enumerate() {
+            let record = record?;
+            if i < sample_size {
+                reservoir.push(record);
+            } else {
+                let random = rng.gen_range(0..=i);
+                if random < sample_size {
+                    reservoir[random] = record;
+                }
+            }
+        }
+        for record in &reservoir {
+            for (scan, cell) in scans.iter_mut().zip(sel.select(record)) {
+                scan.add(cell, &pattern);
+            }
+        }
+    } else {
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (scan, cell) in scans.iter_mut().zip(sel.select(&record)) {
+                scan.add(cell, &pattern);
+            }
+        }
+    }
+
+    let mut report = Vec::new();
+    for (column_name, scan) in column_names.iter().zip(&scans) {
+        for (i, pii_type) in PII_TYPES.iter().enumerate() {
+            let matches = scan.matches[i];
+            if matches == 0 {
+                continue;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let match_rate = matches as f64 / scan.scanned as f64;
+            if match_rate < args.flag_min_rate {
+                continue;
+            }
+            let hint_bonus = if pii_type.hinted_by(column_name) {
+                0.1
+            } else {
+                0.0
+            };
+            let confidence = (match_rate * pii_type.weight() + hint_bonus).min(1.0);
+            report.push(Detection {
+                column: column_name,
+                pii_type: *pii_type,
+                matches,
+                scanned: scan.scanned,
+                match_rate: round(match_rate),
+                confidence: round(confidence),
+            });
+        }
+    }
+
+    if args.flag_json {
+        let json: Vec<serde_json::Value> = report.iter().map(Detection::to_json).collect();
+        let mut wtr = Config::new(&args.flag_output).io_writer()?;
+        writeln!(wtr, "{}", serde_json::Value::Array(json))?;
+        wtr.flush()?;
+    } else {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        wtr.write_record([
+            "column",
+            "pii_type",
+            "matches",
+            "scanned",
+            "match_rate",
+            "confidence",
+        ])?;
+        for detection in &report {
+            wtr.write_record(detection.to_record())?;
+        }
+        wtr.flush()?;
+    }
+    Ok(())
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
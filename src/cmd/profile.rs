@@ -0,0 +1,806 @@
+static USAGE: &str = r#"
+Profiles a CSV file into a single self-contained HTML or Markdown report, combining:
+
+  * its `sniff` metadata - the delimiter, quoting, header row, etc.
+  * the stats of each column, as `qsv stats --everything` computes them.
+  * the top values of each column, as `qsv frequency` counts them.
+  * its null patterns - the sets of columns that are empty together in the same rows.
+  * a histogram of the values of each numeric & date column, like `qsv bin --bars`.
+  * the JSON Schema definition of each column, as `qsv schema` infers it.
+
+The report starts with an overview of the file, its columns & its null patterns,
+followed by a section for each column.
+
+If the input has a valid stats cache (see `qsv stats --help`) computed with --everything
+& equivalent date inference options, it is used instead of computing the stats again.
+
+As the input is read more than once, it cannot be stdin.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_profile.rs.
+
+Usage:
+    qsv profile [options] <input>
+    qsv profile --help
+
+profile options:
+    -f, --format <arg>         The format of the report, either "html" or "markdown".
+                               [default: html]
+    --title <arg>              The title of the report.
+                               Defaults to "Profile of <input file name>".
+    -l, --limit <n>            The number of top values of each column & of null
+                               patterns to show. Set to 0 to show all of them.
+                               [default: 10]
+    -b, --bins <n>             The number of equal-width buckets of the histograms.
+                               [default: 10]
+    --sample <n>               The number of rows to sample to sniff the metadata.
+                               [default: 1000]
+    --enum-threshold NUM       Cardinality threshold for adding enum constraints to
+                               the JSON Schema definitions. [default: 50]
+    --dates-whitelist <list>   The case-insensitive patterns to look for when
+                               shortlisting fields for date inference.
+                               Set to "all" to inspect ALL fields for
+                               date/datetime types.
+                               [default: date,time,due,opened,closed]
+    --prefer-dmy               Prefer to parse dates in dmy format. Otherwise, use mdy format.
+    -j, --jobs <arg>           The number of jobs to run in parallel when the input
+                               is indexed.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write the report to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{fs, io::Write, path::Path};
+
+use ahash::AHashMap;
+use qsv_sniffer::{metadata::Quote, DatePreference, SampleSize, Sniffer};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use thousands::Separable;
+
+use crate::{
+    cmd::{
+        bin::{bar, equal_width_edges, percentage, BinKind, Binner},
+        frequency, schema,
+        stats::{self, StatsCache},
+    },
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:            String,
+    flag_format:          Format,
+    flag_title:           Option<String>,
+    flag_limit:           usize,
+    flag_bins:            usize,
+    flag_sample:          usize,
+    flag_enum_threshold:  usize,
+    flag_dates_whitelist: String,
+    flag_prefer_dmy:      bool,
+    flag_jobs:            Option<usize>,
+    flag_output:          Option<String>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    Html,
+    Markdown,
+}
+
+/// the stats records of the columns, as `qsv stats` computes them
+struct ColumnsStats {
+    headers:      csv::ByteRecord,
+    records:      Vec<csv::StringRecord>,
+    /// the stats column names, without the leading "field" column
+    names:        Vec<String>,
+    record_count: u64,
+}
+
+impl ColumnsStats {
+    fn get<'a>(&self, record: &'a csv::StringRecord, stat: &str) -> &'a str {
+        self.names
+            .iter()
+            .position(|name| name == stat)
+            .and_then(|i| record.get(i))
+            .unwrap_or_default()
+    }
+}
+
+struct Report {
+    title:         String,
+    overview:      Vec<(String, String)>,
+    record_count:  u64,
+    /// the names of the columns that are empty together, & the number of such rows
+    null_patterns: Vec<(Vec<String>, u64)>,
+    columns:       Vec<ColumnProfile>,
+}
+
+struct ColumnProfile {
+    name:       String,
+    typ:        String,
+    nullcount:  String,
+    stats:      Vec<(String, String)>,
+    all_unique: bool,
+    top_values: Vec<(String, u64)>,
+    /// the bucket labels & counts, for numeric & date columns
+    histogram:  Vec<(String, u64)>,
+    schema:     Option<Value>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let mut args: Args = util::get_args(USAGE, argv)?;
+    if args.arg_input == "-" {
+        return fail!("qsv profile cannot read from stdin, as it reads its input more than once.");
+    }
+    if args.flag_bins == 0 {
+        return fail!("--bins must be greater than 0.");
+    }
+
+    let rconfig = args.rconfig();
+    args.flag_prefer_dmy = args.flag_prefer_dmy || rconfig.get_dmy_preference();
+
+    let stats = args.stats(&rconfig)?;
+    let names: Vec<String> = stats
+        .headers
+        .iter()
+        .map(|h| String::from_utf8_lossy(h).to_string())
+        .collect();
+
+    let binners: Vec<Option<Binner>> = stats
+        .records
+        .iter()
+        .map(|record| {
+            let kind = BinKind::from_type(stats.get(record, "type"))?;
+            let min = kind.parse_stat(stats.get(record, "min"))?;
+            let max = kind.parse_stat(stats.get(record, "max"))?;
+            Some(Binner::new(
+                kind,
+                equal_width_edges(min, max, args.flag_bins),
+            ))
+        })
+        .collect();
+    let (null_patterns, bin_counts) = args.scan(&rconfig, &binners)?;
+    let no_nulls: &[usize] = &[];
+    let complete_rows = null_patterns.get(no_nulls).copied().unwrap_or_default();
+
+    let mut overview = args.overview(stats.record_count, names.len())?;
+    overview.push((
+        "Rows without nulls".to_string(),
+        format!(
+            "{} ({}%)",
+            complete_rows.separate_with_commas(),
+            percentage(complete_rows, stats.record_count)
+        ),
+    ));
+
+    let top_values = args.top_values()?;
+    let mut properties = args.schema_properties(&stats)?;
+
+    let mut columns = Vec::with_capacity(names.len());
+    for (i, name) in names.iter().enumerate() {
+        let record = &stats.records[i];
+        let column_stats = stats
+            .names
+            .iter()
+            .zip(record.iter())
+            .filter(|(stat, value)| *stat != "type" && !value.is_empty())
+            .map(|(stat, value)| (stat.clone(), value.to_string()))
+            .collect();
+        let histogram = match &binners[i] {
+            Some(binner) => binner
+                .labels
+                .iter()
+                .cloned()
+                .zip(bin_counts[i].iter().copied())
+                .collect(),
+            None => vec![],
+        };
+        columns.push(ColumnProfile {
+            name: name.clone(),
+            typ: stats.get(record, "type").to_string(),
+            nullcount: stats.get(record, "nullcount").to_string(),
+            stats: column_stats,
+            all_unique: stats.record_count > 1
+                && stats.get(record, "cardinality") == stats.record_count.to_string(),
+            top_values: top_values.get(i).cloned().unwrap_or_default(),
+            histogram,
+            schema: properties.remove(name),
+        });
+    }
+
+    let mut null_patterns: Vec<(Vec<usize>, u64)> = null_patterns.into_iter().collect();
+    null_patterns.sort_unstable_by(|(p1, c1), (p2, c2)| c2.cmp(c1).then_with(|| p1.cmp(p2)));
+    if args.flag_limit > 0 {
+        null_patterns.truncate(args.flag_limit);
+    }
+
+    let report = Report {
+        title: args
+            .flag_title
+            .clone()
+            .unwrap_or_else(|| format!("Profile of {}", file_name(&args.arg_input))),
+        overview,
+        record_count: stats.record_count,
+        null_patterns: null_patterns
+            .into_iter()
+            .map(|(cols, count)| (cols.iter().map(|&col| names[col].clone()).collect(), count))
+            .collect(),
+        columns,
+    };
+
+    let mut wtr = Config::new(&args.flag_output).io_writer()?;
+    match args.flag_format {
+        Format::Html => write_html(&report, &mut wtr)?,
+        Format::Markdown => write_markdown(&report, &mut wtr)?,
+    }
+    Ok(wtr.flush()?)
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&Some(self.arg_input.clone()))
+            .delimiter(self.flag_delimiter)
+            .checkutf8(false)
+    }
+
+    fn stats(&self, rconfig: &Config) -> CliResult<ColumnsStats> {
+        let input = Some(self.arg_input.clone());
+        if let Some(cache) = StatsCache::load(&input) {
+            if cache.options.everything
+                && cache.same_dialect(false, rconfig.get_delimiter())
+                && cache.same_date_inference(true, &self.flag_dates_whitelist, self.flag_prefer_dmy)
+            {
+                log::info!("using stats cache");
+                return Ok(ColumnsStats {
+                    headers:      cache.fields.iter().collect(),
+                    records:      cache.records(),
+                    // the cached stats records don't have the leading "field" column
+                    names:        cache.headers[1..].to_vec(),
+                    record_count: cache.record_count,
+                });
+            }
+            log::info!("stats cache was computed with different options, ignoring it");
+        }
+
+        let stats_args = stats::Args {
+            arg_input:            input,
+            flag_select:          SelectColumns::parse("").unwrap(),
+            flag_groupby:         None,
+            flag_everything:      true,
+            flag_mode:            false,
+            flag_cardinality:     false,
+            flag_median:          false,
+            flag_quartiles:       false,
+            flag_nulls:           false,
+            flag_round:           4,
+            flag_infer_dates:     true,
+            flag_dates_whitelist: self.flag_dates_whitelist.clone(),
+            flag_prefer_dmy:      self.flag_prefer_dmy,
+            flag_cache:           false,
+            flag_jobs:            Some(util::njobs(self.flag_jobs)),
+            flag_output:          None,
+            flag_no_headers:      false,
+            flag_delimiter:       self.flag_delimiter,
+        };
+        let (headers, stats) = match stats_args.rconfig().indexed()? {
+            Some(idx) if util::njobs(self.flag_jobs) > 1 => {
+                stats_args.parallel_stats(&stats_args.flag_dates_whitelist, &idx)
+            }
+            _ => stats_args.sequential_stats(&stats_args.flag_dates_whitelist),
+        }?;
+        Ok(ColumnsStats {
+            headers,
+            records: stats_args.stats_to_records(stats),
+            names: stats_args
+                .stat_headers()
+                .iter()
+                .skip(1)
+                .map(String::from)
+                .collect(),
+            record_count: util::count_rows(rconfig)?,
+        })
+    }
+
+    /// the file metadata, & the dialect `qsv sniff` detects
+    fn overview(&self, record_count: u64, nfields: usize) -> CliResult<Vec<(String, String)>> {
+        let size = fs::metadata(&self.arg_input)?.len();
+        let mut overview = vec![
+            ("File".to_string(), file_name(&self.arg_input)),
+            (
+                "Size".to_string(),
+                format!("{} bytes", size.separate_with_commas()),
+            ),
+            ("Records".to_string(), record_count.separate_with_commas()),
+            ("Fields".to_string(), nfields.to_string()),
+        ];
+
+        let dt_preference = if self.flag_prefer_dmy {
+            DatePreference::DmyFormat
+        } else {
+            DatePreference::MdyFormat
+        };
+        let rdr = Config::new(&Some(self.arg_input.clone()))
+            .flexible(true)
+            .checkutf8(false)
+            .reader_file_stdin()?;
+        let sniffed = Sniffer::new()
+            .sample_size(SampleSize::Records(self.flag_sample.max(10)))
+            .date_preference(dt_preference)
+            .sniff_reader(rdr.into_inner());
+        match sniffed {
+            Ok(metadata) => {
+                let delimiter = match metadata.dialect.delimiter {
+                    b'\t' => "tab".to_string(),
+                    delimiter => char::from(delimiter).to_string(),
+                };
+                let quote = match metadata.dialect.quote {
+                    Quote::Some(chr) => char::from(chr).to_string(),
+                    Quote::None => "none".to_string(),
+                };
+                overview.extend([
+                    ("Delimiter".to_string(), delimiter),
+                    ("Quote character".to_string(), quote),
+                    (
+                        "Header row".to_string(),
+                        metadata.dialect.header.has_header_row.to_string(),
+                    ),
+                    (
+                        "Preamble rows".to_string(),
+                        metadata.dialect.header.num_preamble_rows.to_string(),
+                    ),
+                    (
+                        "Flexible".to_string(),
+                        metadata.dialect.flexible.to_string(),
+                    ),
+                    ("UTF-8".to_string(), metadata.dialect.is_utf8.to_string()),
+                ]);
+            }
+            Err(e) => {
+                log::warn!("sniff error: {e}");
+                overview.push(("Sniff error".to_string(), e.to_string()));
+            }
+        }
+        Ok(overview)
+    }
+
+    /// reads the input once, counting the rows of each null pattern - the indices of the
+    /// empty columns of a row - & the values in each histogram bucket
+    #[allow(clippy::type_complexity)]
+    fn scan(
+        &self,
+        rconfig: &Config,
+        binners: &[Option<Binner>],
+    ) -> CliResult<(AHashMap<Vec<usize>, u64>, Vec<Vec<u64>>)> {
+        let mut rdr = rconfig.reader()?;
+        let mut null_patterns: AHashMap<Vec<usize>, u64> = AHashMap::new();
+        let mut bin_counts: Vec<Vec<u64>> = binners
+            .iter()
+            .map(|binner| vec![0; binner.as_ref().map_or(0, Binner::nbins)])
+            .collect();
+
+        let mut nulls = Vec::new();
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            nulls.clear();
+            for (i, field) in record.iter().enumerate() {
+                if field.is_empty() {
+                    nulls.push(i);
+                } else if let Some(Some(binner)) = binners.get(i) {
+                    let bucket = binner
+                        .kind
+                        .parse(field, self.flag_prefer_dmy)
+                        .and_then(|value| binner.bin(value));
+                    if let Some(bucket) = bucket {
+                        bin_counts[i][bucket] += 1;
+                    }
+                }
+            }
+            match null_patterns.get_mut(&nulls) {
+                Some(count) => *count += 1,
+                None => {
+                    null_patterns.insert(nulls.clone(), 1);
+                }
+            }
+        }
+        Ok((null_patterns, bin_counts))
+    }
+
+    /// the most frequent values of each column, as `qsv frequency` counts them
+    fn top_values(&self) -> CliResult<Vec<Vec<(String, u64)>>> {
+        let freq_args = frequency::Args {
+            arg_input:            Some(self.arg_input.clone()),
+            flag_select:          SelectColumns::parse("").unwrap(),
+            flag_limit:           self.flag_limit,
+            flag_asc:             false,
+            flag_no_nulls:        false,
+            flag_approx:          false,
+            flag_approx_capacity: 0,
+            flag_combine:         false,
+            flag_weight:          None,
+            flag_jobs:            Some(util::njobs(self.flag_jobs)),
+            flag_output:          None,
+            flag_no_headers:      false,
+            flag_delimiter:       self.flag_delimiter,
+        };
+        let (_, ftables) = match freq_args.rconfig().indexed()? {
+            Some(ref mut idx) => freq_args.parallel_ftables(idx),
+            _ => freq_args.sequential_ftables(),
+        }?;
+
+        let limit = if self.flag_limit == 0 {
+            usize::MAX
+        } else {
+            self.flag_limit
+        };
+        Ok(ftables
+            .iter()
+            .map(|ftab| {
+                ftab.most_frequent()
+                    .into_iter()
+                    .take(limit)
+                    .map(|(value, count)| {
+                        let value = if value.is_empty() {
+                            "(NULL)".to_string()
+                        } else {
+                            String::from_utf8_lossy(value).to_string()
+                        };
+                        (value, count)
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// the JSON Schema definitions of the columns, as `qsv schema` infers them
+    fn schema_properties(&self, stats: &ColumnsStats) -> CliResult<Map<String, Value>> {
+        let schema_args = schema::Args {
+            flag_enum_threshold:  self.flag_enum_threshold,
+            flag_strict_dates:    false,
+            flag_pattern_columns: SelectColumns::parse("").unwrap(),
+            flag_dates_whitelist: self.flag_dates_whitelist.clone(),
+            flag_prefer_dmy:      self.flag_prefer_dmy,
+            flag_infer_formats:   false,
+            flag_unique:          false,
+            flag_sample:          None,
+            flag_format:          schema::SchemaFormat::JsonSchema,
+            flag_stdout:          false,
+            flag_jobs:            self.flag_jobs,
+            flag_no_headers:      false,
+            flag_delimiter:       self.flag_delimiter,
+            arg_input:            Some(self.arg_input.clone()),
+        };
+        let stats_col_index_map = stats
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+        schema::infer_schema_from_stats_records(
+            &schema_args,
+            &file_name(&self.arg_input),
+            &stats.headers,
+            &stats.records,
+            &stats_col_index_map,
+        )
+    }
+}
+
+fn file_name(input: &str) -> String {
+    Path::new(input).file_name().map_or_else(
+        || input.to_string(),
+        |name| name.to_string_lossy().to_string(),
+    )
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn null_pattern_label(columns: &[String]) -> String {
+    if columns.is_empty() {
+        "(none)".to_string()
+    } else {
+        columns.join(", ")
+    }
+}
+
+fn md_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn write_md_table(wtr: &mut dyn Write, headers: &[&str], rows: &[Vec<String>]) -> CliResult<()> {
+    writeln!(wtr, "| {} |", headers.join(" | "))?;
+    writeln!(wtr, "|{}", "---|".repeat(headers.len()))?;
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| md_escape(cell)).collect();
+        writeln!(wtr, "| {} |", cells.join(" | "))?;
+    }
+    writeln!(wtr)?;
+    Ok(())
+}
+
+fn write_markdown(report: &Report, wtr: &mut dyn Write) -> CliResult<()> {
+    writeln!(wtr, "# {}\n", md_escape(&report.title))?;
+
+    writeln!(wtr, "## Overview\n")?;
+    let rows: Vec<Vec<String>> = report
+        .overview
+        .iter()
+        .map(|(key, value)| vec![key.clone(), value.clone()])
+        .collect();
+    write_md_table(wtr, &["Property", "Value"], &rows)?;
+
+    writeln!(wtr, "## Columns\n")?;
+    let rows: Vec<Vec<String>> = report
+        .columns
+        .iter()
+        .map(|col| vec![col.name.clone(), col.typ.clone(), col.nullcount.clone()])
+        .collect();
+    write_md_table(wtr, &["Column", "Type", "Nulls"], &rows)?;
+
+    writeln!(wtr, "## Null patterns\n")?;
+    let rows: Vec<Vec<String>> = report
+        .null_patterns
+        .iter()
+        .map(|(columns, count)| {
+            vec![
+                null_pattern_label(columns),
+                count.to_string(),
+                percentage(*count, report.record_count),
+            ]
+        })
+        .collect();
+    write_md_table(wtr, &["Empty columns", "Rows", "Percentage"], &rows)?;
+
+    for col in &report.columns {
+        writeln!(wtr, "## {}\n", md_escape(&col.name))?;
+
+        writeln!(wtr, "### Stats\n")?;
+        let mut rows = vec![vec!["type".to_string(), col.typ.clone()]];
+        rows.extend(
+            col.stats
+                .iter()
+                .map(|(stat, value)| vec![stat.clone(), value.clone()]),
+        );
+        write_md_table(wtr, &["Stat", "Value"], &rows)?;
+
+        writeln!(wtr, "### Top values\n")?;
+        if col.all_unique {
+            writeln!(wtr, "All the values are unique.\n")?;
+        } else {
+            let rows: Vec<Vec<String>> = col
+                .top_values
+                .iter()
+                .map(|(value, count)| {
+                    vec![
+                        value.clone(),
+                        count.to_string(),
+                        percentage(*count, report.record_count),
+                    ]
+                })
+                .collect();
+            write_md_table(wtr, &["Value", "Count", "Percentage"], &rows)?;
+        }
+
+        if !col.histogram.is_empty() {
+            writeln!(wtr, "### Histogram\n")?;
+            let total = col.histogram.iter().map(|(_, count)| count).sum();
+            let max_count = col.histogram.iter().map(|(_, count)| *count).max();
+            let rows: Vec<Vec<String>> = col
+                .histogram
+                .iter()
+                .map(|(label, count)| {
+                    vec![
+                        label.clone(),
+                        count.to_string(),
+                        percentage(*count, total),
+                        bar(*count, max_count.unwrap_or_default(), 20),
+                    ]
+                })
+                .collect();
+            write_md_table(wtr, &["Bucket", "Count", "Percentage", ""], &rows)?;
+        }
+
+        if let Some(ref schema) = col.schema {
+            writeln!(wtr, "### JSON Schema\n")?;
+            writeln!(wtr, "```json\n{}\n```\n", pretty_json(schema))?;
+        }
+    }
+
+    writeln!(wtr, "---\n")?;
+    writeln!(wtr, "Generated by qsv {}", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// writes a table of cells that are already HTML, right-aligning the `numeric` columns
+fn write_html_table(
+    wtr: &mut dyn Write,
+    headers: &[&str],
+    numeric: &[usize],
+    rows: &[Vec<String>],
+) -> CliResult<()> {
+    writeln!(wtr, "<table>")?;
+    write!(wtr, "<tr>")?;
+    for header in headers {
+        write!(wtr, "<th>{}</th>", html_escape(header))?;
+    }
+    writeln!(wtr, "</tr>")?;
+    for row in rows {
+        write!(wtr, "<tr>")?;
+        for (i, cell) in row.iter().enumerate() {
+            if numeric.contains(&i) {
+                write!(wtr, "<td class=\"num\">{cell}</td>")?;
+            } else {
+                write!(wtr, "<td>{cell}</td>")?;
+            }
+        }
+        writeln!(wtr, "</tr>")?;
+    }
+    writeln!(wtr, "</table>")?;
+    Ok(())
+}
+
+const HTML_STYLE: &str = r#"body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.6em; text-align: left; }
+th { background: #f0f0f0; }
+td.num { text-align: right; }
+section { border-top: 2px solid #ddd; margin-top: 2em; }
+.bar { background: #4878a8; height: 1em; min-width: 1px; }
+pre { background: #f6f6f6; padding: 0.8em; overflow-x: auto; }
+footer { color: #888; font-size: 0.9em; margin-top: 2em; }"#;
+
+#[allow(clippy::cast_precision_loss)]
+fn write_html(report: &Report, wtr: &mut dyn Write) -> CliResult<()> {
+    let title = html_escape(&report.title);
+    writeln!(wtr, "<!DOCTYPE html>")?;
+    writeln!(wtr, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(
+        wtr,
+        "<title>{title}</title>\n<style>\n{HTML_STYLE}\n</style>"
+    )?;
+    writeln!(wtr, "</head>\n<body>\n<h1>{title}</h1>")?;
+
+    writeln!(wtr, "<h2>Overview</h2>")?;
+    let rows: Vec<Vec<String>> = report
+        .overview
+        .iter()
+        .map(|(key, value)| vec![html_escape(key), html_escape(value)])
+        .collect();
+    write_html_table(wtr, &["Property", "Value"], &[], &rows)?;
+
+    writeln!(wtr, "<h2>Columns</h2>")?;
+    let rows: Vec<Vec<String>> = report
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| {
+            vec![
+                format!("<a href=\"#column-{i}\">{}</a>", html_escape(&col.name)),
+                html_escape(&col.typ),
+                html_escape(&col.nullcount),
+            ]
+        })
+        .collect();
+    write_html_table(wtr, &["Column", "Type", "Nulls"], &[2], &rows)?;
+
+    writeln!(wtr, "<h2>Null patterns</h2>")?;
+    let rows: Vec<Vec<String>> = report
+        .null_patterns
+        .iter()
+        .map(|(columns, count)| {
+            vec![
+                html_escape(&null_pattern_label(columns)),
+                count.to_string(),
+                percentage(*count, report.record_count),
+            ]
+        })
+        .collect();
+    write_html_table(
+        wtr,
+        &["Empty columns", "Rows", "Percentage"],
+        &[1, 2],
+        &rows,
+    )?;
+
+    for (i, col) in report.columns.iter().enumerate() {
+        writeln!(wtr, "<section id=\"column-{i}\">")?;
+        writeln!(wtr, "<h2>{}</h2>", html_escape(&col.name))?;
+
+        writeln!(wtr, "<h3>Stats</h3>")?;
+        let mut rows = vec![vec!["type".to_string(), html_escape(&col.typ)]];
+        rows.extend(
+            col.stats
+                .iter()
+                .map(|(stat, value)| vec![html_escape(stat), html_escape(value)]),
+        );
+        write_html_table(wtr, &["Stat", "Value"], &[], &rows)?;
+
+        writeln!(wtr, "<h3>Top values</h3>")?;
+        if col.all_unique {
+            writeln!(wtr, "<p>All the values are unique.</p>")?;
+        } else {
+            let rows: Vec<Vec<String>> = col
+                .top_values
+                .iter()
+                .map(|(value, count)| {
+                    vec![
+                        html_escape(value),
+                        count.to_string(),
+                        percentage(*count, report.record_count),
+                    ]
+                })
+                .collect();
+            write_html_table(wtr, &["Value", "Count", "Percentage"], &[1, 2], &rows)?;
+        }
+
+        if !col.histogram.is_empty() {
+            writeln!(wtr, "<h3>Histogram</h3>")?;
+            let total = col.histogram.iter().map(|(_, count)| count).sum();
+            let max_count = col
+                .histogram
+                .iter()
+                .map(|(_, count)| *count)
+                .max()
+                .unwrap_or_default();
+            let rows: Vec<Vec<String>> = col
+                .histogram
+                .iter()
+                .map(|(label, count)| {
+                    let width = if max_count == 0 {
+                        0.0
+                    } else {
+                        *count as f64 * 100.0 / max_count as f64
+                    };
+                    vec![
+                        html_escape(label),
+                        count.to_string(),
+                        percentage(*count, total),
+                        format!(
+                            "<div class=\"bar\" style=\"width: {}px\"></div>",
+                            stats::round_num(width * 3.0, 1)
+                        ),
+                    ]
+                })
+                .collect();
+            write_html_table(wtr, &["Bucket", "Count", "Percentage", ""], &[1, 2], &rows)?;
+        }
+
+        if let Some(ref schema) = col.schema {
+            writeln!(wtr, "<h3>JSON Schema</h3>")?;
+            writeln!(wtr, "<pre>{}</pre>", html_escape(&pretty_json(schema)))?;
+        }
+        writeln!(wtr, "</section>")?;
+    }
+
+    writeln!(
+        wtr,
+        "<footer>Generated by qsv {}</footer>\n</body>\n</html>",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    Ok(())
+}
@@ -2,12 +2,47 @@ static USAGE: &str = r#"
 Pseudonymise the value of the given column by replacing them by an
 incremental identifier.
 
+When several columns are selected, they share the same identifiers, so a value
+gets the same identifier whatever column it appears in (e.g. sender & recipient).
+
+By default, the identifiers restart from 0 at each run. To get the same identifiers
+across runs & files, either:
+
+  * use a --mapping file. It's a CSV with "value,id" columns, loaded if it exists,
+    extended with the identifiers of the new values & saved back at the end of the run.
+    It also allows re-identifying the values with --reverse.
+  * use --hmac-key. Values are replaced by their keyed HMAC-SHA256 hash as lowercase
+    hex, which is deterministic without any state. Without the key, the values
+    can't be recovered from the hashes, even with a dictionary attack.
+    It can be combined with --mapping to allow re-identification.
+
+As the mapping file can re-identify the values, it should be kept as secret as the
+original data.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_pseudo.rs.
 
 Usage:
     qsv pseudo [options] <column> [<input>]
     qsv pseudo --help
 
+The <column> argument can be a list of columns. See 'qsv select --help' for the
+format details.
+
+pseudo options:
+    -m, --mapping <file>   The persistent "value,id" mapping file, loaded if it exists
+                           & saved with the new values at the end of the run.
+    --hmac-key <source>    Use keyed hashes as identifiers instead of a counter, with the
+                           secret key read from either 'env:<VAR>' for an environment
+                           variable, or 'file:<path>' for a file.
+    --keyed                Same as --hmac-key, with the secret key read from the
+                           QSV_HMAC_KEY environment variable.
+                           Keyed hashes require the apply feature, so --hmac-key
+                           & --keyed are not available in qsvlite & qsvdp.
+    --reverse              Re-identify the pseudonymised columns, by replacing the
+                           identifiers with their original values from the --mapping
+                           file. Unknown identifiers are kept as is.
+                           Cannot be used with --hmac-key or --keyed.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
@@ -17,9 +52,14 @@ Common options:
                            Must be a single character. (default: ,)
 "#;
 
+use std::{fs, path::Path};
+
 use ahash::AHashMap;
 use serde::Deserialize;
+use uuid::Uuid;
 
+#[cfg(feature = "apply")]
+use crate::util::read_hmac_key;
 use crate::{
     config::{Config, Delimiter},
     select::SelectColumns,
@@ -30,6 +70,10 @@ use crate::{
 struct Args {
     arg_column:      SelectColumns,
     arg_input:       Option<String>,
+    flag_mapping:    Option<String>,
+    flag_hmac_key:   Option<String>,
+    flag_keyed:      bool,
+    flag_reverse:    bool,
     flag_output:     Option<String>,
     flag_no_headers: bool,
     flag_delimiter:  Option<Delimiter>,
@@ -47,10 +91,109 @@ pub fn replace_column_value(
         .collect()
 }
 
-type Values = AHashMap<String, u64>;
+type Values = AHashMap<String, String>;
+
+/// the value -> identifier mapping, in the order the values were first seen
+#[derive(Default)]
+struct Mapping {
+    ids:     Values,
+    order:   Vec<String>,
+    counter: u64,
+    changed: bool,
+}
+
+impl Mapping {
+    fn load(path: &str) -> CliResult<Mapping> {
+        let mut mapping = Mapping::default();
+        if !Path::new(path).exists() {
+            return Ok(mapping);
+        }
+        let mut rdr = Config::new(&Some(path.to_string())).reader()?;
+        for record in rdr.records() {
+            let record = record?;
+            let (Some(value), Some(id)) = (record.get(0), record.get(1)) else {
+                return fail_clierror!("The mapping file {path} must have value & id columns.");
+            };
+            // new counter identifiers start after the highest existing one
+            if let Ok(counter) = id.parse::<u64>() {
+                mapping.counter = mapping.counter.max(counter + 1);
+            }
+            mapping.insert(value.to_string(), id.to_string());
+        }
+        mapping.changed = false;
+        Ok(mapping)
+    }
+
+    fn insert(&mut self, value: String, id: String) {
+        self.order.push(value.clone());
+        self.ids.insert(value, id);
+        self.changed = true;
+    }
+
+    fn id(&mut self, value: &str, key: Option<&[u8]>) -> String {
+        if let Some(id) = self.ids.get(value) {
+            return id.clone();
+        }
+        let id = match key {
+            #[cfg(feature = "apply")]
+            Some(key) => util::hmac_sha256_hex(key, value),
+            _ => {
+                let id = self.counter.to_string();
+                self.counter += 1;
+                id
+            }
+        };
+        self.insert(value.to_string(), id.clone());
+        id
+    }
+
+    // the mapping is written to a temporary file in the same directory, then renamed
+    // over the mapping file, so a failed run can't leave a truncated mapping behind
+    fn save(&self, path: &str) -> CliResult<()> {
+        let tmp_path = format!("{path}.{}.tmp", Uuid::new_v4());
+        if let Err(e) = self.write(&tmp_path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+        if let Err(e) = fs::rename(&tmp_path, path) {
+            let _ = fs::remove_file(&tmp_path);
+            return fail_clierror!("Cannot save the mapping file {path}: {e}");
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &str) -> CliResult<()> {
+        let mut wtr = Config::new(&Some(path.to_string())).writer()?;
+        wtr.write_record(["value", "id"])?;
+        for value in &self.order {
+            wtr.write_record([value, &self.ids[value]])?;
+        }
+        Ok(wtr.flush()?)
+    }
+}
+
+// keyed hashes need the hmac & sha2 dependencies of the apply feature
+#[cfg(not(feature = "apply"))]
+fn read_hmac_key(_source: &Option<String>) -> CliResult<Vec<u8>> {
+    fail!(
+        "--hmac-key & --keyed require the apply feature, so they are not available in this build."
+    )
+}
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_reverse && args.flag_mapping.is_none() {
+        return fail!("--reverse requires the --mapping file.");
+    }
+    if args.flag_reverse && (args.flag_hmac_key.is_some() || args.flag_keyed) {
+        return fail!("--reverse cannot be used with --hmac-key or --keyed.");
+    }
+    let key = if args.flag_hmac_key.is_some() || args.flag_keyed {
+        Some(read_hmac_key(&args.flag_hmac_key)?)
+    } else {
+        None
+    };
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
@@ -61,27 +204,48 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
-    let column_index = *sel.iter().next().unwrap();
 
     if !rconfig.no_headers {
         wtr.write_record(&headers)?;
     }
 
+    let mut mapping = match args.flag_mapping {
+        Some(ref path) => Mapping::load(path)?,
+        None => Mapping::default(),
+    };
+
     let mut record = csv::StringRecord::new();
-    let mut values = Values::new();
-    let mut counter: u64 = 0;
+
+    if args.flag_reverse {
+        let values: Values = mapping
+            .ids
+            .into_iter()
+            .map(|(value, id)| (id, value))
+            .collect();
+        while rdr.read_record(&mut record)? {
+            for column_index in sel.iter() {
+                if let Some(value) = values.get(&record[*column_index]) {
+                    record = replace_column_value(&record, *column_index, value);
+                }
+            }
+            wtr.write_record(&record)?;
+        }
+        return Ok(wtr.flush()?);
+    }
 
     while rdr.read_record(&mut record)? {
-        let value = record[column_index].to_owned();
-
-        if let Some(id) = values.get(&value) {
-            record = replace_column_value(&record, column_index, &id.to_string());
-        } else {
-            values.insert(value, counter);
-            record = replace_column_value(&record, column_index, &counter.to_string());
-            counter += 1;
+        for column_index in sel.iter() {
+            let id = mapping.id(&record[*column_index], key.as_deref());
+            record = replace_column_value(&record, *column_index, &id);
         }
         wtr.write_record(&record)?;
     }
-    Ok(wtr.flush()?)
+    wtr.flush()?;
+
+    if let Some(ref path) = args.flag_mapping {
+        if mapping.changed {
+            mapping.save(path)?;
+        }
+    }
+    Ok(())
 }
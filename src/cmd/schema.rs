@@ -7,13 +7,31 @@ including validation rules based on data type and input data domain/range.
 Running `validate` command on original input CSV with generated schema 
 should not flag any invalid records.
 
+With --format, a Frictionless Table Schema (https://specs.frictionlessdata.io/table-schema/)
+or Data Package (https://specs.frictionlessdata.io/data-package/) can be generated instead,
+e.g. to publish the CSV on CKAN. The Data Package has the CSV as its only resource, with
+its dialect, Table Schema, and size & row count stats.
+
 Generated schema file has `.schema.json` postfix appended. For example, 
 for input `mydata.csv`, schema file would be `mydata.csv.schema.json`. 
+A Table Schema file has `.tableschema.json` appended instead, while a Data Package
+is written to `datapackage.json` in the directory of the input.
 If piped from stdin, then schema file would be `stdin.csv.schema.json` and
 a `stdin.csv` file will created with stdin's contents as well.
 
 Note that `stdin.csv` will be overwritten if it already exists.
 
+With --infer-formats, the "format" of String columns is detected when all their values
+are emails, URIs, UUIDs, IPv4 or IPv6 addresses. Date & DateTime columns get the "date"
+& "date-time" formats when all their values are RFC 3339 dates & date-times.
+
+With --unique, the columns whose values are all unique are marked with "unique": true,
+which `validate` checks like a Table Schema unique constraint.
+
+If the input has a valid stats cache (see `qsv stats --help`) that has cardinality
+and was computed with equivalent date inference options, it is used instead of
+computing the stats again.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_schema.rs.
 
 Usage:
@@ -34,6 +52,18 @@ Schema options:
                                date/datetime types.
                                [default: date,time,due,opened,closed]
     --prefer-dmy               Prefer to parse dates in dmy format. Otherwise, use mdy format.
+    --infer-formats            Detect the "format" of String, Date & DateTime columns.
+    --unique                   Mark the columns whose values are all unique.
+    --sample <size>            Infer the schema from a random sample of <size> records,
+                               for a quick draft of the schema of a huge file.
+                               Note that the enum, minimum, maximum, length & pattern
+                               constraints then only reflect the sample.
+                               Cannot be used with --unique.
+    --format <format>          The format of the generated schema. One of:
+                                 jsonschema - JSON Schema (Draft 7)
+                                 tableschema - Frictionless Table Schema
+                                 datapackage - Frictionless Data Package
+                               [default: jsonschema]
     --stdout                   Send generated JSON schema file to stdout instead.
     -j, --jobs <arg>           The number of jobs to run in parallel.
                                When not set, the number of jobs is set to the
@@ -49,20 +79,31 @@ Common options:
                                Must be a single character. [default: ,]
 "#;
 
-use std::{collections::HashSet, fs::File, io::Write, path::Path};
+use std::{
+    collections::HashSet,
+    env::temp_dir,
+    fs::File,
+    io::Write,
+    net::{Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
 
 use ahash::AHashMap;
-use csv::ByteRecord;
+use chrono::{DateTime, NaiveDate};
+use csv::{ByteRecord, StringRecord};
 use grex::RegExpBuilder;
 use itertools::Itertools;
 use log::{debug, error, info, warn};
+use rand::Rng;
 use serde::Deserialize;
 use serde_json::{json, value::Number, Map, Value};
 use stats::Frequencies;
+use uuid::Uuid;
 
 use crate::{
-    cmd::stats::Stats,
+    cmd::stats::StatsCache,
     config::{Config, Delimiter},
+    regex_once_cell,
     select::SelectColumns,
     util, CliResult,
 };
@@ -74,6 +115,10 @@ pub struct Args {
     pub flag_pattern_columns: SelectColumns,
     pub flag_dates_whitelist: String,
     pub flag_prefer_dmy:      bool,
+    pub flag_infer_formats:   bool,
+    pub flag_unique:          bool,
+    pub flag_sample:          Option<usize>,
+    pub flag_format:          SchemaFormat,
     pub flag_stdout:          bool,
     pub flag_jobs:            Option<usize>,
     pub flag_no_headers:      bool,
@@ -81,6 +126,14 @@ pub struct Args {
     pub arg_input:            Option<String>,
 }
 
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaFormat {
+    JsonSchema,
+    TableSchema,
+    DataPackage,
+}
+
 const STDIN_CSV: &str = "stdin.csv";
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -109,14 +162,69 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // Config has not been created yet at this point
     args.flag_prefer_dmy = args.flag_prefer_dmy || std::env::var("QSV_PREFER_DMY").is_ok();
 
-    // build schema for each field by their inferred type, min/max value/length, and unique values
-    let mut properties_map: Map<String, Value> =
-        match infer_schema_from_stats(&args, &input_filename) {
-            Ok(map) => map,
-            Err(e) => {
-                return fail_clierror!("Failed to infer schema via stats and frequency: {e}");
+    // with --sample, the schema is inferred from a random sample of the input,
+    // written to a temporary file with the same delimiter
+    let _sample_file = match args.flag_sample {
+        Some(sample_size) => {
+            if args.flag_unique {
+                return fail_clierror!("--unique cannot be used with --sample.");
             }
-        };
+            let sample_file = SampleFile(format!(
+                "{}/{}.csv",
+                temp_dir().to_string_lossy(),
+                Uuid::new_v4()
+            ));
+            let delimiter = write_sample(&args, sample_size, &sample_file.0)?;
+            args.arg_input = Some(sample_file.0.clone());
+            args.flag_delimiter = Some(delimiter);
+            Some(sample_file)
+        }
+        None => None,
+    };
+
+    // build schema for each field by their inferred type, min/max value/length, and unique values.
+    // the stats are kept, as the Table Schema types are inferred from them
+    let (csv_fields, csv_stats, stats_col_index_map, record_count) = match get_stats_records(&args)
+    {
+        Ok(stats) => stats,
+        Err(e) => {
+            return fail_clierror!("Failed to infer schema via stats and frequency: {e}");
+        }
+    };
+    let mut properties_map: Map<String, Value> = match infer_schema_from_stats_records(
+        &args,
+        &input_filename,
+        &csv_fields,
+        &csv_stats,
+        &stats_col_index_map,
+    ) {
+        Ok(map) => map,
+        Err(e) => {
+            return fail_clierror!("Failed to infer schema via stats and frequency: {e}");
+        }
+    };
+
+    if args.flag_infer_formats {
+        let format_map = infer_formats(&args, &csv_fields, &csv_stats, &stats_col_index_map)?;
+        for (field_name, format) in format_map {
+            if let Some(Value::Object(field_def_map)) = properties_map.get_mut(&field_name) {
+                // with --strict-dates, dates already have their format
+                field_def_map
+                    .entry("format")
+                    .or_insert_with(|| Value::String(format.to_string()));
+            }
+        }
+    }
+
+    if args.flag_unique {
+        let unique_fields =
+            get_unique_fields(record_count, &csv_fields, &csv_stats, &stats_col_index_map)?;
+        for field_name in unique_fields {
+            if let Some(Value::Object(field_def_map)) = properties_map.get_mut(&field_name) {
+                field_def_map.insert("unique".to_string(), Value::Bool(true));
+            }
+        }
+    }
 
     // generate regex pattern for selected String columns
     let pattern_map = generate_string_patterns(&args, &properties_map)?;
@@ -135,14 +243,33 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let required_fields = get_required_fields(&properties_map);
 
     // create final JSON object for output
-    let schema = json!({
-        "$schema": "https://json-schema.org/draft-07/schema",
-        "title": format!("JSON Schema for {input_filename}"),
-        "description": "Inferred JSON Schema from QSV schema command",
-        "type": "object",
-        "properties": Value::Object(properties_map),
-        "required": Value::Array(required_fields)
-    });
+    let schema = match args.flag_format {
+        SchemaFormat::JsonSchema => json!({
+            "$schema": "https://json-schema.org/draft-07/schema",
+            "title": format!("JSON Schema for {input_filename}"),
+            "description": "Inferred JSON Schema from QSV schema command",
+            "type": "object",
+            "properties": Value::Object(properties_map),
+            "required": Value::Array(required_fields)
+        }),
+        SchemaFormat::TableSchema => table_schema(
+            &properties_map,
+            &csv_fields,
+            &csv_stats,
+            &stats_col_index_map,
+        )?,
+        SchemaFormat::DataPackage => data_package(
+            &args,
+            &input_path,
+            &input_filename,
+            table_schema(
+                &properties_map,
+                &csv_fields,
+                &csv_stats,
+                &stats_col_index_map,
+            )?,
+        )?,
+    };
 
     let schema_pretty = match serde_json::to_string_pretty(&schema) {
         Ok(s) => s,
@@ -158,7 +285,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
         info!("Schema written to stdout");
     } else {
-        let schema_output_filename = input_path + ".schema.json";
+        let schema_output_filename = match args.flag_format {
+            SchemaFormat::JsonSchema => input_path + ".schema.json",
+            SchemaFormat::TableSchema => input_path + ".tableschema.json",
+            SchemaFormat::DataPackage => Path::new(&input_path)
+                .with_file_name("datapackage.json")
+                .to_string_lossy()
+                .to_string(),
+        };
         let mut schema_output_file = File::create(&schema_output_filename)?;
 
         schema_output_file.write_all(schema_pretty.as_bytes())?;
@@ -171,6 +305,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+/// the temporary file of the --sample records, deleted when dropped so it's not left
+/// behind when schema fails
+struct SampleFile(String);
+
+impl Drop for SampleFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
 /// Builds JSON MAP object that corresponds to the "properties" object of JSON Schema (Draft 7) by
 /// looking at CSV value stats Supported JSON Schema validation vocabularies:
 ///  * type
@@ -179,11 +323,30 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 ///  * maxLength
 ///  * min
 ///  * max
-#[allow(clippy::len_zero)]
 pub fn infer_schema_from_stats(args: &Args, input_filename: &str) -> CliResult<Map<String, Value>> {
     // invoke cmd::stats
-    let (csv_fields, csv_stats, stats_col_index_map) = get_stats_records(args)?;
+    let (csv_fields, csv_stats, stats_col_index_map, _) = get_stats_records(args)?;
+
+    infer_schema_from_stats_records(
+        args,
+        input_filename,
+        &csv_fields,
+        &csv_stats,
+        &stats_col_index_map,
+    )
+}
 
+/// Builds the "properties" object of JSON Schema like `infer_schema_from_stats`, from stats
+/// records that were already computed, e.g. by another command that needs them too.
+/// `stats_col_index_map` maps the stats column names to their index in `csv_stats`.
+#[allow(clippy::len_zero)]
+pub fn infer_schema_from_stats_records(
+    args: &Args,
+    input_filename: &str,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StringRecord],
+    stats_col_index_map: &AHashMap<String, usize>,
+) -> CliResult<Map<String, Value>> {
     // amortize memory allocation
     let mut low_cardinality_column_indices: Vec<usize> =
         Vec::with_capacity(args.flag_enum_threshold);
@@ -192,9 +355,9 @@ pub fn infer_schema_from_stats(args: &Args, input_filename: &str) -> CliResult<M
     let column_select_arg: String = build_low_cardinality_column_selector_arg(
         &mut low_cardinality_column_indices,
         args.flag_enum_threshold,
-        &csv_fields,
-        &csv_stats,
-        &stats_col_index_map,
+        csv_fields,
+        csv_stats,
+        stats_col_index_map,
     );
 
     // invoke cmd::frequency to get unique values for each field
@@ -215,7 +378,7 @@ pub fn infer_schema_from_stats(args: &Args, input_filename: &str) -> CliResult<M
         let header_string = convert_to_string(header_byte_slice)?;
 
         // grab stats record for current column
-        let stats_record = csv_stats.get(i).unwrap().clone().to_record(4);
+        let stats_record = csv_stats.get(i).unwrap();
 
         debug!("stats[{header_string}]: {stats_record:?}");
 
@@ -353,12 +516,327 @@ pub fn infer_schema_from_stats(args: &Args, input_filename: &str) -> CliResult<M
     Ok(properties_map)
 }
 
-/// get stats records from `cmd::stats`
-/// returns tuple (`csv_fields`, `csv_stats`, `stats_col_index_map`)
-fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap<String, usize>)> {
+/// Builds a Frictionless Table Schema from the inferred JSON Schema properties. The field types
+/// come from the stats, as JSON Schema has no date types.
+fn table_schema(
+    properties_map: &Map<String, Value>,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StringRecord],
+    stats_col_index_map: &AHashMap<String, usize>,
+) -> CliResult<Value> {
+    let mut fields: Vec<Value> = Vec::with_capacity(csv_fields.len());
+    for (header, stats_record) in csv_fields.iter().zip(csv_stats) {
+        let name = convert_to_string(header)?;
+        let col_type = stats_record
+            .get(stats_col_index_map["type"])
+            .unwrap_or_default();
+        let field_type = match col_type {
+            "Integer" => "integer",
+            "Float" => "number",
+            "Date" => "date",
+            "DateTime" => "datetime",
+            "NULL" => "any",
+            _ => "string",
+        };
+        let property = properties_map.get(&name).unwrap_or(&Value::Null);
+
+        let mut field = Map::with_capacity(4);
+        field.insert("name".to_string(), Value::String(name.clone()));
+        field.insert("type".to_string(), Value::String(field_type.to_string()));
+        if let Some(description) = property.get("description") {
+            field.insert("description".to_string(), description.clone());
+        }
+        if field_type == "date" || field_type == "datetime" {
+            // qsv infers dates in many formats, not just the ISO 8601 ones of the
+            // Table Schema default format, unless --strict-dates is set
+            let format = if property.get("format").is_some() {
+                "default"
+            } else {
+                "any"
+            };
+            field.insert("format".to_string(), Value::String(format.to_string()));
+        }
+
+        let mut constraints = Map::new();
+        // only the columns without nulls have a JSON Schema type list without "null"
+        let has_nulls = property["type"]
+            .as_array()
+            .map_or(true, |types| types.contains(&json!("null")));
+        if !has_nulls {
+            constraints.insert("required".to_string(), Value::Bool(true));
+        }
+        for keyword in ["minLength", "maxLength", "minimum", "maximum"] {
+            if let Some(value) = property.get(keyword) {
+                constraints.insert(keyword.to_string(), value.clone());
+            }
+        }
+        if let Some(pattern) = property["pattern"].as_str() {
+            // Table Schema patterns are implicitly anchored
+            let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+            let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+            constraints.insert("pattern".to_string(), Value::String(pattern.to_string()));
+        }
+        if let Some(values) = property["enum"].as_array() {
+            let values = values
+                .iter()
+                .filter(|value| !value.is_null())
+                .cloned()
+                .collect();
+            constraints.insert("enum".to_string(), Value::Array(values));
+        }
+        if property["unique"] == Value::Bool(true) {
+            constraints.insert("unique".to_string(), Value::Bool(true));
+        }
+        if !constraints.is_empty() {
+            field.insert("constraints".to_string(), Value::Object(constraints));
+        }
+
+        fields.push(Value::Object(field));
+    }
+
+    Ok(json!({
+        "fields": fields,
+        "missingValues": [""]
+    }))
+}
+
+/// Builds a Frictionless Data Package with the input as its only resource
+fn data_package(
+    args: &Args,
+    input_path: &str,
+    input_filename: &str,
+    table_schema: Value,
+) -> CliResult<Value> {
+    // the input, not its sample
+    let rconfig = Config::new(&Some(input_path.to_string()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let rows = util::count_rows(&rconfig)?;
+    let bytes = std::fs::metadata(input_path)?.len();
+    let fields = table_schema["fields"].as_array().map_or(0, Vec::len);
+
+    let path = Path::new(input_filename);
+    // Data Package names are lowercase, with only alphanumerics, '-', '_' and '.'
+    let name: String = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let format = path.extension().map_or("csv".to_string(), |ext| {
+        ext.to_string_lossy().to_lowercase()
+    });
+    let mediatype = match format.as_str() {
+        "tsv" | "tab" => "text/tab-separated-values",
+        _ => "text/csv",
+    };
+
+    Ok(json!({
+        "name": name,
+        "resources": [{
+            "name": name,
+            "path": input_filename,
+            "profile": "tabular-data-resource",
+            "format": format,
+            "mediatype": mediatype,
+            "encoding": "utf-8",
+            "dialect": {
+                "delimiter": (rconfig.get_delimiter() as char).to_string(),
+                "header": !args.flag_no_headers
+            },
+            "bytes": bytes,
+            "schema": table_schema,
+            "stats": {
+                "bytes": bytes,
+                "fields": fields,
+                "rows": rows
+            }
+        }]
+    }))
+}
+
+/// writes a random sample of `sample_size` records of the input to `sample_path` using reservoir
+/// sampling, and returns the delimiter of the sample
+fn write_sample(args: &Args, sample_size: usize, sample_path: &str) -> CliResult<Delimiter> {
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rdr = rconfig.reader()?;
+    let delimiter = Delimiter(rconfig.get_delimiter());
+    let mut wtr = Config::new(&Some(sample_path.to_string()))
+        .delimiter(Some(delimiter))
+        .writer()?;
+    rconfig.write_headers(&mut rdr, &mut wtr)?;
+
+    // https://en.wikipedia.org/wiki/Reservoir_sampling
+    let mut reservoir: Vec<ByteRecord> = Vec::with_capacity(sample_size);
+    let mut rng = rand::thread_rng();
+    for (i, record) in rdr.byte_records().enumerate() {
+        let record = record?;
+        if i < sample_size {
+            reservoir.push(record);
+        } else {
+            let random = rng.gen_range(0..=i);
+            if random < sample_size {
+                reservoir[random] = record;
+            }
+        }
+    }
+    info!(
+        "inferring schema from a sample of {} records",
+        reservoir.len()
+    );
+
+    for record in reservoir {
+        wtr.write_byte_record(&record)?;
+    }
+    wtr.flush()?;
+    Ok(delimiter)
+}
+
+/// whether the value has the JSON Schema format
+fn has_format(value: &str, format: &str) -> bool {
+    match format {
+        "email" => regex_once_cell!(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").is_match(value),
+        "uri" => regex_once_cell!(r"^[a-zA-Z][a-zA-Z0-9+.-]*://\S+$").is_match(value),
+        "uuid" => regex_once_cell!(
+            r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$"
+        )
+        .is_match(value),
+        "ipv4" => value.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => value.parse::<Ipv6Addr>().is_ok(),
+        "date" => {
+            regex_once_cell!(r"^\d{4}-\d{2}-\d{2}$").is_match(value)
+                && NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        }
+        "date-time" => {
+            regex_once_cell!(r"^\d{4}-\d{2}-\d{2}T").is_match(value)
+                && DateTime::parse_from_rfc3339(value).is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// the format of the String, Date & DateTime columns whose non-empty values all have the
+/// same format
+fn infer_formats(
+    args: &Args,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StringRecord],
+    stats_col_index_map: &AHashMap<String, usize>,
+) -> CliResult<AHashMap<String, &'static str>> {
+    // the formats each column may still have
+    let mut candidates: Vec<Vec<&'static str>> = csv_stats
+        .iter()
+        .map(|stats_record| {
+            match stats_record
+                .get(stats_col_index_map["type"])
+                .unwrap_or_default()
+            {
+                "String" => vec!["email", "uri", "uuid", "ipv4", "ipv6"],
+                "Date" => vec!["date"],
+                "DateTime" => vec!["date-time"],
+                _ => Vec::new(),
+            }
+        })
+        .collect();
+    let mut has_values = vec![false; candidates.len()];
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rdr = rconfig.reader()?;
+    let mut record = ByteRecord::new();
+    while !candidates.iter().all(Vec::is_empty) && rdr.read_byte_record(&mut record)? {
+        for ((value, formats), has_value) in record
+            .iter()
+            .zip(candidates.iter_mut())
+            .zip(has_values.iter_mut())
+        {
+            let value = String::from_utf8_lossy(value);
+            let value = value.trim();
+            if formats.is_empty() || value.is_empty() {
+                continue;
+            }
+            *has_value = true;
+            formats.retain(|format| has_format(value, format));
+        }
+    }
+
+    let mut format_map = AHashMap::new();
+    for (i, formats) in candidates.into_iter().enumerate() {
+        if let (Some(format), true) = (formats.first(), has_values[i]) {
+            format_map.insert(convert_to_string(&csv_fields[i])?, *format);
+        }
+    }
+    Ok(format_map)
+}
+
+/// the names of the columns whose values are all unique, i.e. with a cardinality equal to the
+/// record count
+fn get_unique_fields(
+    record_count: u64,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StringRecord],
+    stats_col_index_map: &AHashMap<String, usize>,
+) -> CliResult<Vec<String>> {
+    let mut unique_fields = Vec::new();
+    for (header, stats_record) in csv_fields.iter().zip(csv_stats) {
+        let cardinality = stats_record
+            .get(stats_col_index_map["cardinality"])
+            .and_then(|cardinality| cardinality.parse::<u64>().ok())
+            .unwrap_or_default();
+        if record_count > 1 && cardinality == record_count {
+            unique_fields.push(convert_to_string(header)?);
+        }
+    }
+    Ok(unique_fields)
+}
+
+/// get stats records from the stats cache if its valid, otherwise from `cmd::stats`
+/// returns tuple (`csv_fields`, `csv_stats`, `stats_col_index_map`, `record_count`)
+fn get_stats_records(
+    args: &Args,
+) -> CliResult<(ByteRecord, Vec<StringRecord>, AHashMap<String, usize>, u64)> {
+    if let Some(cache) = StatsCache::load(&args.arg_input) {
+        let rconfig = Config::new(&args.arg_input)
+            .delimiter(args.flag_delimiter)
+            .no_headers(args.flag_no_headers);
+        if cache.has_cardinality()
+            && cache.same_dialect(rconfig.no_headers, rconfig.get_delimiter())
+            && cache.same_date_inference(true, &args.flag_dates_whitelist, args.flag_prefer_dmy)
+        {
+            info!("using stats cache");
+            let stats_col_index_map = cache
+                .headers
+                .iter()
+                .skip(1)
+                .enumerate()
+                .map(|(i, col)| (col.clone(), i))
+                .collect();
+            let csv_fields = cache.fields.iter().collect();
+            return Ok((
+                csv_fields,
+                cache.records(),
+                stats_col_index_map,
+                cache.record_count,
+            ));
+        }
+        info!("stats cache was computed with different options, ignoring it");
+    }
+
     let stats_args = crate::cmd::stats::Args {
         arg_input:            args.arg_input.clone(),
         flag_select:          crate::select::SelectColumns::parse("").unwrap(),
+        flag_groupby:         None,
         flag_everything:      false,
         flag_mode:            false,
         flag_cardinality:     true,
@@ -369,6 +847,7 @@ fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap
         flag_infer_dates:     true,
         flag_dates_whitelist: args.flag_dates_whitelist.to_string(),
         flag_prefer_dmy:      args.flag_prefer_dmy,
+        flag_cache:           false,
         flag_jobs:            Some(util::njobs(args.flag_jobs)),
         flag_output:          None,
         flag_no_headers:      args.flag_no_headers,
@@ -392,6 +871,9 @@ fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap
         }
     }?;
 
+    let record_count = csv_stats.first().map_or(0, crate::cmd::stats::Stats::count);
+    let csv_stats = stats_args.stats_to_records(csv_stats);
+
     let stats_columns = stats_args.stat_headers();
     debug!("stats columns: {stats_columns:?}");
 
@@ -404,7 +886,7 @@ fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap
         }
     }
 
-    Ok((csv_fields, csv_stats, stats_col_index_map))
+    Ok((csv_fields, csv_stats, stats_col_index_map, record_count))
 }
 
 /// get column selector argument string for low cardinality columns
@@ -412,7 +894,7 @@ fn build_low_cardinality_column_selector_arg(
     low_cardinality_column_indices: &mut Vec<usize>,
     enum_cardinality_threshold: usize,
     csv_fields: &ByteRecord,
-    csv_stats: &[Stats],
+    csv_stats: &[StringRecord],
     stats_col_index_map: &AHashMap<String, usize>,
 ) -> String {
     low_cardinality_column_indices.clear();
@@ -420,7 +902,7 @@ fn build_low_cardinality_column_selector_arg(
     // identify low cardinality columns
     for i in 0..csv_fields.len() {
         // grab stats record for current column
-        let stats_record = csv_stats.get(i).unwrap().clone().to_record(4);
+        let stats_record = csv_stats.get(i).unwrap();
 
         // get Cardinality
         let col_cardinality = match stats_record.get(stats_col_index_map["cardinality"]) {
@@ -453,15 +935,19 @@ fn get_unique_values(
 ) -> CliResult<AHashMap<String, Vec<String>>> {
     // prepare arg for invoking cmd::frequency
     let freq_args = crate::cmd::frequency::Args {
-        arg_input:       args.arg_input.clone(),
-        flag_select:     crate::select::SelectColumns::parse(column_select_arg).unwrap(),
-        flag_limit:      args.flag_enum_threshold,
-        flag_asc:        false,
-        flag_no_nulls:   true,
-        flag_jobs:       Some(util::njobs(args.flag_jobs)),
-        flag_output:     None,
-        flag_no_headers: args.flag_no_headers,
-        flag_delimiter:  args.flag_delimiter,
+        arg_input:            args.arg_input.clone(),
+        flag_select:          crate::select::SelectColumns::parse(column_select_arg).unwrap(),
+        flag_limit:           args.flag_enum_threshold,
+        flag_asc:             false,
+        flag_no_nulls:        true,
+        flag_approx:          false,
+        flag_approx_capacity: 0,
+        flag_combine:         false,
+        flag_weight:          None,
+        flag_jobs:            Some(util::njobs(args.flag_jobs)),
+        flag_output:          None,
+        flag_no_headers:      args.flag_no_headers,
+        flag_delimiter:       args.flag_delimiter,
     };
 
     let (headers, ftables) = match freq_args.rconfig().indexed()? {
@@ -0,0 +1,408 @@
+static USAGE: &str = r#"
+Compares two JSON Schemas of a CSV - e.g. the ones generated by `qsv schema` for two
+releases of a dataset - & classifies their differences as breaking or non-breaking.
+
+A change is breaking when the new schema can reject records the old one accepted:
+
+  * a removed column.
+  * a new required column, i.e. whose type doesn't allow nulls.
+  * a narrowed type, e.g. number to integer, or a column that became required.
+  * a tightened enum, i.e. with removed values, or a new enum.
+  * a tightened range or length, i.e. a higher minimum or minLength, a lower maximum or
+    maxLength, or a new one.
+  * a new or changed pattern or format, or a new "unique" constraint.
+
+The opposite changes - new optional columns, widened types, new enum values,
+loosened ranges, removed constraints... - are non-breaking. Descriptions are ignored.
+
+The report has a row per change:
+
+    field,change,impact,old,new,detail
+
+where change is one of removed, added, type, required, enum, minimum, maximum, minLength,
+maxLength, pattern, format & unique, impact is breaking or non-breaking, and old & new are
+the JSON values of the schema keyword. Or with --json, an object with the breaking &
+non-breaking change counts, and the changes.
+
+When there are breaking changes, the report is still output, but the exit code is 1,
+and 0 otherwise, so CI can block a breaking publish.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_schemadiff.rs.
+
+Usage:
+    qsv schemadiff [options] <old-schema> <new-schema>
+    qsv schemadiff --help
+
+schemadiff options:
+    --breaking-only            Only report the breaking changes.
+    --json                     Output the report as JSON.
+    --pretty-json              Same as --json, but pretty printed.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+"#;
+
+use std::{fs, io::Write};
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{config::Config, util, CliResult};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_old_schema:     String,
+    arg_new_schema:     String,
+    flag_breaking_only: bool,
+    flag_json:          bool,
+    flag_pretty_json:   bool,
+    flag_output:        Option<String>,
+}
+
+/// a difference between the old & new definitions of a column
+struct Change {
+    field:    String,
+    change:   &'static str,
+    breaking: bool,
+    old:      Option<Value>,
+    new:      Option<Value>,
+    detail:   String,
+}
+
+impl Change {
+    fn new(
+        field: &str,
+        change: &'static str,
+        breaking: bool,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        detail: String,
+    ) -> Change {
+        Change {
+            field: field.to_string(),
+            change,
+            breaking,
+            old: old.cloned(),
+            new: new.cloned(),
+            detail,
+        }
+    }
+
+    const fn impact(&self) -> &'static str {
+        if self.breaking {
+            "breaking"
+        } else {
+            "non-breaking"
+        }
+    }
+
+    fn to_record(&self) -> Vec<String> {
+        let to_string =
+            |value: &Option<Value>| value.as_ref().map(Value::to_string).unwrap_or_default();
+        vec![
+            self.field.clone(),
+            self.change.to_string(),
+            self.impact().to_string(),
+            to_string(&self.old),
+            to_string(&self.new),
+            self.detail.clone(),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "field": self.field,
+            "change": self.change,
+            "impact": self.impact(),
+            "old": self.old,
+            "new": self.new,
+            "detail": self.detail,
+        })
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let old_properties = load_properties(&args.arg_old_schema)?;
+    let new_properties = load_properties(&args.arg_new_schema)?;
+
+    let mut changes = Vec::new();
+    for (field, old) in &old_properties {
+        match new_properties.get(field) {
+            Some(new) => diff_property(field, old, new, &mut changes),
+            None => changes.push(Change::new(
+                field,
+                "removed",
+                true,
+                Some(old),
+                None,
+                "column removed".to_string(),
+            )),
+        }
+    }
+    for (field, new) in &new_properties {
+        if old_properties.contains_key(field) {
+            continue;
+        }
+        let required = !allows_null(new);
+        changes.push(Change::new(
+            field,
+            "added",
+            required,
+            None,
+            Some(new),
+            if required {
+                "required column added".to_string()
+            } else {
+                "optional column added".to_string()
+            },
+        ));
+    }
+
+    let breaking_count = changes.iter().filter(|change| change.breaking).count();
+    let non_breaking_count = changes.len() - breaking_count;
+    if args.flag_breaking_only {
+        changes.retain(|change| change.breaking);
+    }
+
+    if args.flag_json || args.flag_pretty_json {
+        let report = json!({
+            "breaking": breaking_count,
+            "non_breaking": non_breaking_count,
+            "changes": changes.iter().map(Change::to_json).collect::<Vec<Value>>(),
+        });
+        let json = if args.flag_pretty_json {
+            serde_json::to_string_pretty(&report)
+        } else {
+            serde_json::to_string(&report)
+        };
+        let Ok(json) = json else {
+            return fail!("Cannot serialize the schema changes to JSON.");
+        };
+        let mut wtr = Config::new(&args.flag_output).io_writer()?;
+        writeln!(wtr, "{json}")?;
+        wtr.flush()?;
+    } else {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        wtr.write_record(["field", "change", "impact", "old", "new", "detail"])?;
+        for change in &changes {
+            wtr.write_record(change.to_record())?;
+        }
+        wtr.flush()?;
+    }
+
+    if breaking_count > 0 {
+        return fail_clierror!(
+            "{breaking_count} breaking schema change{}.",
+            if breaking_count == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+/// the "properties" of the JSON Schema file
+fn load_properties(path: &str) -> CliResult<Map<String, Value>> {
+    let schema: Value = match fs::read_to_string(path) {
+        Ok(s) => match serde_json::from_str(&s) {
+            Ok(schema) => schema,
+            Err(e) => return fail_clierror!("Unable to parse the JSON Schema {path}: {e}"),
+        },
+        Err(e) => return fail_clierror!("Unable to read the JSON Schema {path}: {e}"),
+    };
+    match schema.get("properties") {
+        Some(Value::Object(properties)) => Ok(properties.clone()),
+        _ => fail_clierror!("The JSON Schema {path} has no 'properties' object."),
+    }
+}
+
+/// the types of a property, without "null". None when any type is allowed.
+fn types(property: &Value) -> Option<Vec<&str>> {
+    match property.get("type") {
+        Some(Value::String(typ)) => Some(vec![typ.as_str()]),
+        Some(Value::Array(types)) => Some(types.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+    .map(|types| types.into_iter().filter(|typ| *typ != "null").collect())
+}
+
+fn allows_null(property: &Value) -> bool {
+    match property.get("type") {
+        Some(Value::String(typ)) => typ == "null",
+        Some(Value::Array(types)) => types.iter().any(|typ| typ == "null"),
+        _ => true,
+    }
+}
+
+/// whether the types accept the values of the type, as JSON Schema numbers include integers
+fn accepts(types: &Option<Vec<&str>>, typ: &str) -> bool {
+    match types {
+        None => true,
+        Some(types) => types.contains(&typ) || (typ == "integer" && types.contains(&"number")),
+    }
+}
+
+fn diff_property(field: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    let old_types = types(old);
+    let new_types = types(new);
+    let narrowed = match old_types {
+        None => new_types.is_some(),
+        Some(ref old_types) => old_types.iter().any(|typ| !accepts(&new_types, typ)),
+    };
+    let same_types = match (&old_types, &new_types) {
+        (Some(old_types), Some(new_types)) => {
+            old_types.len() == new_types.len() && old_types.iter().all(|t| new_types.contains(t))
+        }
+        (old_types, new_types) => old_types.is_none() && new_types.is_none(),
+    };
+    if !same_types {
+        changes.push(Change::new(
+            field,
+            "type",
+            narrowed,
+            old.get("type"),
+            new.get("type"),
+            if narrowed {
+                "type narrowed".to_string()
+            } else {
+                "type widened".to_string()
+            },
+        ));
+    }
+
+    match (allows_null(old), allows_null(new)) {
+        (true, false) => changes.push(Change::new(
+            field,
+            "required",
+            true,
+            old.get("type"),
+            new.get("type"),
+            "column became required".to_string(),
+        )),
+        (false, true) => changes.push(Change::new(
+            field,
+            "required",
+            false,
+            old.get("type"),
+            new.get("type"),
+            "column became optional".to_string(),
+        )),
+        _ => {}
+    }
+
+    diff_enum(field, old.get("enum"), new.get("enum"), changes);
+
+    // the lower bounds are tightened when raised, the upper ones when lowered
+    for (keyword, is_lower_bound) in [
+        ("minimum", true),
+        ("maximum", false),
+        ("minLength", true),
+        ("maxLength", false),
+    ] {
+        let old_bound = old.get(keyword);
+        let new_bound = new.get(keyword);
+        let (breaking, detail) = match (
+            old_bound.and_then(Value::as_f64),
+            new_bound.and_then(Value::as_f64),
+        ) {
+            (None, None) => continue,
+            (None, Some(_)) => (true, format!("{keyword} added")),
+            (Some(_), None) => (false, format!("{keyword} removed")),
+            (Some(old_bound), Some(new_bound)) => {
+                if old_bound == new_bound {
+                    continue;
+                }
+                let tightened = (new_bound > old_bound) == is_lower_bound;
+                (
+                    tightened,
+                    format!(
+                        "{keyword} {}",
+                        if tightened { "tightened" } else { "loosened" }
+                    ),
+                )
+            }
+        };
+        changes.push(Change::new(
+            field, keyword, breaking, old_bound, new_bound, detail,
+        ));
+    }
+
+    for keyword in ["pattern", "format"] {
+        let old_value = old.get(keyword);
+        let new_value = new.get(keyword);
+        let (breaking, detail) = match (old_value, new_value) {
+            (None, None) => continue,
+            (None, Some(_)) => (true, format!("{keyword} added")),
+            (Some(_), None) => (false, format!("{keyword} removed")),
+            (Some(old_value), Some(new_value)) => {
+                if old_value == new_value {
+                    continue;
+                }
+                (true, format!("{keyword} changed"))
+            }
+        };
+        changes.push(Change::new(
+            field, keyword, breaking, old_value, new_value, detail,
+        ));
+    }
+
+    let old_unique = old.get("unique") == Some(&Value::Bool(true));
+    let new_unique = new.get("unique") == Some(&Value::Bool(true));
+    if old_unique != new_unique {
+        changes.push(Change::new(
+            field,
+            "unique",
+            new_unique,
+            old.get("unique"),
+            new.get("unique"),
+            if new_unique {
+                "unique constraint added".to_string()
+            } else {
+                "unique constraint removed".to_string()
+            },
+        ));
+    }
+}
+
+fn diff_enum(field: &str, old: Option<&Value>, new: Option<&Value>, changes: &mut Vec<Change>) {
+    let (breaking, detail) = match (old.and_then(Value::as_array), new.and_then(Value::as_array)) {
+        (None, None) => return,
+        (None, Some(_)) => (true, "enum added".to_string()),
+        (Some(_), None) => (false, "enum removed".to_string()),
+        (Some(old_values), Some(new_values)) => {
+            let display = |values: Vec<&Value>| {
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::String(s) => s.clone(),
+                        value => value.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            let removed: Vec<&Value> = old_values
+                .iter()
+                .filter(|value| !new_values.contains(value))
+                .collect();
+            let added: Vec<&Value> = new_values
+                .iter()
+                .filter(|value| !old_values.contains(value))
+                .collect();
+            match (removed.is_empty(), added.is_empty()) {
+                (true, true) => return,
+                (true, false) => (false, format!("enum values added: {}", display(added))),
+                (false, true) => (true, format!("enum values removed: {}", display(removed))),
+                (false, false) => (
+                    true,
+                    format!(
+                        "enum values removed: {}; added: {}",
+                        display(removed),
+                        display(added)
+                    ),
+                ),
+            }
+        }
+    };
+    changes.push(Change::new(field, "enum", breaking, old, new, detail));
+}
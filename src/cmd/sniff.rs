@@ -9,6 +9,9 @@ to adjust).
 If you want more robust, guaranteed schemata, use the "schema" or "stats" commands
 instead as they scan the entire file.
 
+If the input has a valid stats cache (see `qsv stats --help`), the number of records
+is taken from it instead of counting them.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_sniff.rs.
 
 Usage:
@@ -36,7 +39,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use thousands::Separable;
 
-use crate::{config::Config, util, CliResult};
+use crate::{cmd::stats::StatsCache, config::Config, util, CliResult};
 
 #[derive(Deserialize)]
 struct Args {
@@ -78,7 +81,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
     let conf = Config::new(&args.arg_input).flexible(true).checkutf8(false);
-    let n_rows = util::count_rows(&conf)?;
+    let n_rows = match StatsCache::load(&args.arg_input) {
+        // the cached record count excludes the header row, same as count_rows
+        Some(cache) if cache.same_dialect(conf.no_headers, conf.get_delimiter()) => {
+            cache.record_count
+        }
+        _ => util::count_rows(&conf)?,
+    };
 
     let mut sample_size = args.flag_sample;
     let mut sample_all = false;
@@ -26,6 +26,16 @@ is scanned, and not just sampled.
 Computing statistics on a large file can be made much faster if you create an index for it
 first with 'qsv index' to enable multithreading.
 
+With --cache, the computed statistics are also saved to a "<input>.stats.json" cache file
+next to the input. The cache is tagged with the input's size, its last modified time and
+the stats options used. While the cache is still valid, the stats, schema, tojsonl, frequency
+and sniff commands reuse it instead of rescanning the input.
+
+With --groupby, the statistics are computed separately for each distinct combination of
+values in the groupby columns. The group key values are emitted as the leading columns of
+each stats row, and groups are sorted by their key values. This saves having to partition
+the CSV and run stats on each partition.
+
 Usage:
     qsv stats [options] [<input>]
     qsv stats --help
@@ -35,6 +45,9 @@ stats options:
                               See 'qsv select --help' for the format details.
                               This is provided here because piping 'qsv select'
                               into 'qsv stats' will disable the use of indexing.
+    -g, --groupby <cols>      Compute the statistics for each group of rows sharing
+                              the same values in the <cols> columns.
+                              See 'qsv select --help' for the format details.
     --everything              Show all statistics available.
     --mode                    Show the mode/s. Multimodal-aware.
                               This requires loading all CSV data in memory.
@@ -64,6 +77,9 @@ stats options:
                               [default: date,time,due,opened,closed]
     --prefer-dmy              Parse dates in dmy format. Otherwise, use mdy format.
                               Ignored if --infer-dates is false.
+    --cache                   Save the statistics to a "<input>.stats.json" cache file.
+                              Ignored when reading from stdin, or when --select or
+                              --groupby is used, as the cache covers all columns.
     -j, --jobs <arg>          The number of jobs to run in parallel.
                               This works only when the given CSV has an index.
                               Note that a file handle is opened for each job.
@@ -82,17 +98,20 @@ Common options:
 
 use std::{
     borrow::ToOwned,
+    collections::{btree_map::Entry, BTreeMap},
     default::Default,
-    fmt, fs, io,
+    env, fmt, fs, io,
     iter::repeat,
+    path::PathBuf,
     str::{self, FromStr},
     sync::atomic::{AtomicBool, Ordering},
 };
 
+use filetime::FileTime;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use qsv_dateparser::parse_with_preference;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use stats::{merge_all, Commute, MinMax, OnlineStats, Unsorted};
 use threadpool::ThreadPool;
 
@@ -109,6 +128,7 @@ use crate::{
 pub struct Args {
     pub arg_input:            Option<String>,
     pub flag_select:          SelectColumns,
+    pub flag_groupby:         Option<SelectColumns>,
     pub flag_everything:      bool,
     pub flag_mode:            bool,
     pub flag_cardinality:     bool,
@@ -119,12 +139,138 @@ pub struct Args {
     pub flag_infer_dates:     bool,
     pub flag_dates_whitelist: String,
     pub flag_prefer_dmy:      bool,
+    pub flag_cache:           bool,
     pub flag_jobs:            Option<usize>,
     pub flag_output:          Option<String>,
     pub flag_no_headers:      bool,
     pub flag_delimiter:       Option<Delimiter>,
 }
 
+type ByteString = Vec<u8>;
+
+/// stats for each group, keyed by the values of the groupby columns
+pub type GroupedStats = BTreeMap<Vec<ByteString>, Vec<Stats>>;
+
+const STATS_CACHE_EXT: &str = ".stats.json";
+
+/// the stats options that determine the contents of a stats cache
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsCacheOptions {
+    pub everything:      bool,
+    pub mode:            bool,
+    pub cardinality:     bool,
+    pub median:          bool,
+    pub quartiles:       bool,
+    pub round:           u8,
+    pub nulls:           bool,
+    pub infer_dates:     bool,
+    pub dates_whitelist: String,
+    pub prefer_dmy:      bool,
+    pub no_headers:      bool,
+    pub delimiter:       char,
+}
+
+/// `StatsCache` is the machine-readable "<input>.stats.json" sidecar written by
+/// `stats --cache`. It's only valid while the input's size & last modified time
+/// match the ones it was computed from. The modified time is kept with sub-second
+/// precision, so a same-size rewrite of the input in the same second invalidates it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsCache {
+    pub qsv_version:  String,
+    pub input_size:   u64,
+    /// the last modified time of the input, in nanoseconds since the Unix epoch
+    pub input_mtime:  u64,
+    pub record_count: u64,
+    pub options:      StatsCacheOptions,
+    /// the stats column names, starting with "field"
+    pub headers:      Vec<String>,
+    pub fields:       Vec<String>,
+    /// one stats record per field, without the leading field name
+    pub stats:        Vec<Vec<String>>,
+    /// per field, whether some of its values have leading or trailing whitespace, so
+    /// its cardinality may be higher than the one of its trimmed values
+    #[serde(default)]
+    pub padded:       Vec<bool>,
+}
+
+impl StatsCache {
+    pub fn path(input: &str) -> PathBuf {
+        PathBuf::from(format!("{input}{STATS_CACHE_EXT}"))
+    }
+
+    /// Loads the stats cache of `input` if there is one, and it is still valid.
+    pub fn load(input: &Option<String>) -> Option<StatsCache> {
+        let input = match input {
+            Some(input) if input != "-" => input,
+            _ => return None,
+        };
+        let input_md = fs::metadata(input).ok()?;
+        let cache_file = fs::File::open(StatsCache::path(input)).ok()?;
+        let cache: StatsCache = match serde_json::from_reader(io::BufReader::new(cache_file)) {
+            Ok(cache) => cache,
+            Err(e) => {
+                log::warn!("ignoring unreadable stats cache of {input}: {e}");
+                return None;
+            }
+        };
+        if cache.qsv_version != env!("CARGO_PKG_VERSION")
+            || cache.input_size != input_md.len()
+            || cache.input_mtime != last_modified_nanos(&input_md)
+        {
+            log::info!("ignoring stale stats cache of {input}");
+            return None;
+        }
+        log::info!("found valid stats cache of {input}");
+        Some(cache)
+    }
+
+    /// returns the position of the `stat` column in the cached stats records
+    pub fn stat_index(&self, stat: &str) -> Option<usize> {
+        self.headers.iter().skip(1).position(|h| h == stat)
+    }
+
+    pub const fn has_cardinality(&self) -> bool {
+        self.options.cardinality || self.options.everything
+    }
+
+    pub fn same_dialect(&self, no_headers: bool, delimiter: u8) -> bool {
+        self.options.no_headers == no_headers && self.options.delimiter == delimiter as char
+    }
+
+    /// checks if the cached data types were inferred with date inference settings
+    /// equivalent to the given ones, i.e. they shortlist the same columns
+    pub fn same_date_inference(
+        &self,
+        infer_dates: bool,
+        whitelist: &str,
+        prefer_dmy: bool,
+    ) -> bool {
+        let cached = date_inference_flags(
+            &self.fields,
+            self.options.infer_dates,
+            &self.options.dates_whitelist,
+        );
+        let wanted = date_inference_flags(&self.fields, infer_dates, whitelist);
+        cached == wanted && (!wanted.contains(&true) || self.options.prefer_dmy == prefer_dmy)
+    }
+
+    pub fn records(&self) -> Vec<csv::StringRecord> {
+        self.stats
+            .iter()
+            .map(|stat| csv::StringRecord::from(stat.clone()))
+            .collect()
+    }
+}
+
+// the last modified time in nanoseconds, as util::last_modified only has second resolution
+fn last_modified_nanos(md: &fs::Metadata) -> u64 {
+    let mtime = FileTime::from_last_modification_time(md);
+    (mtime.unix_seconds() as u64)
+        .saturating_mul(1_000_000_000)
+        .saturating_add(u64::from(mtime.nanoseconds()))
+}
+
 static INFER_DATE_FLAGS: once_cell::sync::OnceCell<Vec<bool>> = OnceCell::new();
 static DMY_PREFERENCE: AtomicBool = AtomicBool::new(false);
 
@@ -132,6 +278,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
+    if args.flag_groupby.is_some() {
+        return run_grouped(&args, &mut wtr);
+    }
+
+    let cacheable = args
+        .arg_input
+        .as_deref()
+        .map_or(false, |input| input != "-")
+        && args.selects_all_columns()?;
+    if cacheable {
+        if let Some(cache) = StatsCache::load(&args.arg_input) {
+            if cache.options == args.cache_options() {
+                return write_cached_stats(&args, &cache, &mut wtr);
+            }
+        }
+    }
+
     let (headers, stats) = match args.rconfig().indexed()? {
         None => args.sequential_stats(&args.flag_dates_whitelist),
         Some(idx) => {
@@ -146,8 +309,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
         }
     }?;
+    // every record has a value for each column, so all the columns have the record count
+    let record_count = stats.first().map_or(0, |stat| stat.count);
+    let padded: Vec<bool> = stats.iter().map(|stat| stat.padded).collect();
     let stats = args.stats_to_records(stats);
 
+    if args.flag_cache {
+        if cacheable {
+            args.write_stats_cache(&headers, &stats, record_count, padded)?;
+        } else {
+            log::warn!("--cache ignored. Only stats of all the columns of a file are cached.");
+        }
+    }
+
     wtr.write_record(&args.stat_headers())?;
     let fields = headers.iter().zip(stats.into_iter());
     for (i, (header, stat)) in fields.enumerate() {
@@ -163,6 +337,68 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+fn write_cached_stats(
+    args: &Args,
+    cache: &StatsCache,
+    wtr: &mut csv::Writer<Box<dyn io::Write>>,
+) -> CliResult<()> {
+    wtr.write_record(&cache.headers)?;
+    for (i, (field, stat)) in cache.fields.iter().zip(cache.stats.iter()).enumerate() {
+        let field = if args.flag_no_headers {
+            i.to_string()
+        } else {
+            field.clone()
+        };
+        wtr.write_record(std::iter::once(&field).chain(stat.iter()))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+fn run_grouped(args: &Args, wtr: &mut csv::Writer<Box<dyn io::Write>>) -> CliResult<()> {
+    let (headers, group_headers, groups) = match args.rconfig().indexed()? {
+        Some(idx) if util::njobs(args.flag_jobs) > 1 => {
+            args.parallel_grouped_stats(&args.flag_dates_whitelist, &idx)
+        }
+        _ => args.sequential_grouped_stats(&args.flag_dates_whitelist),
+    }?;
+
+    let mut header_row = group_headers;
+    for stat_header in &args.stat_headers() {
+        header_row.push_field(stat_header.as_bytes());
+    }
+    wtr.write_record(&header_row)?;
+
+    // convert the stats of all the groups in one go, so we only spin up one threadpool
+    let (keys, stats): (Vec<_>, Vec<_>) = groups.into_iter().unzip();
+    let mut records = args
+        .stats_to_records(stats.into_iter().flatten().collect())
+        .into_iter();
+
+    let mut row = csv::ByteRecord::new();
+    for key in &keys {
+        for (i, header) in headers.iter().enumerate() {
+            row.clear();
+            for key_field in key {
+                row.push_field(key_field);
+            }
+            if args.flag_no_headers {
+                row.push_field(i.to_string().as_bytes());
+            } else {
+                row.push_field(header);
+            }
+            if let Some(stat) = records.next() {
+                for piece in &stat {
+                    row.push_field(piece.as_bytes());
+                }
+            }
+            wtr.write_byte_record(&row)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
 impl Args {
     pub fn sequential_stats(&self, whitelist: &str) -> CliResult<(csv::ByteRecord, Vec<Stats>)> {
         let mut rdr = self.rconfig().reader()?;
@@ -222,6 +458,140 @@ impl Args {
         Ok((headers, merge_all(recv.iter()).unwrap_or_default()))
     }
 
+    pub fn sequential_grouped_stats(
+        &self,
+        whitelist: &str,
+    ) -> CliResult<(csv::ByteRecord, csv::ByteRecord, GroupedStats)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (group_headers, groupby) = self.groupby_headers(&mut rdr)?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        init_date_inference(
+            self.flag_infer_dates,
+            self.flag_prefer_dmy,
+            &headers,
+            whitelist,
+        )?;
+
+        let groups = self.compute_grouped(&sel, &groupby, rdr.byte_records());
+        Ok((headers, group_headers, groups))
+    }
+
+    pub fn parallel_grouped_stats(
+        &self,
+        whitelist: &str,
+        idx: &Indexed<fs::File, fs::File>,
+    ) -> CliResult<(csv::ByteRecord, csv::ByteRecord, GroupedStats)> {
+        if idx.count() == 0 {
+            return self.sequential_grouped_stats(whitelist);
+        }
+
+        let mut rdr = self.rconfig().reader()?;
+        let (group_headers, groupby) = self.groupby_headers(&mut rdr)?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        init_date_inference(
+            self.flag_infer_dates,
+            self.flag_prefer_dmy,
+            &headers,
+            whitelist,
+        )?;
+
+        let chunk_size = util::chunk_size(idx.count() as usize, util::njobs(self.flag_jobs));
+        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+
+        let pool = ThreadPool::new(util::njobs(self.flag_jobs));
+        let (send, recv) = channel::bounded(0);
+        for i in 0..nchunks {
+            let (send, args, sel, groupby) =
+                (send.clone(), self.clone(), sel.clone(), groupby.clone());
+            pool.execute(move || unsafe {
+                let mut idx = args
+                    .rconfig()
+                    .indexed()
+                    .unwrap_unchecked()
+                    .unwrap_unchecked();
+                idx.seek((i * chunk_size) as u64).unwrap_unchecked();
+                let it = idx.byte_records().take(chunk_size);
+                send.send(args.compute_grouped(&sel, &groupby, it))
+                    .unwrap_unchecked();
+            });
+        }
+        drop(send);
+
+        let mut groups = GroupedStats::new();
+        for chunk_groups in recv.iter() {
+            merge_groups(&mut groups, chunk_groups);
+        }
+        Ok((headers, group_headers, groups))
+    }
+
+    /// the stats cache only covers stats on all the columns, in their original order
+    fn selects_all_columns(&self) -> CliResult<bool> {
+        if self.flag_groupby.is_some() {
+            return Ok(false);
+        }
+        let mut rdr = self.rconfig().reader()?;
+        let headers = rdr.byte_headers()?.clone();
+        let sel = self.rconfig().selection(&headers)?;
+        Ok(sel.iter().copied().eq(0..headers.len()))
+    }
+
+    pub fn cache_options(&self) -> StatsCacheOptions {
+        let rconfig = self.rconfig();
+        StatsCacheOptions {
+            everything:      self.flag_everything,
+            mode:            self.flag_mode,
+            cardinality:     self.flag_cardinality,
+            median:          self.flag_median,
+            quartiles:       self.flag_quartiles,
+            round:           self.flag_round,
+            nulls:           self.flag_nulls,
+            infer_dates:     self.flag_infer_dates,
+            dates_whitelist: self.flag_dates_whitelist.to_lowercase(),
+            prefer_dmy:      self.flag_prefer_dmy || env::var("QSV_PREFER_DMY").is_ok(),
+            no_headers:      rconfig.no_headers,
+            delimiter:       rconfig.get_delimiter() as char,
+        }
+    }
+
+    fn write_stats_cache(
+        &self,
+        headers: &csv::ByteRecord,
+        stats: &[csv::StringRecord],
+        record_count: u64,
+        padded: Vec<bool>,
+    ) -> CliResult<()> {
+        // cacheable implies we have an input file
+        let input = self.arg_input.clone().unwrap_or_default();
+        let input_md = fs::metadata(&input)?;
+        let cache = StatsCache {
+            qsv_version: env!("CARGO_PKG_VERSION").to_string(),
+            input_size: input_md.len(),
+            input_mtime: last_modified_nanos(&input_md),
+            record_count,
+            options: self.cache_options(),
+            headers: self.stat_headers().iter().map(String::from).collect(),
+            fields: headers
+                .iter()
+                .map(|h| String::from_utf8_lossy(h).to_string())
+                .collect(),
+            stats: stats
+                .iter()
+                .map(|stat| stat.iter().map(String::from).collect())
+                .collect(),
+            padded,
+        };
+
+        let cache_path = StatsCache::path(&input);
+        let cache_file = io::BufWriter::new(fs::File::create(&cache_path)?);
+        if let Err(e) = serde_json::to_writer_pretty(cache_file, &cache) {
+            return fail_clierror!("Cannot write stats cache {}: {e}", cache_path.display());
+        }
+        log::info!("stats cache written to {}", cache_path.display());
+        Ok(())
+    }
+
     pub fn stats_to_records(&self, stats: Vec<Stats>) -> Vec<csv::StringRecord> {
         let round_places = self.flag_round;
         let mut records = Vec::with_capacity(stats.len());
@@ -265,6 +635,56 @@ impl Args {
         stats
     }
 
+    #[inline]
+    fn compute_grouped<I>(&self, sel: &Selection, groupby: &Selection, it: I) -> GroupedStats
+    where
+        I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+    {
+        let mut groups = GroupedStats::new();
+
+        #[allow(unused_assignments)]
+        let mut record = csv::ByteRecord::with_capacity(100, sel.len());
+        let mut key: Vec<ByteString> = Vec::with_capacity(groupby.len());
+        for row in it {
+            record = unsafe { row.unwrap_unchecked() };
+            key.clear();
+            key.extend(groupby.select(&record).map(<[u8]>::to_vec));
+
+            // only allocate a new key when we see a new group
+            if !groups.contains_key(&key) {
+                groups.insert(key.clone(), self.new_stats(sel.len()));
+            }
+            let stats = unsafe { groups.get_mut(&key).unwrap_unchecked() };
+            for (i, field) in sel.select(&record).enumerate() {
+                unsafe {
+                    stats
+                        .get_unchecked_mut(i)
+                        .add(field, *INFER_DATE_FLAGS.get_unchecked().get_unchecked(i));
+                }
+            }
+        }
+        groups
+    }
+
+    /// returns the names of the groupby columns and their selection
+    /// against the full, unselected headers
+    fn groupby_headers<R: io::Read>(
+        &self,
+        rdr: &mut csv::Reader<R>,
+    ) -> CliResult<(csv::ByteRecord, Selection)> {
+        let headers = rdr.byte_headers()?.clone();
+        let groupby = match self.flag_groupby {
+            Some(ref cols) => cols.selection(&headers, !self.flag_no_headers)?,
+            None => return fail_clierror!("--groupby columns are required for grouped stats."),
+        };
+        let group_headers = if self.flag_no_headers {
+            groupby.iter().map(|i| i.to_string().into_bytes()).collect()
+        } else {
+            groupby.select(&headers).collect()
+        };
+        Ok((group_headers, groupby))
+    }
+
     fn sel_headers<R: io::Read>(
         &self,
         rdr: &mut csv::Reader<R>,
@@ -343,6 +763,18 @@ impl Args {
     }
 }
 
+/// merges the per-group stats of `other` into `groups`
+fn merge_groups(groups: &mut GroupedStats, other: GroupedStats) {
+    for (key, stats) in other {
+        match groups.entry(key) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(stats),
+            Entry::Vacant(entry) => {
+                entry.insert(stats);
+            }
+        }
+    }
+}
+
 #[inline]
 fn init_date_inference(
     infer_dates: bool,
@@ -353,51 +785,56 @@ fn init_date_inference(
     if infer_dates {
         let dmy_preferred = prefer_dmy || std::env::var("QSV_PREFER_DMY").is_ok();
         DMY_PREFERENCE.store(dmy_preferred, Ordering::Relaxed);
+        log::info!(
+            "inferring dates with date-whitelist: {} and DMY preference: {dmy_preferred}",
+            flag_whitelist.to_lowercase()
+        );
+    }
 
-        let whitelist_lower = flag_whitelist.to_lowercase();
-        log::info!("inferring dates with date-whitelist: {whitelist_lower}");
+    let infer_date_flags = date_inference_flags(headers, infer_dates, flag_whitelist);
+    for (header, _) in headers
+        .iter()
+        .zip(infer_date_flags.iter())
+        .filter(|(_, infer)| **infer)
+    {
+        log::info!("inferring dates for {}", String::from_utf8_lossy(header));
+    }
+    match INFER_DATE_FLAGS.set(infer_date_flags) {
+        Ok(_) => Ok(()),
+        Err(e) => fail_format!("Cannot init date inference flags: {e:?}"),
+    }
+}
 
-        if whitelist_lower == "all" {
-            log::info!("inferring dates for ALL fields with DMY preference: {dmy_preferred}");
-            match INFER_DATE_FLAGS.set(vec![true; headers.len()]) {
-                Ok(_) => (),
-                Err(e) => {
-                    return fail_format!("Cannot init date inference flags for ALL fields: {e:?}")
-                }
-            };
-        } else {
-            let whitelist = whitelist_lower
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect_vec();
-
-            let mut infer_date_flags: Vec<bool> = Vec::with_capacity(headers.len());
-            for header in headers {
-                let header_str = from_bytes::<String>(header).to_lowercase();
-                let mut date_found = false;
-                for whitelist_item in &whitelist {
-                    if header_str.contains(whitelist_item) {
-                        date_found = true;
-                        log::info!(
-                            "inferring dates for {header_str} with DMY preference: {dmy_preferred}"
-                        );
-                        break;
-                    }
-                }
-                infer_date_flags.push(date_found);
-            }
-            match INFER_DATE_FLAGS.set(infer_date_flags) {
-                Ok(_) => (),
-                Err(e) => return fail_format!("Cannot init date inference flags: {e:?}"),
-            };
-        }
-    } else {
-        match INFER_DATE_FLAGS.set(vec![false; headers.len()]) {
-            Ok(_) => (),
-            Err(e) => return fail_format!("Cannot init empty date inference flags: {e:?}"),
-        };
+/// Shortlists the fields for date inferencing. A field is shortlisted if its
+/// lowercased name contains any of the comma-separated whitelist patterns,
+/// or if the whitelist is "all".
+fn date_inference_flags<H, I>(headers: I, infer_dates: bool, flag_whitelist: &str) -> Vec<bool>
+where
+    H: AsRef<[u8]>,
+    I: IntoIterator<Item = H>,
+{
+    let headers = headers.into_iter();
+    if !infer_dates {
+        return headers.map(|_| false).collect();
     }
-    Ok(())
+
+    let whitelist_lower = flag_whitelist.to_lowercase();
+    if whitelist_lower == "all" {
+        return headers.map(|_| true).collect();
+    }
+    let whitelist = whitelist_lower
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .collect_vec();
+
+    headers
+        .map(|header| {
+            let header_str = String::from_utf8_lossy(header.as_ref()).to_lowercase();
+            whitelist
+                .iter()
+                .any(|whitelist_item| header_str.contains(whitelist_item))
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -426,13 +863,23 @@ pub struct Stats {
     minmax:    Option<TypedMinMax>,
     online:    Option<OnlineStats>,
     nullcount: u64,
+    // the number of values, i.e. of records
+    count:     u64,
+    // whether some values have leading or trailing whitespace
+    padded:    bool,
     modes:     Option<Unsorted<Vec<u8>>>,
     median:    Option<Unsorted<f64>>,
     quartiles: Option<Unsorted<f64>>,
     which:     WhichStats,
 }
 
-fn round_num(dec_f64: f64, places: u8) -> String {
+// whether the value has leading or trailing whitespace, as trimmed by str::trim
+fn is_padded(sample: &[u8]) -> bool {
+    let value = String::from_utf8_lossy(sample);
+    value.trim().len() != value.len()
+}
+
+pub fn round_num(dec_f64: f64, places: u8) -> String {
     use rust_decimal::prelude::*;
 
     let dec_num = Decimal::from_f64(dec_f64).unwrap_or_default();
@@ -469,6 +916,8 @@ impl Stats {
             minmax,
             online,
             nullcount: 0,
+            count: 0,
+            padded: false,
             modes,
             median,
             quartiles,
@@ -480,6 +929,7 @@ impl Stats {
     fn add(&mut self, sample: &[u8], infer_dates: bool) {
         let sample_type = FieldType::from_sample(infer_dates, sample);
         self.typ.merge(sample_type);
+        self.count += 1;
 
         let t = self.typ;
         if let Some(v) = self.sum.as_mut() {
@@ -490,6 +940,9 @@ impl Stats {
         };
         if let Some(v) = self.modes.as_mut() {
             v.add(sample.to_vec());
+            if !self.padded {
+                self.padded = is_padded(sample);
+            }
         };
         if sample_type == TNull {
             self.nullcount += 1;
@@ -527,6 +980,11 @@ impl Stats {
         }
     }
 
+    /// the number of values, i.e. of records
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_record(&mut self, round_places: u8) -> csv::StringRecord {
         let typ = self.typ;
@@ -674,6 +1132,8 @@ impl Commute for Stats {
         self.minmax.merge(other.minmax);
         self.online.merge(other.online);
         self.nullcount += other.nullcount;
+        self.count += other.count;
+        self.padded |= other.padded;
         self.modes.merge(other.modes);
         self.median.merge(other.median);
         self.quartiles.merge(other.quartiles);
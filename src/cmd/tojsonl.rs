@@ -78,6 +78,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         // json doesn't have a date type, so don't infer dates
         flag_dates_whitelist: "none".to_string(),
         flag_prefer_dmy:      false,
+        flag_infer_formats:   false,
+        flag_unique:          false,
+        flag_sample:          None,
+        flag_format:          crate::cmd::schema::SchemaFormat::JsonSchema,
         flag_stdout:          false,
         flag_jobs:            Some(util::njobs(args.flag_jobs)),
         flag_no_headers:      false,
@@ -1,6 +1,8 @@
 static USAGE: &str = r#"
-Validate CSV data with JSON Schema, and put invalid records into a separate file.
-When run without JSON Schema, only a simple CSV check (RFC 4180) is performed.
+Validate CSV data with JSON Schema and/or primary & foreign key constraints, and put
+invalid records into a separate file.
+When run without JSON Schema or key constraints, only a simple CSV check (RFC 4180)
+is performed.
 
 Example output files from `mydata.csv`. If piped from stdin, then filename is `stdin.csv`.
 
@@ -8,23 +10,52 @@ Example output files from `mydata.csv`. If piped from stdin, then filename is `s
 * mydata.csv.invalid
 * mydata.csv.validation-errors.tsv
 
-JSON Schema can be a local file or a URL.
+JSON Schema can be a local file or a URL. The values of JSON Schema properties marked
+with "unique": true (see `qsv schema --unique`) must also be unique, except empty ones.
+
+A Frictionless Table Schema (https://specs.frictionlessdata.io/table-schema/) can be used
+instead of JSON Schema, as can a Data Package (e.g. datapackage.json) whose resource for
+the input has a Table Schema. Its field types, formats, constraints (required, unique,
+minLength, maxLength, minimum, maximum, pattern & enum), missingValues and primaryKey
+are validated. See `qsv schema --format` to generate them.
+
+The values of the --primary-key columns must be unique, and not empty. With several
+columns, their combinations must be unique, and none of their values empty.
+
+Each --foreign-key is declared as "<columns>:<reference csv>:<reference columns>", e.g.
+"country_id:countries.csv:id". The values of the columns (or their combinations with
+several columns) must exist in the reference columns of the reference CSV, except when
+they're all empty. The option can be repeated for several foreign keys.
+
+The rows violating the key constraints are put into the invalid file, and their errors
+into the validation-errors.tsv file, like the rows that are invalid per the JSON Schema.
 
 Returns exitcode 0 when the CSV file is valid, exitcode 1 otherwise.
 
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_validate.rs.
 
 Usage:
-    qsv validate [options] [<input>] [<json-schema>]
+    qsv validate [--foreign-key <spec>...] [options] [<input>] [<json-schema>]
     qsv validate --help
 
 Validate options:
     --fail-fast                Stops on first error.
+    --primary-key <cols>       The columns of the primary key. See 'qsv select --help'
+                               for the format details.
+    --foreign-key <spec>       A foreign key, as "<columns>:<reference csv>:<reference columns>".
     --valid <suffix>           Valid record output file suffix. [default: valid]
     --invalid <suffix>         Invalid record output file suffix. [default: invalid]
     --json                     When validating without a schema, return the RFC 4180 check
                                as a JSON file instead of a message.
     --pretty-json              Same as --json, but pretty printed.
+    --summary <file>           Also write a JSON summary of the validation to <file>, with
+                               the valid & invalid record counts, and the error counts by
+                               column and by keyword (e.g. type, enum, pattern, minimum,
+                               primaryKey), each with example row numbers & values.
+                               Set to "-" to write it to stdout.
+                               Requires a schema or key constraints.
+    --summary-examples <n>     The maximum number of examples for each column & keyword
+                               in the summary. [default: 5]
     -j, --jobs <arg>           The number of jobs to run in parallel.
                                When not set, the number of jobs is set to the
                                number of CPUs detected.
@@ -45,15 +76,17 @@ use std::{
     env,
     fs::File,
     io::{BufReader, BufWriter, Read, Write},
+    path::Path,
     str,
 };
 
+use ahash::{AHashMap, AHashSet};
 use csv::ByteRecord;
 #[cfg(any(feature = "full", feature = "lite"))]
 use indicatif::{ProgressBar, ProgressDrawTarget};
 use itertools::Itertools;
 use jsonschema::{output::BasicOutput, paths::PathChunk, JSONSchema};
-use log::info;
+use log::{info, warn};
 use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -62,6 +95,7 @@ use thousands::Separable;
 
 use crate::{
     config::{Config, Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    select::SelectColumns,
     util, CliResult,
 };
 
@@ -73,17 +107,21 @@ static NULL_TYPE: once_cell::sync::OnceCell<Value> = OnceCell::new();
 
 #[derive(Deserialize)]
 struct Args {
-    flag_fail_fast:   bool,
-    flag_valid:       Option<String>,
-    flag_invalid:     Option<String>,
-    flag_json:        bool,
-    flag_pretty_json: bool,
-    flag_jobs:        Option<usize>,
-    flag_no_headers:  bool,
-    flag_delimiter:   Option<Delimiter>,
-    flag_progressbar: bool,
-    arg_input:        Option<String>,
-    arg_json_schema:  Option<String>,
+    flag_fail_fast:        bool,
+    flag_primary_key:      Option<SelectColumns>,
+    flag_foreign_key:      Vec<String>,
+    flag_valid:            Option<String>,
+    flag_invalid:          Option<String>,
+    flag_json:             bool,
+    flag_pretty_json:      bool,
+    flag_summary:          Option<String>,
+    flag_summary_examples: usize,
+    flag_jobs:             Option<usize>,
+    flag_no_headers:       bool,
+    flag_delimiter:        Option<Delimiter>,
+    flag_progressbar:      bool,
+    arg_input:             Option<String>,
+    arg_json_schema:       Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -129,8 +167,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         progress.set_draw_target(ProgressDrawTarget::hidden());
     }
 
-    // if no json schema supplied, only let csv reader RFC4180-validate csv file
-    if args.arg_json_schema.is_none() {
+    let has_key_constraints = args.flag_primary_key.is_some() || !args.flag_foreign_key.is_empty();
+
+    // if no json schema or key constraints supplied, only let csv reader RFC4180-validate csv file
+    if args.arg_json_schema.is_none() && !has_key_constraints {
+        if args.flag_summary.is_some() {
+            return fail_clierror!("--summary requires a schema or key constraints.");
+        }
+
         // just read csv file and let csv reader report problems
 
         let mut header_msg = String::new();
@@ -228,32 +272,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let headers = rdr.byte_headers()?.clone();
     let headers_len = headers.len();
 
-    // parse and compile supplied JSON Schema
-    let (schema_json, schema_compiled): (Value, JSONSchema) =
-        match load_json(&args.arg_json_schema.unwrap()) {
-            Ok(s) => {
-                // parse JSON string
-                match serde_json::from_str(&s) {
-                    Ok(json) => {
-                        // compile JSON Schema
-                        match JSONSchema::options().compile(&json) {
-                            Ok(schema) => (json, schema),
-                            Err(e) => {
-                                return fail_clierror!("Cannot compile schema json. error: {e}");
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        return fail_clierror!("Unable to parse schema json. error: {e}");
-                    }
-                }
-            }
-            Err(e) => {
-                return fail_clierror!("Unable to retrieve json. error: {e}");
-            }
-        };
+    // parse and compile supplied JSON Schema, if any
+    let schema = match args.arg_json_schema {
+        Some(ref json_schema) => Some(compile_schema(json_schema, &args.arg_input)?),
+        None => None,
+    };
 
-    // debug!("compiled schema: {:?}", &schema_compiled);
+    let mut key_constraints = KeyConstraints::new(&args, &headers, schema.as_ref())?;
+    let missing_values = schema
+        .as_ref()
+        .map_or(&[][..], |schema| &schema.missing_values[..]);
 
     // how many rows read and processed as batches
     let mut row_number: u32 = 0;
@@ -267,6 +295,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut validation_results = Vec::with_capacity(BATCH_SIZE);
     let mut valid_flags: Vec<bool> = Vec::with_capacity(BATCH_SIZE);
     let mut validation_error_messages: Vec<String> = Vec::with_capacity(50);
+    let mut summary = args
+        .flag_summary
+        .as_ref()
+        .map(|_| ValidationSummary::new(args.flag_summary_examples));
 
     // set RAYON_NUM_THREADS
     util::njobs(args.flag_jobs);
@@ -288,6 +320,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         // non-allocating trimming in place is much faster on the record level
                         // with our csv fork than doing per field std::str::trim which is allocating
                         record.trim();
+                        // Table Schema missing values are validated like empty values
+                        if !missing_values.is_empty()
+                            && record
+                                .iter()
+                                .any(|field| missing_values.iter().any(|v| v == field))
+                        {
+                            record = record
+                                .iter()
+                                .enumerate()
+                                .map(|(i, field)| {
+                                    if i < headers_len && missing_values.iter().any(|v| v == field)
+                                    {
+                                        &b""[..]
+                                    } else {
+                                        field
+                                    }
+                                })
+                                .collect();
+                        }
                         batch.push(record.clone());
                     } else {
                         // nothing else to add to batch
@@ -310,25 +361,54 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         batch
             .par_iter()
             .map(|record| {
+                let schema = schema.as_ref()?;
                 do_json_validation(
                     &headers,
                     headers_len,
                     record,
-                    &schema_json,
-                    &schema_compiled,
+                    &schema.json,
+                    &schema.compiled,
                 )
             })
             .collect_into_vec(&mut validation_results);
 
+        // the key constraints are checked sequentially, as they depend on the previous rows
+        if !key_constraints.is_empty() {
+            for (record, result) in batch.iter().zip(validation_results.iter_mut()) {
+                if let Some(mut key_errors) = key_constraints.check(record, headers_len) {
+                    match result {
+                        Some(errors) => errors.append(&mut key_errors),
+                        None => *result = Some(key_errors),
+                    }
+                }
+            }
+        }
+
         // write to validation error report, but keep Vec<bool> to gen valid/invalid files later
         // because Rayon collect() guaranteeds original order, can sequentially append results to
         // vector with each batch
-        for result in &validation_results {
-            if let Some(validation_error_msg) = result {
+        for (record, result) in batch.iter().zip(validation_results.iter()) {
+            if let Some(errors) = result {
                 invalid_count += 1;
                 valid_flags.push(false);
 
-                validation_error_messages.push(validation_error_msg.to_string());
+                // row number was added as last column. We use unsafe from_utf8_unchecked to
+                // skip UTF8 validation since we know its safe as we added it earlier
+                let row_number_string =
+                    unsafe { str::from_utf8_unchecked(record.get(headers_len).unwrap()) };
+                // squash multiple errors into one long String with linebreaks.
+                // validation error file format: row_number, field, error
+                validation_error_messages.push(
+                    errors
+                        .iter()
+                        .map(|error| {
+                            format!("{row_number_string}\t{}\t{}", error.field, error.message)
+                        })
+                        .join("\n"),
+                );
+                if let Some(ref mut summary) = summary {
+                    summary.add(row_number_string, errors);
+                }
             } else {
                 valid_flags.push(true);
             }
@@ -355,12 +435,31 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         util::finish_progress(&progress);
     }
 
+    let summary_to_stdout = args.flag_summary.as_deref() == Some("-");
+    if let (Some(summary), Some(summary_path)) = (summary, &args.flag_summary) {
+        let summary_json = summary.to_json(&headers, row_number, invalid_count);
+        let summary_pretty = match serde_json::to_string_pretty(&summary_json) {
+            Ok(s) => s,
+            Err(e) => return fail_clierror!("Cannot prettify summary json: {e}"),
+        };
+        if summary_to_stdout {
+            println!("{summary_pretty}");
+        } else {
+            let mut summary_file = File::create(summary_path)?;
+            summary_file.write_all(summary_pretty.as_bytes())?;
+            summary_file.flush()?;
+        }
+    }
+
     // only write out invalid/valid/errors output files if there are actually invalid records.
     // if 100% invalid, valid file is not needed, but this is rare so OK with creating empty file.
     if invalid_count > 0 {
         let msg = "Writing invalid/valid/error files...";
         info!("{msg}");
-        println!("{msg}");
+        // stdout is reserved for the summary
+        if !summary_to_stdout {
+            println!("{msg}");
+        }
 
         let input_path = args
             .arg_input
@@ -402,6 +501,654 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+/// a validation error of a record's field, with the JSON Schema keyword or key constraint it
+/// violates. Key constraint errors have the comma-separated key columns as field.
+struct FieldError {
+    field:   String,
+    keyword: String,
+    value:   String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ErrorExample {
+    row:     u64,
+    value:   String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct KeywordErrors {
+    errors:   u64,
+    examples: Vec<ErrorExample>,
+}
+
+/// the error counts by column & keyword, with their first examples
+struct ValidationSummary {
+    max_examples: usize,
+    errors:       u64,
+    columns:      AHashMap<String, AHashMap<String, KeywordErrors>>,
+}
+
+impl ValidationSummary {
+    fn new(max_examples: usize) -> ValidationSummary {
+        ValidationSummary {
+            max_examples,
+            errors: 0,
+            columns: AHashMap::new(),
+        }
+    }
+
+    fn add(&mut self, row_number: &str, errors: &[FieldError]) {
+        let row = row_number.parse::<u64>().unwrap_or_default();
+        let max_examples = self.max_examples;
+        for error in errors {
+            self.errors += 1;
+            let keyword_errors = self
+                .columns
+                .entry(error.field.clone())
+                .or_default()
+                .entry(error.keyword.clone())
+                .or_insert_with(|| KeywordErrors {
+                    errors:   0,
+                    examples: Vec::with_capacity(max_examples),
+                });
+            keyword_errors.errors += 1;
+            if keyword_errors.examples.len() < max_examples {
+                keyword_errors.examples.push(ErrorExample {
+                    row,
+                    value: error.value.clone(),
+                    message: error.message.clone(),
+                });
+            }
+        }
+    }
+
+    /// the summary, with the columns in the order of the headers & the keywords with the
+    /// most errors first
+    fn to_json(self, headers: &ByteRecord, records: u32, invalid: u32) -> Value {
+        let mut keywords: AHashMap<String, u64> = AHashMap::new();
+        let mut columns: Vec<(String, AHashMap<String, KeywordErrors>)> =
+            self.columns.into_iter().collect();
+        columns.sort_by_cached_key(|(column, _)| {
+            (
+                headers
+                    .iter()
+                    .position(|header| header == column.as_bytes())
+                    .unwrap_or(usize::MAX),
+                column.clone(),
+            )
+        });
+
+        let mut columns_map = Map::with_capacity(columns.len());
+        for (column, column_keywords) in columns {
+            let mut column_keywords: Vec<(String, KeywordErrors)> =
+                column_keywords.into_iter().collect();
+            column_keywords.sort_by(|(keyword_a, errors_a), (keyword_b, errors_b)| {
+                errors_b
+                    .errors
+                    .cmp(&errors_a.errors)
+                    .then_with(|| keyword_a.cmp(keyword_b))
+            });
+
+            let mut column_errors = 0;
+            let mut keywords_map = Map::with_capacity(column_keywords.len());
+            for (keyword, keyword_errors) in column_keywords {
+                column_errors += keyword_errors.errors;
+                *keywords.entry(keyword.clone()).or_default() += keyword_errors.errors;
+                keywords_map.insert(keyword, json!(keyword_errors));
+            }
+            columns_map.insert(
+                column,
+                json!({
+                    "errors": column_errors,
+                    "keywords": keywords_map,
+                }),
+            );
+        }
+
+        let mut keywords: Vec<(String, u64)> = keywords.into_iter().collect();
+        keywords.sort_by(|(keyword_a, errors_a), (keyword_b, errors_b)| {
+            errors_b
+                .cmp(errors_a)
+                .then_with(|| keyword_a.cmp(keyword_b))
+        });
+        let keywords_map: Map<String, Value> = keywords
+            .into_iter()
+            .map(|(keyword, errors)| (keyword, json!(errors)))
+            .collect();
+
+        json!({
+            "records": records,
+            "valid": records - invalid,
+            "invalid": invalid,
+            "errors": self.errors,
+            "keywords": keywords_map,
+            "columns": columns_map,
+        })
+    }
+}
+
+/// the compiled JSON Schema. When converted from a Frictionless Table Schema, also has the
+/// Table Schema's missing values & keys
+struct Schema {
+    json:           Value,
+    compiled:       JSONSchema,
+    /// the non-empty values that are treated as nulls
+    missing_values: Vec<Vec<u8>>,
+    primary_key:    Vec<String>,
+    unique_fields:  Vec<String>,
+}
+
+/// load, parse and compile the JSON Schema at the given path or URL.
+/// A Frictionless Table Schema, or a Data Package with the Table Schema of the input,
+/// is converted to JSON Schema first.
+fn compile_schema(json_schema: &str, input: &Option<String>) -> CliResult<Schema> {
+    let s = match load_json(json_schema) {
+        Ok(s) => s,
+        Err(e) => return fail_clierror!("Unable to retrieve json. error: {e}"),
+    };
+    let mut json: Value = match serde_json::from_str(&s) {
+        Ok(json) => json,
+        Err(e) => return fail_clierror!("Unable to parse schema json. error: {e}"),
+    };
+
+    let mut missing_values = Vec::new();
+    let mut primary_key = Vec::new();
+    let mut unique_fields = Vec::new();
+    if json.get("resources").is_some() {
+        json = data_package_table_schema(&json, input)?;
+    }
+    if json.get("fields").is_some() && json.get("properties").is_none() {
+        info!("converting Table Schema to JSON Schema");
+        missing_values = match json.get("missingValues") {
+            Some(Value::Array(values)) => values
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.as_bytes().to_vec())
+                .collect(),
+            _ => Vec::new(),
+        };
+        primary_key = match json.get("primaryKey") {
+            Some(Value::String(field)) => vec![field.clone()],
+            Some(Value::Array(fields)) => fields
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect(),
+            _ => Vec::new(),
+        };
+        json = table_schema_to_json_schema(&json)?;
+    }
+    // the "unique" keyword isn't part of JSON Schema, but `qsv schema --unique` adds it
+    if let Some(Value::Object(properties)) = json.get("properties") {
+        unique_fields = properties
+            .iter()
+            .filter(|(_, property)| property["unique"] == Value::Bool(true))
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    match JSONSchema::options().compile(&json) {
+        Ok(compiled) => Ok(Schema {
+            json,
+            compiled,
+            missing_values,
+            primary_key,
+            unique_fields,
+        }),
+        Err(e) => fail_clierror!("Cannot compile schema json. error: {e}"),
+    }
+}
+
+/// the Table Schema of the Data Package resource with the input's file name,
+/// or of its first resource
+fn data_package_table_schema(data_package: &Value, input: &Option<String>) -> CliResult<Value> {
+    let resources = data_package["resources"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let input_filename = input.as_ref().and_then(|input| {
+        Path::new(input)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    });
+    let resource = resources
+        .iter()
+        .find(|resource| {
+            resource["path"]
+                .as_str()
+                .and_then(|path| Path::new(path).file_name())
+                == input_filename.as_deref().map(std::ffi::OsStr::new)
+        })
+        .or_else(|| resources.first());
+
+    match resource.map(|resource| &resource["schema"]) {
+        Some(Value::Object(table_schema)) => Ok(Value::Object(table_schema.clone())),
+        Some(Value::String(table_schema_path)) => match load_json(table_schema_path) {
+            Ok(s) => match serde_json::from_str(&s) {
+                Ok(table_schema) => Ok(table_schema),
+                Err(e) => fail_clierror!(
+                    "Unable to parse the Table Schema {table_schema_path}. error: {e}"
+                ),
+            },
+            Err(e) => fail_clierror!(
+                "Unable to retrieve the Table Schema {table_schema_path}. error: {e}"
+            ),
+        },
+        _ => fail_clierror!("The Data Package has no resource with a Table Schema."),
+    }
+}
+
+/// converts a Frictionless Table Schema into JSON Schema, mapping the field types, formats &
+/// constraints to their JSON Schema equivalents. The unique constraints and the primary key
+/// are checked like the --primary-key option instead.
+fn table_schema_to_json_schema(table_schema: &Value) -> CliResult<Value> {
+    let Some(fields) = table_schema["fields"].as_array() else {
+        return fail_clierror!("Table Schema 'fields' should be an array.");
+    };
+
+    let mut properties = Map::with_capacity(fields.len());
+    for field in fields {
+        let Some(name) = field["name"].as_str() else {
+            return fail_clierror!("Table Schema field without a name: {field}");
+        };
+        let field_type = field["type"].as_str().unwrap_or("string");
+        let format = field["format"].as_str().unwrap_or("default");
+        let constraints = &field["constraints"];
+        let required = constraints["required"] == Value::Bool(true);
+
+        let json_type = match field_type {
+            "integer" | "year" => "integer",
+            "number" => "number",
+            _ => "string",
+        };
+
+        let mut property = Map::new();
+        if let Some(description) = field.get("description") {
+            property.insert("description".to_string(), description.clone());
+        }
+        property.insert(
+            "type".to_string(),
+            if required {
+                json!(json_type)
+            } else {
+                json!([json_type, "null"])
+            },
+        );
+
+        match (field_type, format) {
+            ("string", "email" | "uri" | "uuid") => {
+                property.insert("format".to_string(), json!(format));
+            }
+            ("date", "default") => {
+                property.insert("format".to_string(), json!("date"));
+            }
+            ("datetime", "default") => {
+                property.insert("format".to_string(), json!("date-time"));
+            }
+            ("time", "default") => {
+                property.insert(
+                    "pattern".to_string(),
+                    json!(r"^([01]\d|2[0-3]):[0-5]\d:[0-5]\d$"),
+                );
+            }
+            ("yearmonth", _) => {
+                property.insert("pattern".to_string(), json!(r"^\d{4}-(0[1-9]|1[0-2])$"));
+            }
+            ("boolean", _) => {
+                let true_values = field
+                    .get("trueValues")
+                    .cloned()
+                    .unwrap_or_else(|| json!(["true", "True", "TRUE", "1"]));
+                let false_values = field
+                    .get("falseValues")
+                    .cloned()
+                    .unwrap_or_else(|| json!(["false", "False", "FALSE", "0"]));
+                let mut values: Vec<Value> = true_values
+                    .as_array()
+                    .into_iter()
+                    .chain(false_values.as_array())
+                    .flatten()
+                    .cloned()
+                    .collect();
+                if !required {
+                    values.push(Value::Null);
+                }
+                property.insert("enum".to_string(), Value::Array(values));
+            }
+            _ => {}
+        }
+
+        for keyword in ["minLength", "maxLength"] {
+            if let Some(length) = constraints.get(keyword) {
+                property.insert(keyword.to_string(), length.clone());
+            }
+        }
+        for keyword in ["minimum", "maximum"] {
+            let bound = match constraints.get(keyword) {
+                Some(Value::Number(bound)) => Value::Number(bound.clone()),
+                Some(Value::String(bound)) if json_type != "string" => {
+                    match bound.parse::<f64>().ok().and_then(Number::from_f64) {
+                        Some(bound) => Value::Number(bound),
+                        None => {
+                            return fail_clierror!(
+                                "Table Schema field {name} has an invalid {keyword}: {bound}"
+                            )
+                        }
+                    }
+                }
+                Some(bound) => {
+                    warn!("{keyword} constraint {bound} of {field_type} field {name} is ignored");
+                    continue;
+                }
+                None => continue,
+            };
+            property.insert(keyword.to_string(), bound);
+        }
+        if constraints["unique"] == Value::Bool(true) {
+            property.insert("unique".to_string(), Value::Bool(true));
+        }
+        if let Some(pattern) = constraints["pattern"].as_str() {
+            // Table Schema patterns match the whole value
+            property.insert("pattern".to_string(), json!(format!("^(?:{pattern})$")));
+        }
+        if let Some(values) = constraints["enum"].as_array() {
+            let mut values: Vec<Value> = values
+                .iter()
+                .map(|value| match value.as_str() {
+                    Some(s) if json_type == "integer" => s
+                        .parse::<i64>()
+                        .map_or_else(|_| value.clone(), |int| json!(int)),
+                    Some(s) if json_type == "number" => s
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map_or_else(|| value.clone(), Value::Number),
+                    _ => value.clone(),
+                })
+                .collect();
+            if !required {
+                values.push(Value::Null);
+            }
+            property.insert("enum".to_string(), Value::Array(values));
+        }
+
+        properties.insert(name.to_string(), Value::Object(property));
+    }
+
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft-07/schema",
+        "type": "object",
+        "properties": Value::Object(properties),
+    }))
+}
+
+/// the columns whose values must be unique, with the row number where each value was first seen.
+/// The values of a primary key must also be non-empty.
+struct UniqueKey {
+    cols:    Vec<usize>,
+    names:   String,
+    primary: bool,
+    seen:    AHashMap<Vec<Vec<u8>>, String>,
+}
+
+/// the foreign key columns, with all the key values of the reference CSV
+struct ForeignKey {
+    cols:      Vec<usize>,
+    names:     String,
+    reference: String,
+    values:    AHashSet<Vec<Vec<u8>>>,
+}
+
+struct KeyConstraints {
+    primary_key:  Option<UniqueKey>,
+    unique:       Vec<UniqueKey>,
+    foreign_keys: Vec<ForeignKey>,
+}
+
+impl KeyConstraints {
+    /// the --primary-key & --foreign-key constraints. The primary key and unique fields of a
+    /// Table Schema are also used, with --primary-key taking precedence.
+    fn new(
+        args: &Args,
+        headers: &ByteRecord,
+        schema: Option<&Schema>,
+    ) -> CliResult<KeyConstraints> {
+        let table_schema_primary_key = schema.map_or(&[][..], |schema| &schema.primary_key[..]);
+        let primary_key_cols = match args.flag_primary_key {
+            Some(ref primary_key) => primary_key
+                .selection(headers, !args.flag_no_headers)?
+                .to_vec(),
+            None if !table_schema_primary_key.is_empty() => {
+                column_indices(headers, table_schema_primary_key)?
+            }
+            None => Vec::new(),
+        };
+        let primary_key = if primary_key_cols.is_empty() {
+            None
+        } else {
+            Some(UniqueKey::new(
+                primary_key_cols,
+                headers,
+                args.flag_no_headers,
+                true,
+            ))
+        };
+
+        let unique = match schema {
+            Some(schema) => column_indices(headers, &schema.unique_fields)?
+                .into_iter()
+                .map(|col| UniqueKey::new(vec![col], headers, args.flag_no_headers, false))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let foreign_keys = args
+            .flag_foreign_key
+            .iter()
+            .map(|spec| ForeignKey::new(spec, headers, args.flag_no_headers))
+            .collect::<CliResult<Vec<_>>>()?;
+
+        Ok(KeyConstraints {
+            primary_key,
+            unique,
+            foreign_keys,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.primary_key.is_none() && self.unique.is_empty() && self.foreign_keys.is_empty()
+    }
+
+    /// returns the errors of the key constraints the record violates.
+    /// The row number is the last field of the record.
+    fn check(&mut self, record: &ByteRecord, headers_len: usize) -> Option<Vec<FieldError>> {
+        let row_number = String::from_utf8_lossy(record.get(headers_len).unwrap_or_default());
+        let mut errors = Vec::new();
+
+        for unique_key in self.primary_key.iter_mut().chain(self.unique.iter_mut()) {
+            if let Some(error) = unique_key.check(record, &row_number) {
+                errors.push(error);
+            }
+        }
+
+        for foreign_key in &self.foreign_keys {
+            let key = key_values(record, &foreign_key.cols);
+            // like in SQL, a foreign key with only empty values is not checked
+            if key.iter().all(Vec::is_empty) || foreign_key.values.contains(&key) {
+                continue;
+            }
+            errors.push(FieldError {
+                field:   foreign_key.names.clone(),
+                keyword: "foreignKey".to_string(),
+                value:   display_key(&key),
+                message: format!(
+                    "foreign key value \"{}\" not found in {}",
+                    display_key(&key),
+                    foreign_key.reference
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors)
+        }
+    }
+}
+
+impl UniqueKey {
+    fn new(cols: Vec<usize>, headers: &ByteRecord, no_headers: bool, primary: bool) -> UniqueKey {
+        UniqueKey {
+            names: column_names(headers, &cols, no_headers),
+            cols,
+            primary,
+            seen: AHashMap::new(),
+        }
+    }
+
+    fn check(&mut self, record: &ByteRecord, row_number: &str) -> Option<FieldError> {
+        let key = key_values(record, &self.cols);
+        let keyword = if self.primary { "primaryKey" } else { "unique" };
+        if let Some(empty) = key.iter().position(Vec::is_empty) {
+            if self.primary {
+                return Some(FieldError {
+                    field:   self.names.clone(),
+                    keyword: keyword.to_string(),
+                    value:   display_key(&key),
+                    message: format!(
+                        "primary key column {} is empty",
+                        self.names.split(',').nth(empty).unwrap_or_default()
+                    ),
+                });
+            }
+            // like in SQL, empty values of unique columns aren't checked
+            return None;
+        }
+
+        if let Some(first_row) = self.seen.get(&key) {
+            return Some(FieldError {
+                field:   self.names.clone(),
+                keyword: keyword.to_string(),
+                value:   display_key(&key),
+                message: format!(
+                    "duplicate {} value \"{}\", first seen in row {first_row}",
+                    if self.primary {
+                        "primary key"
+                    } else {
+                        "unique"
+                    },
+                    display_key(&key)
+                ),
+            });
+        }
+        self.seen.insert(key, row_number.to_string());
+        None
+    }
+}
+
+impl ForeignKey {
+    /// parses a "<columns>:<reference csv>:<reference columns>" spec & loads the reference keys
+    fn new(spec: &str, headers: &ByteRecord, no_headers: bool) -> CliResult<ForeignKey> {
+        let (cols, reference_path, reference_cols) = match spec
+            .split_once(':')
+            .and_then(|(cols, rest)| rest.rsplit_once(':').map(|(path, refs)| (cols, path, refs)))
+        {
+            Some((cols, path, refs))
+                if !cols.is_empty() && !path.is_empty() && !refs.is_empty() =>
+            {
+                (cols, path, refs)
+            }
+            _ => {
+                return fail_clierror!(
+                    "Invalid foreign key \"{spec}\". Expected \"<columns>:<reference \
+                     csv>:<reference columns>\"."
+                )
+            }
+        };
+
+        let sel = SelectColumns::parse(cols)?.selection(headers, !no_headers)?;
+
+        let ref_config = Config::new(&Some(reference_path.to_string()))
+            .select(SelectColumns::parse(reference_cols)?);
+        let mut ref_rdr = match ref_config.reader() {
+            Ok(rdr) => rdr,
+            Err(e) => {
+                return fail_clierror!(
+                    "Cannot read the foreign key reference {reference_path}: {e}"
+                )
+            }
+        };
+        let ref_headers = ref_rdr.byte_headers()?.clone();
+        let ref_sel = ref_config.selection(&ref_headers)?;
+        if ref_sel.len() != sel.len() {
+            return fail_clierror!(
+                "The foreign key \"{spec}\" has {} columns, but {} reference columns.",
+                sel.len(),
+                ref_sel.len()
+            );
+        }
+
+        let mut values = AHashSet::new();
+        let mut ref_record = ByteRecord::new();
+        while ref_rdr.read_byte_record(&mut ref_record)? {
+            ref_record.trim();
+            values.insert(key_values(&ref_record, &ref_sel));
+        }
+
+        Ok(ForeignKey {
+            cols: sel.to_vec(),
+            names: column_names(headers, &sel, no_headers),
+            reference: format!(
+                "{} of {reference_path}",
+                column_names(&ref_headers, &ref_sel, false)
+            ),
+            values,
+        })
+    }
+}
+
+#[inline]
+fn key_values(record: &ByteRecord, cols: &[usize]) -> Vec<Vec<u8>> {
+    cols.iter()
+        .map(|&col| record.get(col).unwrap_or_default().to_vec())
+        .collect()
+}
+
+fn display_key(key: &[Vec<u8>]) -> String {
+    key.iter()
+        .map(|value| String::from_utf8_lossy(value))
+        .join(",")
+}
+
+/// the indices of the named columns
+fn column_indices(headers: &ByteRecord, names: &[String]) -> CliResult<Vec<usize>> {
+    names
+        .iter()
+        .map(
+            |name| match headers.iter().position(|header| header == name.as_bytes()) {
+                Some(col) => Ok(col),
+                None => fail_clierror!("Table Schema field {name} is not a column of the input."),
+            },
+        )
+        .collect()
+}
+
+fn column_names(headers: &ByteRecord, cols: &[usize], no_headers: bool) -> String {
+    cols.iter()
+        .map(|&col| {
+            if no_headers {
+                (col + 1).to_string()
+            } else {
+                String::from_utf8_lossy(&headers[col]).to_string()
+            }
+        })
+        .join(",")
+}
+
 fn split_invalid_records(
     rconfig: &Config,
     valid_flags: &[bool],
@@ -474,39 +1221,44 @@ fn write_error_report(input_path: &str, validation_error_messages: Vec<String>)
     Ok(())
 }
 
-/// if given record is valid, return None, otherwise, error file entry string
+/// if given record is valid, return None, otherwise, its field errors
 fn do_json_validation(
     headers: &ByteRecord,
     headers_len: usize,
     record: &ByteRecord,
     schema_json: &Value,
     schema_compiled: &JSONSchema,
-) -> Option<String> {
-    // row number was added as last column. We use unsafe from_utf8_unchecked to
-    // skip UTF8 validation since we know its safe as we added it earlier
-    let row_number_string = unsafe { str::from_utf8_unchecked(record.get(headers_len).unwrap()) };
-
+) -> Option<Vec<FieldError>> {
     // debug!("instance[{row_number}]: {instance:?}");
-    validate_json_instance(
-        &(match to_json_instance(headers, headers_len, record, schema_json) {
-            Ok(obj) => obj,
-            Err(e) => {
-                return Some(format!("{row_number_string}\t<RECORD>\t{e}"));
-            }
-        }),
-        schema_compiled,
-    )
-    .map(|validation_errors| {
-        // squash multiple errors into one long String with linebreaks
-        let combined_errors: String = validation_errors
-            .iter()
-            .map(|tuple| {
-                // validation error file format: row_number, field, error
-                format!("{row_number_string}\t{}\t{}", tuple.0, tuple.1)
-            })
-            .join("\n");
+    let instance = match to_json_instance(headers, headers_len, record, schema_json) {
+        Ok(obj) => obj,
+        Err(e) => {
+            return Some(vec![FieldError {
+                field:   "<RECORD>".to_string(),
+                keyword: "type".to_string(),
+                value:   String::new(),
+                message: e,
+            }]);
+        }
+    };
 
-        combined_errors
+    validate_json_instance(&instance, schema_compiled).map(|validation_errors| {
+        validation_errors
+            .into_iter()
+            .map(|(field, keyword, message)| {
+                let value = headers
+                    .iter()
+                    .position(|header| header == field.as_bytes())
+                    .map(|i| String::from_utf8_lossy(&record[i]).to_string())
+                    .unwrap_or_default();
+                FieldError {
+                    field,
+                    keyword,
+                    value,
+                    message,
+                }
+            })
+            .collect()
     })
 }
 
@@ -742,12 +1494,13 @@ mod tests_for_csv_to_json_conversion {
 }
 
 /// Validate JSON instance against compiled JSON schema
-/// If invalid, returns Some(Vec<(String,String)>) holding the error messages
+/// If invalid, returns Some(Vec<(String,String,String)>) holding the fields, the keywords
+/// they violate and the error messages
 #[inline]
 fn validate_json_instance(
     instance: &Value,
     schema_compiled: &JSONSchema,
-) -> Option<Vec<(String, String)>> {
+) -> Option<Vec<(String, String, String)>> {
     let validation_output = schema_compiled.apply(instance);
 
     // If validation output is Invalid, then grab field names and errors
@@ -755,15 +1508,29 @@ fn validate_json_instance(
         None
     } else {
         // get validation errors as String
-        let validation_errors: Vec<(String, String)> = match validation_output.basic() {
+        let validation_errors: Vec<(String, String, String)> = match validation_output.basic() {
             BasicOutput::Invalid(errors) => errors
                 .iter()
                 .map(|e| {
+                    // the keyword is the last segment of the keyword location,
+                    // e.g. "minimum" for "/properties/age/minimum"
+                    let keyword = e
+                        .keyword_location()
+                        .to_string()
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string();
                     if let Some(PathChunk::Property(box_str)) = e.instance_location().last() {
-                        (box_str.to_string(), e.error_description().to_string())
+                        (
+                            box_str.to_string(),
+                            keyword,
+                            e.error_description().to_string(),
+                        )
                     } else {
                         (
                             e.instance_location().to_string(),
+                            keyword,
                             e.error_description().to_string(),
                         )
                     }
@@ -854,6 +1621,7 @@ mod tests_for_schema_validation {
         assert_eq!(
             vec![(
                 "name".to_string(),
+                "minLength".to_string(),
                 "\"X\" is shorter than 2 characters".to_string()
             )],
             result.unwrap()
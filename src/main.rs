@@ -85,9 +85,13 @@ fn main() -> QsvExitCode {
 
     enabled_commands.push_str(
         "    behead      Drop header from CSV file
+    bin         Bin numeric or date columns & show histograms
     cat         Concatenate by row or column
+    check       Check data quality rules declared in a YAML file
+    correlation Compute correlation & covariance of numeric columns
     count       Count records
     dedup       Remove redundant rows
+    drift       Compare the column distributions of two CSVs
     enum        Add a new column enumerating CSV lines
     excel       Exports an Excel sheet to a CSV
     exclude     Excludes the records in one CSV from another
@@ -118,7 +122,9 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str("    generate    Generate test data by profiling a CSV\n");
 
     enabled_commands.push_str(
-        "    headers     Show header names
+        "    geo         Distances, bounding box & radius filters, geohashes of locations
+    geojoin     Append the GeoJSON polygon properties of row locations
+    headers     Show header names
     help        Show this usage message
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
@@ -130,7 +136,10 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str("    luau        Execute Luau script on CSV data\n");
 
     enabled_commands.push_str(
-        "    partition   Partition CSV data based on a column value
+        "    outliers    Flag or filter rows with outliers
+    partition   Partition CSV data based on a column value
+    piiscan     Scan CSV columns for likely personal data
+    profile     Profile CSV data into an HTML or Markdown report
     pseudo      Pseudonymise the values of a column\n",
     );
 
@@ -144,6 +153,7 @@ fn main() -> QsvExitCode {
     safenames   Modify a CSV's header names to db-safe names
     sample      Randomly sample CSV data
     schema      Generate JSON Schema from CSV data
+    schemadiff  Classify the changes between two JSON Schemas as breaking or not
     search      Search CSV data with a regex
     searchset   Search CSV data with a regex set
     select      Select, re-order, duplicate or drop columns
@@ -249,9 +259,13 @@ enum Command {
     #[cfg(all(feature = "apply", not(feature = "lite")))]
     Apply,
     Behead,
+    Bin,
     Cat,
+    Check,
+    Correlation,
     Count,
     Dedup,
+    Drift,
     Enum,
     Excel,
     Exclude,
@@ -270,6 +284,8 @@ enum Command {
     Frequency,
     #[cfg(all(feature = "generate", not(feature = "lite")))]
     Generate,
+    Geo,
+    Geojoin,
     Headers,
     Help,
     Index,
@@ -278,7 +294,10 @@ enum Command {
     Jsonl,
     #[cfg(all(feature = "luau", not(feature = "lite")))]
     Luau,
+    Outliers,
     Partition,
+    Piiscan,
+    Profile,
     Pseudo,
     #[cfg(all(feature = "python", not(feature = "lite")))]
     Py,
@@ -288,6 +307,7 @@ enum Command {
     Safenames,
     Sample,
     Schema,
+    Schemadiff,
     Search,
     SearchSet,
     Select,
@@ -317,11 +337,15 @@ impl Command {
         }
         match self {
             Command::Behead => cmd::behead::run(argv),
+            Command::Bin => cmd::bin::run(argv),
             #[cfg(all(feature = "apply", not(feature = "lite")))]
             Command::Apply => cmd::apply::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Check => cmd::check::run(argv),
+            Command::Correlation => cmd::correlation::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
+            Command::Drift => cmd::drift::run(argv),
             Command::Enum => cmd::enumerate::run(argv),
             Command::Excel => cmd::excel::run(argv),
             Command::Exclude => cmd::exclude::run(argv),
@@ -340,6 +364,8 @@ impl Command {
             Command::Frequency => cmd::frequency::run(argv),
             #[cfg(all(feature = "generate", not(feature = "lite")))]
             Command::Generate => cmd::generate::run(argv),
+            Command::Geo => cmd::geo::run(argv),
+            Command::Geojoin => cmd::geojoin::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
@@ -352,7 +378,10 @@ impl Command {
             Command::Jsonl => cmd::jsonl::run(argv),
             #[cfg(all(feature = "luau", not(feature = "lite")))]
             Command::Luau => cmd::luau::run(argv),
+            Command::Outliers => cmd::outliers::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Piiscan => cmd::piiscan::run(argv),
+            Command::Profile => cmd::profile::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
             #[cfg(all(feature = "python", not(feature = "lite")))]
             Command::Py => cmd::python::run(argv),
@@ -362,6 +391,7 @@ impl Command {
             Command::Safenames => cmd::safenames::run(argv),
             Command::Sample => cmd::sample::run(argv),
             Command::Schema => cmd::schema::run(argv),
+            Command::Schemadiff => cmd::schemadiff::run(argv),
             Command::Search => cmd::search::run(argv),
             Command::SearchSet => cmd::searchset::run(argv),
             Command::Select => cmd::select::run(argv),
@@ -14,9 +14,13 @@ macro_rules! command_list {
     () => {
         "
     behead      Drop header from CSV file
+    bin         Bin numeric or date columns & show histograms
     cat         Concatenate by row or column
+    check       Check data quality rules declared in a YAML file
+    correlation Compute correlation & covariance of numeric columns
     count       Count records
     dedup       Remove redundant rows
+    drift       Compare the column distributions of two CSVs
     enum        Add a new column enumerating CSV lines
     excel       Exports an Excel sheet to a CSV
     exclude     Excludes the records in one CSV from another
@@ -27,13 +31,18 @@ macro_rules! command_list {
     flatten     Show one field per line
     fmt         Format CSV output (change field delimiter)
     frequency   Show frequency tables
+    geo         Distances, bounding box & radius filters, geohashes of locations
+    geojoin     Append the GeoJSON polygon properties of row locations
     headers     Show header names
     help        Show this usage message
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
     join        Join CSV files
     jsonl       Convert newline-delimited JSON files to CSV
+    outliers    Flag or filter rows with outliers
     partition   Partition CSV data based on a column value
+    piiscan     Scan CSV columns for likely personal data
+    profile     Profile CSV data into an HTML or Markdown report
     pseudo      Pseudonymise the values of a column
     rename      Rename the columns of CSV data efficiently
     replace     Replace patterns in CSV data
@@ -41,6 +50,7 @@ macro_rules! command_list {
     safenames   Modify a CSV's header names to db-safe names
     sample      Randomly sample CSV data
     schema      Generate JSON Schema from CSV data
+    schemadiff  Classify the changes between two JSON Schemas as breaking or not
     search      Search CSV data with a regex
     searchset   Search CSV data with a regex set
     select      Select, re-order, duplicate or drop columns
@@ -175,9 +185,13 @@ Please choose one of the following commands:",
 #[serde(rename_all = "lowercase")]
 enum Command {
     Behead,
+    Bin,
     Cat,
+    Check,
+    Correlation,
     Count,
     Dedup,
+    Drift,
     Enum,
     Excel,
     Exclude,
@@ -188,13 +202,18 @@ enum Command {
     Flatten,
     Fmt,
     Frequency,
+    Geo,
+    Geojoin,
     Headers,
     Help,
     Index,
     Input,
     Join,
     Jsonl,
+    Outliers,
     Partition,
+    Piiscan,
+    Profile,
     Pseudo,
     Rename,
     Replace,
@@ -202,6 +221,7 @@ enum Command {
     Safenames,
     Sample,
     Schema,
+    Schemadiff,
     Search,
     SearchSet,
     Select,
@@ -231,9 +251,13 @@ impl Command {
         }
         match self {
             Command::Behead => cmd::behead::run(argv),
+            Command::Bin => cmd::bin::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Check => cmd::check::run(argv),
+            Command::Correlation => cmd::correlation::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
+            Command::Drift => cmd::drift::run(argv),
             Command::Enum => cmd::enumerate::run(argv),
             Command::Excel => cmd::excel::run(argv),
             Command::Exclude => cmd::exclude::run(argv),
@@ -244,6 +268,8 @@ impl Command {
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::Geo => cmd::geo::run(argv),
+            Command::Geojoin => cmd::geojoin::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
@@ -254,7 +280,10 @@ impl Command {
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
             Command::Jsonl => cmd::jsonl::run(argv),
+            Command::Outliers => cmd::outliers::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Piiscan => cmd::piiscan::run(argv),
+            Command::Profile => cmd::profile::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
             Command::Rename => cmd::rename::run(argv),
             Command::Replace => cmd::replace::run(argv),
@@ -262,6 +291,7 @@ impl Command {
             Command::Safenames => cmd::safenames::run(argv),
             Command::Sample => cmd::sample::run(argv),
             Command::Schema => cmd::schema::run(argv),
+            Command::Schemadiff => cmd::schemadiff::run(argv),
             Command::Search => cmd::search::run(argv),
             Command::SearchSet => cmd::searchset::run(argv),
             Command::Select => cmd::select::run(argv),
@@ -759,6 +759,66 @@ pub fn is_safe_name(header_name: &str) -> bool {
     safename_re.is_match(header_name)
 }
 
+/// read the secret key of keyed hashes from its --hmac-key source - either 'env:<VAR>' or
+/// 'file:<path>' - or from the QSV_HMAC_KEY env var when no source is given
+#[cfg(feature = "apply")]
+pub fn read_hmac_key(source: &Option<String>) -> CliResult<Vec<u8>> {
+    let key = match source {
+        Some(source) => {
+            if let Some(var) = source.strip_prefix("env:") {
+                match env::var(var) {
+                    Ok(key) => key.into_bytes(),
+                    Err(_) => {
+                        return fail_clierror!(
+                            "The {var} environment variable of --hmac-key is not set."
+                        )
+                    }
+                }
+            } else if let Some(path) = source.strip_prefix("file:") {
+                match fs::read(path) {
+                    Ok(mut key) => {
+                        while key.last().map_or(false, |b| *b == b'\n' || *b == b'\r') {
+                            key.pop();
+                        }
+                        key
+                    }
+                    Err(e) => return fail_clierror!("Cannot read the --hmac-key file {path}: {e}"),
+                }
+            } else {
+                return fail_clierror!(
+                    "--hmac-key must be either 'env:<VAR>' or 'file:<path>': {source}"
+                );
+            }
+        }
+        None => match env::var("QSV_HMAC_KEY") {
+            Ok(key) => key.into_bytes(),
+            Err(_) => {
+                return fail!(
+                    "The hmac operation requires a secret key, set with --hmac-key or the \
+                     QSV_HMAC_KEY environment variable."
+                )
+            }
+        },
+    };
+    if key.is_empty() {
+        return fail!("The hmac secret key cannot be empty.");
+    }
+    Ok(key)
+}
+
+/// the keyed HMAC-SHA256 hash of the value, as lowercase hex
+#[cfg(feature = "apply")]
+pub fn hmac_sha256_hex(key: &[u8], value: &str) -> String {
+    use data_encoding::HEXLOWER;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    // HMAC accepts keys of any length, so this never fails
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(value.as_bytes());
+    HEXLOWER.encode(&mac.finalize().into_bytes())
+}
+
 pub fn log_end(mut qsv_args: String, now: std::time::Instant) {
     if log::log_enabled!(log::Level::Info) {
         let ellipsis = if qsv_args.len() > 24 {
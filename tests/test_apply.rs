@@ -2170,6 +2170,475 @@ fn apply_datefmt_fmtstring_notime() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn apply_dateops_tz_trunc() {
+    let wrk = Workdir::new("apply_dateops_tz_trunc");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["created_at"],
+            svec!["2021-05-01T01:17:02Z"],
+            svec!["2022-01-15T12:00:00Z"],
+            svec!["not a date"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("to_tz=America/New_York,trunc=month")
+        .arg("created_at")
+        .args(["--new-column", "month"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["created_at", "month"],
+        svec!["2021-05-01T01:17:02Z", "2021-04-01T00:00:00-04:00"],
+        svec!["2022-01-15T12:00:00Z", "2022-01-01T00:00:00-05:00"],
+        svec!["not a date", "not a date"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_dateops_dst_gap() {
+    let wrk = Workdir::new("apply_dateops_dst_gap");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["local", "utc"],
+            svec!["2023-03-26 02:30:00", "2023-03-25T01:30:00Z"],
+        ],
+    );
+    // 02:30 doesn't exist in Paris on 2023-03-26, as the clocks go from 02:00 to 03:00
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("from_tz=Europe/Paris")
+        .arg("local")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["local", "utc"],
+        svec!["2023-03-26T03:30:00+02:00", "2023-03-25T01:30:00Z"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("to_tz=Europe/Paris,add=1d")
+        .arg("utc")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["local", "utc"],
+        svec!["2023-03-26 02:30:00", "2023-03-26T03:30:00+02:00"],
+    ];
+    assert_eq!(got, expected);
+
+    // midnight doesn't exist in Sao Paulo on 2018-11-04, so the day starts at 01:00
+    wrk.create(
+        "days.csv",
+        vec![svec!["date"], svec!["2018-11-04T14:00:00Z"]],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("to_tz=America/Sao_Paulo,trunc=day")
+        .arg("date")
+        .arg("days.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["date"], svec!["2018-11-04T01:00:00-02:00"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_dateops_add_parts() {
+    let wrk = Workdir::new("apply_dateops_add_parts");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["date"],
+            svec!["2022-01-31T10:00:00Z"],
+            svec!["July 4, 2005"],
+            svec!["2021-01-03T08:00:00Z"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("add=1mo2d")
+        .arg("date")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date"],
+        svec!["2022-03-02T10:00:00+00:00"],
+        svec!["2005-08-06"],
+        svec!["2021-02-05T08:00:00+00:00"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("sub=8h,isoweek")
+        .arg("date")
+        .args(["--new-column", "week"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date", "week"],
+        svec!["2022-01-31T10:00:00Z", "5"],
+        svec!["July 4, 2005", "26"],
+        svec!["2021-01-03T08:00:00Z", "53"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_dateops_diff() {
+    let wrk = Workdir::new("apply_dateops_diff");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["OpenDate", "CloseDate"],
+            svec!["2022-01-31T00:00:00Z", "2022-03-01T00:00:00Z"],
+            svec!["2022-03-01T00:00:00Z", "2022-01-31T00:00:00Z"],
+            svec!["", "2022-03-01T00:00:00Z"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("diff=days")
+        .arg("CloseDate")
+        .args(["--comparand", "OpenDate"])
+        .args(["--new-column", "days_open"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["OpenDate", "CloseDate", "days_open"],
+        svec!["2022-01-31T00:00:00Z", "2022-03-01T00:00:00Z", "29"],
+        svec!["2022-03-01T00:00:00Z", "2022-01-31T00:00:00Z", "-29"],
+        svec!["", "2022-03-01T00:00:00Z", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("diff=months")
+        .arg("CloseDate")
+        .args(["--comparand", "OpenDate"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "1");
+    assert_eq!(got[2][1], "-1");
+}
+
+#[test]
+fn apply_dateops_invalid() {
+    let wrk = Workdir::new("apply_dateops_invalid");
+    wrk.create("data.csv", vec![svec!["date"], svec!["2022-01-31"]]);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("to_tz=Mars/Olympus_Mons")
+        .arg("date")
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("Unknown timezone 'Mars/Olympus_Mons'"));
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("dateops")
+        .arg("year,month")
+        .arg("date")
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("The 'year' date operation must be the last one."));
+}
+
+#[test]
+fn apply_extract_first() {
+    let wrk = Workdir::new("apply_extract_first");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "notes"],
+            svec!["1", "see ABC-123 and XYZ-9"],
+            svec!["2", "no ticket"],
+            svec!["3", "DEF-42"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("extract")
+        .arg("(?P<code>[A-Z]{3})-(?P<number>[0-9]+)")
+        .arg("notes")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "notes", "code", "number"],
+        svec!["1", "see ABC-123 and XYZ-9", "ABC", "123"],
+        svec!["2", "no ticket", "", ""],
+        svec!["3", "DEF-42", "DEF", "42"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_extract_join() {
+    let wrk = Workdir::new("apply_extract_join");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["notes"],
+            svec!["see ABC-123 and XYZ-9"],
+            svec!["no ticket"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("extract")
+        .arg("([A-Z]{3})-([0-9]+)")
+        .arg("notes")
+        .args(["--matches", "join"])
+        .args(["--separator", ";"])
+        .args(["--replacement", "none"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["notes", "notes_1", "notes_2"],
+        svec!["see ABC-123 and XYZ-9", "ABC;XYZ", "123;9"],
+        svec!["no ticket", "none", "none"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_extract_explode() {
+    let wrk = Workdir::new("apply_extract_explode");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "contacts"],
+            svec!["Alice", "alice@example.com, alice.w@example.org"],
+            svec!["Bob", "call me"],
+            svec!["Carol", "carol@example.net"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("extract")
+        .arg(r"[\w.+-]+@[\w-]+\.[\w.]+")
+        .arg("contacts")
+        .args(["--matches", "explode"])
+        .args(["--new-column", "email"])
+        .args(["--replacement", "N/A"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "contacts", "email"],
+        svec![
+            "Alice",
+            "alice@example.com, alice.w@example.org",
+            "alice@example.com"
+        ],
+        svec![
+            "Alice",
+            "alice@example.com, alice.w@example.org",
+            "alice.w@example.org"
+        ],
+        svec!["Bob", "call me", "N/A"],
+        svec!["Carol", "carol@example.net", "carol@example.net"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_extract_invalid() {
+    let wrk = Workdir::new("apply_extract_invalid");
+    wrk.create("data.csv", vec![svec!["notes"], svec!["ABC-123"]]);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("extract")
+        .arg("([A-Z]{3})-([0-9]+)")
+        .arg("notes")
+        .args(["--new-column", "code"])
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got
+        .starts_with("--new-column must have 2 comma-separated name(s), one per extracted group."));
+}
+
+#[test]
+fn apply_recipe_yaml() {
+    let wrk = Workdir::new("apply_recipe_yaml");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "email", "signup", "country"],
+            svec![
+                "  john smith ",
+                " John@Example.COM ",
+                "2023-01-15T10:00:00Z",
+                "US"
+            ],
+            svec!["jane doe", "jane@example.com", "", ""],
+        ],
+    );
+    wrk.create_from_string(
+        "recipe.yaml",
+        r#"steps:
+  - columns: name
+    operations: trim,titlecase
+  - columns: email
+    operations: trim,lower
+  - columns: email
+    operations: len
+    new_column: email_len
+  - columns: signup
+    datefmt: '%Y-%m-%d'
+  - columns: country
+    emptyreplace: Unknown
+  - columns: signup
+    dateops: year
+    new_column: signup_year
+  - columns: signup_year
+    operations: replace
+    comparand: '20'
+    replacement: "'"
+"#,
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("recipe").arg("recipe.yaml").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "name",
+            "email",
+            "signup",
+            "country",
+            "email_len",
+            "signup_year"
+        ],
+        svec![
+            "John Smith",
+            "john@example.com",
+            "2023-01-15",
+            "US",
+            "16",
+            "'23"
+        ],
+        svec!["Jane Doe", "jane@example.com", "", "Unknown", "16", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_recipe_json() {
+    let wrk = Workdir::new("apply_recipe_json");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["first", "last", "start", "end"],
+            svec!["ada", "lovelace", "2023-01-01", "2023-03-01"],
+            svec!["alan", "turing", "2023-05-01", ""],
+        ],
+    );
+    wrk.create_from_string(
+        "recipe.json",
+        r#"{
+  "steps": [
+    {"columns": "first,last", "operations": "upper"},
+    {"columns": "last", "operations": "mask", "mask_keep": 2},
+    {"columns": "end", "dateops": "diff=days", "comparand": "start", "new_column": "days"}
+  ]
+}"#,
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("recipe").arg("recipe.json").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["first", "last", "start", "end", "days"],
+        svec!["ADA", "XXXXXXCE", "2023-01-01", "2023-03-01", "59"],
+        svec!["ALAN", "XXXXNG", "2023-05-01", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_recipe_mask_steps() {
+    let wrk = Workdir::new("apply_recipe_mask_steps");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["card", "phone", "ssn"],
+            svec!["4111111111111111", "555-0123", "078-05-1120"],
+        ],
+    );
+    wrk.create_from_string(
+        "recipe.yaml",
+        r#"steps:
+  - columns: card
+    operations: mask
+  - columns: phone
+    operations: mask
+    mask_keep: 2
+  - columns: ssn
+    operations: mask
+    mask_keep: 0
+"#,
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("recipe").arg("recipe.yaml").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["card", "phone", "ssn"],
+        svec!["9999999999991111", "999-9923", "999-99-9999"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_recipe_invalid() {
+    let wrk = Workdir::new("apply_recipe_invalid");
+    wrk.create("data.csv", vec![svec!["name", "email"], svec!["a", "b"]]);
+    wrk.create_from_string(
+        "recipe.yaml",
+        r#"steps:
+  - columns: name
+    operations: trim
+  - columns: email
+    operations: lower
+    datefmt: '%Y'
+"#,
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("recipe").arg("recipe.yaml").arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with(
+        "recipe step 2 (columns: email): Each step must have exactly one of operations, datefmt, \
+         dateops or emptyreplace."
+    ));
+
+    wrk.create_from_string(
+        "unknown.yaml",
+        "steps:\n  - columns: nickname\n    operations: trim\n",
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("recipe").arg("unknown.yaml").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn apply_geocode() {
     let wrk = Workdir::new("apply");
@@ -2285,3 +2754,504 @@ fn apply_geocode_fmtstring_intl() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn apply_ops_hashes() {
+    let wrk = Workdir::new("apply_ops_hashes");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "surname"],
+            svec!["John", "Cena"],
+            svec!["Mary", "Jane"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("md5")
+        .arg("surname")
+        .arg("--new-column")
+        .arg("md5")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "surname", "md5"],
+        svec!["John", "Cena", "6755e6b3457f9e8447566dab2996aaf1"],
+        svec!["Mary", "Jane", "2b95993380f8be6bd4bd46bf44f98db9"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("sha256")
+        .arg("surname")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "surname"],
+        svec![
+            "John",
+            "941b287ad6871bc91981df240395dc62217d6b5eef5345cbdcf718d549182485"
+        ],
+        svec![
+            "Mary",
+            "4f23798d92708359b734a18172c9c864f1d48044a754115a0d4b843bca3a5332"
+        ],
+    ];
+    assert_eq!(got, expected);
+
+    for (op, hex_len) in [("blake3", 64), ("xxh3", 16)] {
+        let mut cmd = wrk.command("apply");
+        cmd.arg("operations")
+            .arg(op)
+            .arg("name,surname")
+            .arg("data.csv");
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        for row in &got[1..] {
+            assert_ne!(row[0], row[1]);
+            for hash in row {
+                assert_eq!(hash.len(), hex_len);
+                assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+            }
+        }
+    }
+}
+
+#[test]
+fn apply_ops_hmac() {
+    let wrk = Workdir::new("apply_ops_hmac");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "surname"],
+            svec!["John", "Cena"],
+            svec!["Mary", "Jane"],
+        ],
+    );
+    let expected = vec![
+        svec!["name", "surname"],
+        svec![
+            "John",
+            "b6a0c70adc3fe7eba3b438b27777eb400a125033e82c4dc5957b6e2e8f5f3007"
+        ],
+        svec![
+            "Mary",
+            "9cb84f607355693582c88deaa79aa4e5abf517c6d352c71a4cc75d3243f6ee4b"
+        ],
+    ];
+
+    let mut cmd = wrk.command("apply");
+    cmd.env("QSV_HMAC_KEY", "secret")
+        .arg("operations")
+        .arg("hmac")
+        .arg("surname")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.env("PII_KEY", "secret")
+        .arg("operations")
+        .arg("hmac")
+        .arg("surname")
+        .arg("--hmac-key")
+        .arg("env:PII_KEY")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    wrk.create_from_string("hmac.key", "secret\n");
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("hmac")
+        .arg("surname")
+        .arg("--hmac-key")
+        .arg("file:hmac.key")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_ops_hmac_no_key() {
+    let wrk = Workdir::new("apply_ops_hmac_no_key");
+    wrk.create(
+        "data.csv",
+        vec![svec!["name", "surname"], svec!["John", "Cena"]],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.env_remove("QSV_HMAC_KEY")
+        .arg("operations")
+        .arg("hmac")
+        .arg("surname")
+        .arg("data.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("The hmac operation requires a secret key"));
+}
+
+#[test]
+fn apply_ops_mask() {
+    let wrk = Workdir::new("apply_ops_mask");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["card", "name"],
+            svec!["4111-1111-1111-1234", "Mary Jane"],
+            svec!["12", "Sue"],
+            svec!["", "Ab1"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("mask")
+        .arg("card")
+        .arg("--new-column")
+        .arg("masked")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["card", "name", "masked"],
+        svec!["4111-1111-1111-1234", "Mary Jane", "9999-9999-9999-1234"],
+        svec!["12", "Sue", "12"],
+        svec!["", "Ab1", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("mask")
+        .arg("name")
+        .arg("--mask-keep")
+        .arg("1")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["card", "name"],
+        svec!["4111-1111-1111-1234", "Xxxx Xxxe"],
+        svec!["12", "Xxe"],
+        svec!["", "Xx1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_ops_unicode_normalization() {
+    let wrk = Workdir::new("apply_ops_unicode_normalization");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["composed", "decomposed", "compat"],
+            svec!["\u{e9}t\u{e9}", "e\u{301}te\u{301}", "\u{fb01}le \u{2460}"],
+        ],
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("nfd,len")
+        .arg("composed")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][0], "7");
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("nfc")
+        .arg("decomposed")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "\u{e9}t\u{e9}");
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("nfkc")
+        .arg("compat")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][2], "file 1");
+}
+
+#[test]
+fn apply_ops_unaccent_ascii() {
+    let wrk = Workdir::new("apply_ops_unaccent_ascii");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name"],
+            svec!["Crème Brûlée"],
+            svec!["Łódź"],
+            svec!["Αθήνα"],
+            svec!["हिन्दी"],
+        ],
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("unaccent")
+        .arg("name")
+        .args(["--new-column", "unaccented"])
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "unaccented"],
+        svec!["Crème Brûlée", "Creme Brulee"],
+        svec!["Łódź", "Łodz"],
+        svec!["Αθήνα", "Αθηνα"],
+        svec!["हिन्दी", "हिन्दी"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("ascii")
+        .arg("name")
+        .args(["--new-column", "ascii"])
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "ascii"],
+        svec!["Crème Brûlée", "Creme Brulee"],
+        svec!["Łódź", "Lodz"],
+        svec!["Αθήνα", "Athena"],
+        svec!["हिन्दी", "hindii"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_ops_unaccent_extended_marks() {
+    let wrk = Workdir::new("apply_ops_unaccent_extended_marks");
+    // marks of the Extended, Supplement & Half Marks blocks
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name"],
+            svec!["Ma\u{1ab0}ori\u{1dc0} t\u{fe20}s\u{fe21}"],
+        ],
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("unaccent")
+        .arg("name")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["Maori ts"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_ops_casefold_strip_invisible() {
+    let wrk = Workdir::new("apply_ops_casefold_strip_invisible");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["street"],
+            svec!["Stra\u{200b}ße"],
+            svec!["\u{feff}MAIN\u{7} St"],
+            svec!["Line\tone"],
+        ],
+    );
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("operations")
+        .arg("strip_invisible,casefold")
+        .arg("street")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["street"],
+        svec!["strasse"],
+        svec!["main st"],
+        svec!["line\tone"],
+    ];
+    assert_eq!(got, expected);
+}
+
+fn geonames_cities(wrk: &Workdir) {
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["lat", "lon", "name", "admin1", "admin2", "cc"],
+            svec![
+                "40.6501",
+                "-73.94958",
+                "Brooklyn",
+                "New York",
+                "Kings County",
+                "US"
+            ],
+            svec![
+                "48.85341",
+                "2.3488",
+                "Paris",
+                "Île-de-France",
+                "Paris",
+                "FR"
+            ],
+            svec![
+                "33.66094",
+                "-95.55551",
+                "Paris",
+                "Texas",
+                "Lamar County",
+                "US"
+            ],
+            svec![
+                "39.80172",
+                "-89.64371",
+                "Springfield",
+                "Illinois",
+                "Sangamon County",
+                "US"
+            ],
+            svec![
+                "42.10148",
+                "-72.58981",
+                "Springfield",
+                "Massachusetts",
+                "Hampden County",
+                "US"
+            ],
+            svec!["0.7655", "124.2982", "Passi", "North Sulawesi", "", "ID"],
+        ],
+    );
+}
+
+#[test]
+fn apply_fwdgeocode() {
+    let wrk = Workdir::new("apply_fwdgeocode");
+    geonames_cities(&wrk);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["Place"],
+            svec!["Brooklyn, NY"],
+            svec!["Paris, FR"],
+            svec!["paris, texas"],
+            svec!["Springfeild, MA"],
+            svec!["Pariss, North Sulawesi"],
+            svec!["Atlantis"],
+            svec![""],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("fwdgeocode")
+        .arg("Place")
+        .arg("--cities")
+        .arg("cities.csv")
+        .arg("--new-column")
+        .arg("Location")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Place", "Location"],
+        svec!["Brooklyn, NY", "40.6501, -73.94958"],
+        svec!["Paris, FR", "48.85341, 2.3488"],
+        svec!["paris, texas", "33.66094, -95.55551"],
+        svec!["Springfeild, MA", "42.10148, -72.58981"],
+        svec!["Pariss, North Sulawesi", "0.7655, 124.2982"],
+        svec!["Atlantis", "Atlantis"],
+        svec!["", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_fwdgeocode_fmtstring() {
+    let wrk = Workdir::new("apply_fwdgeocode_fmtstring");
+    geonames_cities(&wrk);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["Place"],
+            svec!["Brooklyn, NY"],
+            svec!["Springfield, IL"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.env("QSV_GEOCODE_CITIES", "cities.csv")
+        .arg("fwdgeocode")
+        .arg("Place")
+        .arg("--formatstr")
+        .arg("county-state-country")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Place"],
+        svec!["Kings County, New York US"],
+        svec!["Sangamon County, Illinois US"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("apply");
+    cmd.arg("fwdgeocode")
+        .arg("Place")
+        .arg("--cities")
+        .arg("cities.csv")
+        .arg("--formatstr")
+        .arg("lat")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["Place"], svec!["40.6501"], svec!["39.80172"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_fwdgeocode_ambiguous() {
+    let wrk = Workdir::new("apply_fwdgeocode_ambiguous");
+    geonames_cities(&wrk);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["Place"],
+            svec!["Paris"],
+            svec!["Paris, US"],
+            svec!["Springfield, US"],
+            svec!["Springfeild"],
+            svec!["Springfeild, IL"],
+        ],
+    );
+    let mut cmd = wrk.command("apply");
+    cmd.arg("fwdgeocode")
+        .arg("Place")
+        .arg("--cities")
+        .arg("cities.csv")
+        .arg("--new-column")
+        .arg("Location")
+        .arg("data.csv");
+
+    // the places with the same name are only resolved when the qualifiers tell them apart
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Place", "Location"],
+        svec!["Paris", "Paris"],
+        svec!["Paris, US", "33.66094, -95.55551"],
+        svec!["Springfield, US", "Springfield, US"],
+        svec!["Springfeild", "Springfeild"],
+        svec!["Springfeild, IL", "39.80172, -89.64371"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn apply_fwdgeocode_no_cities() {
+    let wrk = Workdir::new("apply_fwdgeocode_no_cities");
+    wrk.create("data.csv", vec![svec!["Place"], svec!["Brooklyn, NY"]]);
+    let mut cmd = wrk.command("apply");
+    cmd.env_remove("QSV_GEOCODE_CITIES")
+        .arg("fwdgeocode")
+        .arg("Place")
+        .arg("data.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("fwdgeocode requires a Geonames cities file"));
+}
@@ -0,0 +1,208 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["name", "n"]];
+    for n in 1..=10 {
+        rows.push(vec![format!("name{n}"), n.to_string()]);
+    }
+    rows
+}
+
+#[test]
+fn bin_equal_width() {
+    let wrk = Workdir::new("bin_equal_width");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("n").arg("in.csv").args(["--bins", "3"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "n", "n_bin"],
+        svec!["name1", "1", "[1, 4)"],
+        svec!["name2", "2", "[1, 4)"],
+        svec!["name3", "3", "[1, 4)"],
+        svec!["name4", "4", "[4, 7)"],
+        svec!["name5", "5", "[4, 7)"],
+        svec!["name6", "6", "[4, 7)"],
+        svec!["name7", "7", "[7, 10]"],
+        svec!["name8", "8", "[7, 10]"],
+        svec!["name9", "9", "[7, 10]"],
+        svec!["name10", "10", "[7, 10]"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_summary() {
+    let wrk = Workdir::new("bin_summary");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("n")
+        .arg("in.csv")
+        .args(["--bins", "3"])
+        .arg("--summary");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "bin", "lower", "upper", "count", "percentage"],
+        svec!["n", "[1, 4)", "1", "4", "3", "30"],
+        svec!["n", "[4, 7)", "4", "7", "3", "30"],
+        svec!["n", "[7, 10]", "7", "10", "4", "40"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_summary_from_stats_cache() {
+    let wrk = Workdir::new("bin_summary_from_stats_cache");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    // doctor the cached max, so we can tell bin used it
+    let cache_path = wrk.path("in.csv.stats.json");
+    let cache: String = wrk.from_str(&cache_path);
+    let cache = cache.replacen(r#""10","#, r#""19","#, 1);
+    wrk.create_from_string("in.csv.stats.json", &cache);
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("n")
+        .arg("in.csv")
+        .args(["--bins", "2"])
+        .arg("--summary");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "bin", "lower", "upper", "count", "percentage"],
+        svec!["n", "[1, 10)", "1", "10", "9", "90"],
+        svec!["n", "[10, 19]", "10", "19", "1", "10"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_quantile() {
+    let wrk = Workdir::new("bin_quantile");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["n"],
+            svec!["1"],
+            svec!["2"],
+            svec!["3"],
+            svec!["4"],
+            svec!["100"],
+        ],
+    );
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("n")
+        .arg("in.csv")
+        .args(["--bins", "2"])
+        .arg("--quantile");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "n_bin"],
+        svec!["1", "[1, 3)"],
+        svec!["2", "[1, 3)"],
+        svec!["3", "[3, 100]"],
+        svec!["4", "[3, 100]"],
+        svec!["100", "[3, 100]"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_custom_edges() {
+    let wrk = Workdir::new("bin_custom_edges");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["age"],
+            svec!["5"],
+            svec!["18"],
+            svec!["40"],
+            svec![""],
+            svec!["130"],
+        ],
+    );
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("age")
+        .arg("in.csv")
+        .args(["--edges", "0,18,65,120"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["age", "age_bin"],
+        svec!["5", "[0, 18)"],
+        svec!["18", "[18, 65)"],
+        svec!["40", "[18, 65)"],
+        svec!["", ""],
+        svec!["130", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_dates() {
+    let wrk = Workdir::new("bin_dates");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["day"],
+            svec!["2022-01-01"],
+            svec!["2022-01-05"],
+            svec!["2022-01-09"],
+        ],
+    );
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("day").arg("in.csv").args(["--bins", "2"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["day", "day_bin"],
+        svec!["2022-01-01", "[2022-01-01, 2022-01-05)"],
+        svec!["2022-01-05", "[2022-01-05, 2022-01-09]"],
+        svec!["2022-01-09", "[2022-01-05, 2022-01-09]"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_bars() {
+    let wrk = Workdir::new("bin_bars");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("n")
+        .arg("in.csv")
+        .args(["--bins", "3"])
+        .arg("--bars")
+        .args(["--bar-width", "8"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let lines: Vec<&str> = got.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with('n'));
+    assert!(lines[0].contains("[1, 4)"));
+    assert!(lines[0].ends_with("30%  ██████"));
+    assert!(lines[2].ends_with("40%  ████████"));
+}
+
+#[test]
+fn bin_text_column() {
+    let wrk = Workdir::new("bin_text_column");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("bin");
+    cmd.arg("name").arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,249 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "status", "start", "end", "zip", "email"],
+        svec![
+            "1",
+            "open",
+            "2022-01-01",
+            "2022-01-05",
+            "12345",
+            "a@example.com"
+        ],
+        svec!["2", "closed", "2022-01-02", "2022-01-02", "23456", ""],
+        svec![
+            "3",
+            "open",
+            "2022-01-03",
+            "2022-01-10",
+            "34567-1234",
+            "c@example.com"
+        ],
+        svec![
+            "4",
+            "pending",
+            "2022-01-04",
+            "2022-01-08",
+            "45678",
+            "d@example.com"
+        ],
+    ]
+}
+
+fn run_check(wrk: &Workdir, rules: &str) -> (Vec<Vec<String>>, bool) {
+    wrk.create("in.csv", data());
+    wrk.create_from_string("rules.yaml", rules);
+
+    let mut cmd = wrk.command("check");
+    cmd.arg("rules.yaml").arg("in.csv");
+
+    let output = wrk.output(&mut cmd);
+    let mut rdr = csv::Reader::from_reader(&*output.stdout);
+    let report = rdr
+        .records()
+        .map(|record| record.unwrap().iter().map(String::from).collect())
+        .collect();
+    (report, output.status.success())
+}
+
+#[test]
+fn check_all_pass() {
+    let wrk = Workdir::new("check_all_pass");
+    let rules = r#"
+rules:
+  - type: row_count
+    min: 1
+    max: 10
+  - name: unique ids
+    type: unique
+    columns: [id]
+  - type: null_rate
+    column: email
+    max: 0.25
+  - type: values_in
+    column: status
+    values: [open, closed, pending]
+  - type: monotonic
+    column: start
+    strict: true
+  - type: compare
+    left: end
+    op: ">="
+    right: start
+  - type: regex
+    column: zip
+    pattern: '^\d{5}(-\d{4})?$'
+"#;
+
+    let (report, success) = run_check(&wrk, rules);
+    assert!(success);
+    assert_eq!(report.len(), 7);
+    let names: Vec<&str> = report.iter().map(|row| row[0].as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "row_count",
+            "unique ids",
+            "null_rate(email)",
+            "values_in(status)",
+            "monotonic(start)",
+            "compare(end >= start)",
+            "regex(zip)"
+        ]
+    );
+    assert!(report.iter().all(|row| row[3] == "pass"));
+    assert_eq!(report[0][4], "4");
+    assert_eq!(report[0][6], "4 rows, expected between 1 & 10 rows");
+    assert_eq!(report[2][4], "4");
+    assert_eq!(report[2][5], "1");
+    assert_eq!(report[4][4], "4");
+}
+
+#[test]
+fn check_failures() {
+    let wrk = Workdir::new("check_failures");
+    let rules = r#"
+rules:
+  - type: row_count
+    max: 3
+  - type: unique
+    columns: [status]
+  - type: null_rate
+    column: email
+    max: 0.1
+  - type: values_in
+    column: status
+    values: [open, closed]
+  - type: monotonic
+    column: end
+  - type: compare
+    left: end
+    op: ">"
+    right: start
+  - type: regex
+    column: zip
+    pattern: '^\d{5}$'
+"#;
+
+    let (report, success) = run_check(&wrk, rules);
+    assert!(!success);
+    assert!(report.iter().all(|row| row[3] == "fail"));
+    assert_eq!(report[0][6], "4 rows, expected at most 3 rows");
+    assert_eq!(report[1][5], "1");
+    assert_eq!(report[1][6], "first failed rows: 3");
+    assert_eq!(report[2][6], "null rate 0.25, expected at most 0.1");
+    assert_eq!(report[3][6], "first failed rows: 4");
+    assert_eq!(report[4][6], "first failed rows: 2, 4");
+    assert_eq!(report[5][6], "first failed rows: 2");
+    assert_eq!(report[6][6], "first failed rows: 3");
+}
+
+#[test]
+fn check_compare_value_ignore_case() {
+    let wrk = Workdir::new("check_compare_value_ignore_case");
+    let rules = r#"
+rules:
+  - type: compare
+    left: id
+    op: "<="
+    value: 4
+  - type: values_in
+    column: status
+    values: [OPEN, CLOSED, PENDING]
+    ignore_case: true
+  - type: unique
+    columns: [status, start]
+"#;
+
+    let (report, success) = run_check(&wrk, rules);
+    assert!(success);
+    assert_eq!(report[0][0], "compare(id <= 4)");
+    assert_eq!(report[0][4], "4");
+    assert_eq!(report[2][2], "status,start");
+}
+
+#[test]
+fn check_json() {
+    let wrk = Workdir::new("check_json");
+    wrk.create("in.csv", data());
+    wrk.create_from_string(
+        "rules.yaml",
+        "rules:\n  - type: unique\n    columns: [status]\n",
+    );
+
+    let mut cmd = wrk.command("check");
+    cmd.arg("rules.yaml").arg("in.csv").arg("--json");
+
+    let output = wrk.output(&mut cmd);
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["rule"], "unique(status)");
+    assert_eq!(json[0]["status"], "fail");
+    assert_eq!(json[0]["checked"], 4);
+    assert_eq!(json[0]["failed"], 1);
+    assert_eq!(json[0]["failed_rows"], serde_json::json!([3]));
+}
+
+#[test]
+fn check_unknown_column() {
+    let wrk = Workdir::new("check_unknown_column");
+    wrk.create("in.csv", data());
+    wrk.create_from_string(
+        "rules.yaml",
+        "rules:\n  - type: null_rate\n    column: nope\n    max: 0.1\n",
+    );
+
+    let mut cmd = wrk.command("check");
+    cmd.arg("rules.yaml").arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn check_column_names() {
+    let wrk = Workdir::new("check_column_names");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "start-date", "end-date"],
+            svec!["1", "2022-01-01", "2022-01-05"],
+            svec!["2", "2022-01-02", "2022-01-02"],
+        ],
+    );
+    // start-date is the name of a column, not a range, & 1 is the index of the id column
+    wrk.create_from_string(
+        "rules.yaml",
+        r#"
+rules:
+  - type: compare
+    left: end-date
+    op: ">="
+    right: start-date
+  - type: unique
+    columns: ["1"]
+"#,
+    );
+
+    let mut cmd = wrk.command("check");
+    cmd.arg("rules.yaml").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][0], "compare(end-date >= start-date)");
+    assert_eq!(got[1][3], "pass");
+    assert_eq!(got[2][2], "1");
+    assert_eq!(got[2][3], "pass");
+}
+
+#[test]
+fn check_invalid_rules() {
+    let wrk = Workdir::new("check_invalid_rules");
+    wrk.create("in.csv", data());
+    wrk.create_from_string("rules.yaml", "rules:\n  - type: no_such_rule\n");
+
+    let mut cmd = wrk.command("check");
+    cmd.arg("rules.yaml").arg("in.csv");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("Cannot parse the rules file rules.yaml"));
+}
@@ -0,0 +1,100 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["a", "b", "c", "name"],
+        svec!["1", "2", "3", "x"],
+        svec!["2", "4", "1", "y"],
+        svec!["3", "6", "2", "z"],
+        svec!["4", "8", "", "w"],
+    ]
+}
+
+fn long_expected() -> Vec<Vec<String>> {
+    vec![
+        svec!["x", "y", "n", "pearson", "covariance"],
+        svec!["a", "b", "4", "1", "3.3333"],
+        svec!["a", "c", "3", "-0.5", "-0.5"],
+        svec!["b", "c", "3", "-0.5", "-1"],
+    ]
+}
+
+#[test]
+fn correlation() {
+    let wrk = Workdir::new("correlation");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("correlation");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, long_expected());
+}
+
+#[test]
+fn correlation_indexed() {
+    let wrk = Workdir::new("correlation_indexed");
+    wrk.create_indexed("in.csv", data());
+
+    let mut cmd = wrk.command("correlation");
+    cmd.arg("in.csv").args(["--jobs", "2"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, long_expected());
+}
+
+#[test]
+fn correlation_spearman() {
+    let wrk = Workdir::new("correlation_spearman");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["x", "y"],
+            svec!["1", "1"],
+            svec!["2", "4"],
+            svec!["3", "9"],
+            svec!["4", "1000"],
+        ],
+    );
+
+    let mut cmd = wrk.command("correlation");
+    cmd.arg("in.csv").arg("--spearman");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec!["x", "y", "n", "pearson", "covariance", "spearman"]
+    );
+    // monotonic, but not linear
+    assert_ne!(got[1][3], "1");
+    assert_eq!(got[1][5], "1");
+}
+
+#[test]
+fn correlation_matrix() {
+    let wrk = Workdir::new("correlation_matrix");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("correlation");
+    cmd.arg("in.csv").args(["--matrix", "pearson"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "a", "b", "c"],
+        svec!["a", "1", "1", "-0.5"],
+        svec!["b", "1", "1", "-0.5"],
+        svec!["c", "-0.5", "-0.5", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn correlation_not_enough_numeric_columns() {
+    let wrk = Workdir::new("correlation_not_enough_numeric_columns");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("correlation");
+    cmd.arg("in.csv").args(["--select", "a,name"]);
+
+    wrk.assert_err(&mut cmd);
+}
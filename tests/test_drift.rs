@@ -0,0 +1,226 @@
+use crate::workdir::Workdir;
+
+fn baseline() -> Vec<Vec<String>> {
+    let colors = [
+        "red", "red", "red", "red", "red", "blue", "blue", "blue", "green", "green",
+    ];
+    let mut rows = vec![svec!["id", "amount", "color"]];
+    for (i, color) in colors.iter().enumerate() {
+        rows.push(vec![
+            (i + 1).to_string(),
+            (i + 1).to_string(),
+            color.to_string(),
+        ]);
+    }
+    rows
+}
+
+fn current() -> Vec<Vec<String>> {
+    let colors = [
+        "red", "red", "blue", "blue", "blue", "blue", "green", "green", "green", "green",
+    ];
+    let mut rows = vec![svec!["id", "amount", "color"]];
+    for (i, color) in colors.iter().enumerate() {
+        rows.push(vec![
+            (i + 1).to_string(),
+            (i + 6).to_string(),
+            color.to_string(),
+        ]);
+    }
+    rows
+}
+
+fn metric(row: &[String], i: usize) -> f64 {
+    row[i].parse().unwrap()
+}
+
+#[test]
+fn drift_csv() {
+    let wrk = Workdir::new("drift_csv");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", current());
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv").arg("current.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 4);
+    assert_eq!(
+        got[0],
+        svec![
+            "field",
+            "type",
+            "baseline_rows",
+            "current_rows",
+            "baseline_null_rate",
+            "current_null_rate",
+            "null_rate_delta",
+            "baseline_cardinality",
+            "current_cardinality",
+            "cardinality_delta",
+            "ks_statistic",
+            "ks_pvalue",
+            "psi",
+            "max_share_delta",
+            "max_share_value",
+            "drift"
+        ]
+    );
+    // the same values
+    assert_eq!(got[1][0], "id");
+    assert_eq!(got[1][1], "numeric");
+    assert_eq!(metric(&got[1], 10), 0.0);
+    assert_eq!(metric(&got[1], 11), 1.0);
+    // shifted by half the range
+    assert_eq!(got[2][0], "amount");
+    assert_eq!(metric(&got[2], 10), 0.5);
+    assert!(metric(&got[2], 11) < 0.2);
+    assert_eq!(got[2][12], "");
+}
+
+#[test]
+fn drift_categorical() {
+    let wrk = Workdir::new("drift_categorical");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", current());
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv")
+        .arg("current.csv")
+        .args(["--select", "color"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 2);
+    let row = &got[1];
+    assert_eq!(row[0], "color");
+    assert_eq!(row[1], "categorical");
+    assert_eq!(row[7], "3");
+    assert_eq!(row[8], "3");
+    assert_eq!(row[9], "0");
+    assert_eq!(row[10], "");
+    assert_eq!(metric(row, 12), 0.4423);
+    assert_eq!(metric(row, 13), -0.3);
+    assert_eq!(row[14], "red");
+    assert_eq!(row[15], "");
+}
+
+#[test]
+fn drift_nulls_cardinality() {
+    let wrk = Workdir::new("drift_nulls_cardinality");
+    wrk.create(
+        "baseline.csv",
+        vec![
+            svec!["color"],
+            svec!["red"],
+            svec!["blue"],
+            svec!["red"],
+            svec!["blue"],
+        ],
+    );
+    wrk.create(
+        "current.csv",
+        vec![
+            svec!["color"],
+            svec!["red"],
+            svec![""],
+            svec!["green"],
+            svec!["blue"],
+        ],
+    );
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv").arg("current.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let row = &got[1];
+    assert_eq!(metric(row, 4), 0.0);
+    assert_eq!(metric(row, 5), 0.25);
+    assert_eq!(metric(row, 6), 0.25);
+    assert_eq!(row[7], "2");
+    assert_eq!(row[8], "3");
+    assert_eq!(row[9], "1");
+}
+
+#[test]
+fn drift_thresholds_exit_code() {
+    let wrk = Workdir::new("drift_thresholds_exit_code");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", current());
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv")
+        .arg("current.csv")
+        .args(["--ks-threshold", "0.3"])
+        .args(["--psi-threshold", "0.25"]);
+
+    let output = wrk.output(&mut cmd);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let drift: Vec<&str> = stdout
+        .lines()
+        .skip(1)
+        .map(|line| line.rsplit(',').next().unwrap())
+        .collect();
+    assert_eq!(drift, vec!["", "ks", "psi"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Drift detected in 2 of 3 columns: amount, color"));
+}
+
+#[test]
+fn drift_thresholds_no_drift() {
+    let wrk = Workdir::new("drift_thresholds_no_drift");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", baseline());
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv")
+        .arg("current.csv")
+        .args(["--ks-threshold", "0.1"])
+        .args(["--psi-threshold", "0.1"])
+        .args(["--share-threshold", "0.1"])
+        .args(["--null-threshold", "0.1"]);
+
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn drift_json() {
+    let wrk = Workdir::new("drift_json");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", current());
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv")
+        .arg("current.csv")
+        .args(["--select", "color"])
+        .args(["--top", "2"])
+        .arg("--json");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let json: serde_json::Value = serde_json::from_str(&got).unwrap();
+    let color = &json[0];
+    assert_eq!(color["field"], "color");
+    assert_eq!(color["type"], "categorical");
+    assert_eq!(color["psi"], 0.4423);
+    assert_eq!(color["ks_statistic"], serde_json::Value::Null);
+    assert_eq!(color["max_share_value"], "red");
+    let changes = color["share_changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0]["value"], "red");
+    assert_eq!(changes[0]["baseline_share"], 0.5);
+    assert_eq!(changes[0]["current_share"], 0.2);
+    assert_eq!(changes[1]["value"], "green");
+    assert_eq!(color["drift"], serde_json::json!([]));
+}
+
+#[test]
+fn drift_missing_column() {
+    let wrk = Workdir::new("drift_missing_column");
+    wrk.create("baseline.csv", baseline());
+    wrk.create("current.csv", vec![svec!["id", "amount"], svec!["1", "2"]]);
+
+    let mut cmd = wrk.command("drift");
+    cmd.arg("baseline.csv").arg("current.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -262,3 +262,246 @@ fn assert_eq_ftables(got: &FTables, expected: &FTables) -> bool {
     }
     true
 }
+
+#[test]
+fn frequency_stats_cache_all_unique() {
+    let (wrk, mut cmd) = setup("frequency_stats_cache_all_unique");
+
+    let mut stats_cmd = wrk.command("stats");
+    stats_cmd.args(["--cache", "--cardinality"]).arg("in.csv");
+    wrk.assert_success(&mut stats_cmd);
+
+    // doctor the cached cardinality of h1 to look all unique, so we can tell
+    // frequency used the cache and only kept the first value
+    let cache_json: String = wrk.from_str(&wrk.path("in.csv.stats.json"));
+    let mut cache: serde_json::Value = serde_json::from_str(&cache_json).unwrap();
+    let cardinality_idx = cache["headers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .position(|h| h == "cardinality")
+        .unwrap()
+        - 1;
+    cache["stats"][0][cardinality_idx] = serde_json::Value::String("6".to_string());
+    wrk.create_from_string("in.csv.stats.json", &cache.to_string());
+
+    cmd.args(["--limit", "1"]).args(["--select", "h1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["field", "value", "count"], svec!["h1", "a", "1"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_stats_cache_padded() {
+    let wrk = Workdir::new("frequency_stats_cache_padded");
+    wrk.create(
+        "in.csv",
+        vec![svec!["h1"], svec!["a"], svec!["a "], svec!["b"]],
+    );
+
+    let mut stats_cmd = wrk.command("stats");
+    stats_cmd.args(["--cache", "--cardinality"]).arg("in.csv");
+    wrk.assert_success(&mut stats_cmd);
+
+    // "a" & "a " are distinct to stats, but the same value once trimmed by frequency
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--limit", "1"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["field", "value", "count"], svec!["h1", "a", "2"]];
+    assert_eq!(got, expected);
+}
+
+fn approx_setup(name: &str) -> (Workdir, process::Command) {
+    let rows = vec![
+        svec!["h1"],
+        svec!["x"],
+        svec!["x"],
+        svec!["x"],
+        svec!["x"],
+        svec!["y"],
+        svec!["z"],
+        svec!["x"],
+        svec!["w"],
+    ];
+
+    let wrk = Workdir::new(name);
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv").arg("--approx");
+
+    (wrk, cmd)
+}
+
+#[test]
+fn frequency_approx_exact_when_capacity_suffices() {
+    let (wrk, mut cmd) = approx_setup("frequency_approx_exact_when_capacity_suffices");
+    cmd.args(["--limit", "0"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "max_error"],
+        svec!["h1", "x", "5", "0"],
+        svec!["h1", "w", "1", "0"],
+        svec!["h1", "y", "1", "0"],
+        svec!["h1", "z", "1", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_approx_bounded_capacity() {
+    let (wrk, mut cmd) = approx_setup("frequency_approx_bounded_capacity");
+    cmd.args(["--approx-capacity", "2"]).args(["--limit", "2"]);
+
+    // with two counters, y is evicted by z, which is evicted by w,
+    // so w's count is overestimated by the evicted counts
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "max_error"],
+        svec!["h1", "x", "5", "0"],
+        svec!["h1", "w", "3", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_approx_indexed() {
+    let wrk = Workdir::new("frequency_approx_indexed");
+    let mut rows = vec![svec!["h1"]];
+    for i in 0..100 {
+        rows.push(vec![if i % 3 == 0 {
+            "hot".to_string()
+        } else {
+            format!("cold{i}")
+        }]);
+    }
+    wrk.create_indexed("in.csv", rows);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv")
+        .arg("--approx")
+        .args(["--approx-capacity", "10"])
+        .args(["--limit", "1"])
+        .args(["--jobs", "4"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "hot");
+    let count: u64 = got[1][2].parse().unwrap();
+    let max_error: u64 = got[1][3].parse().unwrap();
+    // the true count of "hot" is 34
+    assert!(count >= 34 && count - max_error <= 34);
+}
+
+#[test]
+fn frequency_approx_capacity_less_than_limit() {
+    let (wrk, mut cmd) = approx_setup("frequency_approx_capacity_less_than_limit");
+    cmd.args(["--approx-capacity", "2"]).args(["--limit", "5"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+fn tally_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["state", "city", "pop"],
+        svec!["NY", "NYC", "8"],
+        svec!["NY", "Buffalo", "1"],
+        svec!["NY", "NYC", ""],
+        svec!["CA", "LA", "4"],
+        svec!["CA", "", "3"],
+    ]
+}
+
+#[test]
+fn frequency_combine() {
+    let wrk = Workdir::new("frequency_combine");
+    wrk.create("in.csv", tally_data());
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv")
+        .args(["--select", "state,city"])
+        .arg("--combine")
+        .arg("--no-nulls");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "state",
+            "city",
+            "count",
+            "percentage",
+            "cumulative_percentage"
+        ],
+        svec!["NY", "NYC", "2", "50", "50"],
+        svec!["CA", "LA", "1", "25", "75"],
+        svec!["NY", "Buffalo", "1", "25", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_weight() {
+    let wrk = Workdir::new("frequency_weight");
+    wrk.create("in.csv", tally_data());
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv")
+        .args(["--select", "state"])
+        .args(["--weight", "pop"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "field",
+            "value",
+            "weight",
+            "percentage",
+            "cumulative_percentage"
+        ],
+        svec!["state", "NY", "9", "56.25", "56.25"],
+        svec!["state", "CA", "7", "43.75", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_combine_weight_indexed() {
+    let wrk = Workdir::new("frequency_combine_weight_indexed");
+    wrk.create_indexed("in.csv", tally_data());
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv")
+        .args(["--select", "state,city"])
+        .arg("--combine")
+        .args(["--weight", "pop"])
+        .args(["--jobs", "2"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "state",
+            "city",
+            "weight",
+            "percentage",
+            "cumulative_percentage"
+        ],
+        svec!["NY", "NYC", "8", "50", "50"],
+        svec!["CA", "LA", "4", "25", "75"],
+        svec!["CA", "(NULL)", "3", "18.75", "93.75"],
+        svec!["NY", "Buffalo", "1", "6.25", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_weight_multiple_columns() {
+    let wrk = Workdir::new("frequency_weight_multiple_columns");
+    wrk.create("in.csv", tally_data());
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv").args(["--weight", "city,pop"]);
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,167 @@
+use crate::workdir::Workdir;
+
+fn cities() -> Vec<Vec<String>> {
+    vec![
+        svec!["city", "lat", "lon"],
+        svec!["New York", "40.7128", "-74.0060"],
+        svec!["Newark", "40.7357", "-74.1724"],
+        svec!["Philadelphia", "39.9526", "-75.1652"],
+        svec!["Boston", "42.3601", "-71.0589"],
+        svec!["Unknown", "", ""],
+    ]
+}
+
+#[test]
+fn geo_distance_to_columns() {
+    let wrk = Workdir::new("geo_distance_to_columns");
+    wrk.create(
+        "trips.csv",
+        vec![
+            svec!["from_lat", "from_lon", "to_lat", "to_lon"],
+            svec!["40.7128", "-74.0060", "34.0522", "-118.2437"],
+            svec!["48.8566", "2.3522", "51.5074", "-0.1278"],
+            svec!["48.8566", "2.3522", "", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("distance")
+        .args(["--to", "to_lat,to_lon"])
+        .args(["--round", "2"])
+        .arg("from_lat,from_lon")
+        .arg("trips.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["from_lat", "from_lon", "to_lat", "to_lon", "distance"],
+        svec!["40.7128", "-74.0060", "34.0522", "-118.2437", "3935.75"],
+        svec!["48.8566", "2.3522", "51.5074", "-0.1278", "343.56"],
+        svec!["48.8566", "2.3522", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn geo_distance_point_vincenty() {
+    let wrk = Workdir::new("geo_distance_point_vincenty");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["location"],
+            svec!["40.7128, -74.0060"],
+            svec!["POINT (-74.0060 40.7128)"],
+            svec!["(34.0522, -118.2437)"],
+            svec!["not a location"],
+        ],
+    );
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("distance")
+        .args(["--point", "34.0522, -118.2437"])
+        .args(["--method", "vincenty"])
+        .args(["--unit", "mi"])
+        .args(["--round", "2"])
+        .args(["--new-column", "miles_to_la"])
+        .arg("location")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["location", "miles_to_la"],
+        svec!["40.7128, -74.0060", "2450.95"],
+        svec!["POINT (-74.0060 40.7128)", "2450.95"],
+        svec!["(34.0522, -118.2437)", "0"],
+        svec!["not a location", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn geo_bbox_radius() {
+    let wrk = Workdir::new("geo_bbox_radius");
+    wrk.create("cities.csv", cities());
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("bbox")
+        .args(["--bbox", "-74.3,40.5,-73.7,40.9"])
+        .arg("lat,lon")
+        .arg("cities.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "lat", "lon"],
+        svec!["New York", "40.7128", "-74.0060"],
+        svec!["Newark", "40.7357", "-74.1724"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("radius")
+        .args(["--point", "40.7128, -74.0060"])
+        .args(["--radius", "150"])
+        .arg("--invert")
+        .arg("lat,lon")
+        .arg("cities.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "lat", "lon"],
+        svec!["Boston", "42.3601", "-71.0589"],
+        svec!["Unknown", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn geo_geohash_encode_decode() {
+    let wrk = Workdir::new("geo_geohash_encode_decode");
+    wrk.create("cities.csv", cities());
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("geohash")
+        .args(["--precision", "7"])
+        .arg("lat,lon")
+        .arg("cities.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1], svec!["New York", "40.7128", "-74.0060", "dr5regw"]);
+    assert_eq!(got[5], svec!["Unknown", "", "", ""]);
+    wrk.create("geohashes.csv", got);
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("geohash")
+        .arg("--decode")
+        .args(["--new-column", "center_lat,center_lon"])
+        .arg("geohash")
+        .arg("geohashes.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec!["city", "lat", "lon", "geohash", "center_lat", "center_lon"]
+    );
+    assert_eq!(got[1][4..], svec!["40.713272", "-74.005966"]);
+    assert_eq!(got[5][4..], svec!["", ""]);
+}
+
+#[test]
+fn geo_parse() {
+    let wrk = Workdir::new("geo_parse");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["location"],
+            svec!["40.7128, -74.0060"],
+            svec!["point(2.3522 48.8566)"],
+            svec!["91, 0"],
+        ],
+    );
+
+    let mut cmd = wrk.command("geo");
+    cmd.arg("parse").arg("location").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["location", "latitude", "longitude"],
+        svec!["40.7128, -74.0060", "40.7128", "-74.006"],
+        svec!["point(2.3522 48.8566)", "48.8566", "2.3522"],
+        svec!["91, 0", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,120 @@
+use serde_json::json;
+
+use crate::workdir::Workdir;
+
+fn zones() -> String {
+    json!({
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"zone": "downtown", "code": 1},
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+                        [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0], [4.0, 6.0], [4.0, 4.0]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": {"zone": "islands", "code": 2},
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[20.0, 20.0], [22.0, 20.0], [22.0, 22.0], [20.0, 22.0], [20.0, 20.0]]],
+                        [[[30.0, 30.0], [32.0, 30.0], [31.0, 32.0], [30.0, 30.0]]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": {"zone": "point"},
+                "geometry": {"type": "Point", "coordinates": [50.0, 50.0]}
+            }
+        ]
+    })
+    .to_string()
+}
+
+fn places() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "location"],
+        svec!["office", "2.5, 1.5"],
+        svec!["park", "5, 5"],
+        svec!["beach", "21, 21"],
+        svec!["lighthouse", "30.5, 31"],
+        svec!["nowhere", "-5, -5"],
+        svec!["invalid", "not a location"],
+    ]
+}
+
+#[test]
+fn geojoin_location() {
+    let wrk = Workdir::new("geojoin_location");
+    wrk.create_from_string("zones.geojson", &zones());
+    wrk.create("data.csv", places());
+
+    let mut cmd = wrk.command("geojoin");
+    cmd.arg("--location")
+        .arg("location")
+        .arg("zones.geojson")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "location", "zone", "code"],
+        svec!["office", "2.5, 1.5", "downtown", "1"],
+        svec!["park", "5, 5", "", ""],
+        svec!["beach", "21, 21", "islands", "2"],
+        svec!["lighthouse", "30.5, 31", "islands", "2"],
+        svec!["nowhere", "-5, -5", "", ""],
+        svec!["invalid", "not a location", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn geojoin_lat_lon_inner() {
+    let wrk = Workdir::new("geojoin_lat_lon_inner");
+    wrk.create_from_string("zones.geojson", &zones());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "lat", "lon"],
+            svec!["office", "2.5", "1.5"],
+            svec!["park", "5", "5"],
+            svec!["beach", "21", "21"],
+            svec!["nowhere", "-5", "-5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("geojoin");
+    cmd.args(["--lat", "lat", "--lon", "lon"])
+        .arg("--inner")
+        .args(["--properties", "zone"])
+        .args(["--prefix", "geo_"])
+        .arg("zones.geojson")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "lat", "lon", "geo_zone"],
+        svec!["office", "2.5", "1.5", "downtown"],
+        svec!["beach", "21", "21", "islands"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn geojoin_no_location() {
+    let wrk = Workdir::new("geojoin_no_location");
+    wrk.create_from_string("zones.geojson", &zones());
+    wrk.create("data.csv", places());
+
+    let mut cmd = wrk.command("geojoin");
+    cmd.arg("zones.geojson").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,100 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["name", "v"]];
+    for v in [1, 2, 3, 4, 5, 6, 7, 8, 9, 100] {
+        rows.push(vec![format!("name{v}"), v.to_string()]);
+    }
+    rows
+}
+
+#[test]
+fn outliers_iqr() {
+    let wrk = Workdir::new("outliers_iqr");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 11);
+    assert_eq!(got[0], svec!["name", "v", "v_outlier"]);
+    assert_eq!(got[1], svec!["name1", "1", ""]);
+    assert_eq!(got[10], svec!["name100", "100", "high"]);
+    assert!(got[1..10].iter().all(|row| row[2].is_empty()));
+}
+
+#[test]
+fn outliers_filter() {
+    let wrk = Workdir::new("outliers_filter");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv").args(["--fence", "outer"]).arg("--filter");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "v", "v_outlier"],
+        svec!["name100", "100", "high"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_filter_invert() {
+    let wrk = Workdir::new("outliers_filter_invert");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv").arg("--filter").arg("--invert");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 10);
+    assert!(got[1..].iter().all(|row| row[2].is_empty()));
+}
+
+#[test]
+fn outliers_zscore() {
+    let wrk = Workdir::new("outliers_zscore");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv")
+        .args(["--method", "zscore"])
+        .args(["--threshold", "2"])
+        .arg("--filter");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "v", "v_outlier"],
+        svec!["name100", "100", "high"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_mad() {
+    let wrk = Workdir::new("outliers_mad");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv").args(["--method", "mad"]).arg("--filter");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "v", "v_outlier"],
+        svec!["name100", "100", "high"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_no_numeric_columns() {
+    let wrk = Workdir::new("outliers_no_numeric_columns");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.arg("in.csv").args(["--select", "name"]);
+
+    wrk.assert_err(&mut cmd);
+}
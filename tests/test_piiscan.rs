@@ -0,0 +1,174 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "email", "phone", "card", "iban", "ssn", "ip", "notes"],
+        svec![
+            "Mary",
+            "mary@example.com",
+            "+1 555-123-4567",
+            "4111 1111 1111 1111",
+            "GB82 WEST 1234 5698 7654 32",
+            "123-45-6789",
+            "192.168.1.1",
+            "hello"
+        ],
+        svec![
+            "John",
+            "john@example.org",
+            "(555) 123-4567",
+            "4111111111111112",
+            "DE89370400440532013000",
+            "078-05-1120",
+            "2001:db8::1",
+            "2022-01-01"
+        ],
+        svec![
+            "Sue",
+            "not an email",
+            "12345",
+            "5500 0000 0000 0004",
+            "NOTANIBAN",
+            "666-12-3456",
+            "300.1.1.1",
+            "3.14159265"
+        ],
+        svec!["Jo", "", "", "", "", "", "", ""],
+    ]
+}
+
+#[test]
+fn piiscan() {
+    let wrk = Workdir::new("piiscan");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("piiscan");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "pii_type",
+            "matches",
+            "scanned",
+            "match_rate",
+            "confidence"
+        ],
+        svec!["email", "email", "2", "3", "0.667", "0.767"],
+        svec!["phone", "phone", "2", "3", "0.667", "0.567"],
+        svec!["card", "credit_card", "2", "3", "0.667", "0.767"],
+        svec!["iban", "iban", "2", "3", "0.667", "0.767"],
+        svec!["ssn", "national_id", "2", "3", "0.667", "0.7"],
+        svec!["ip", "ip_address", "2", "3", "0.667", "0.7"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn piiscan_select_sample() {
+    let wrk = Workdir::new("piiscan_select_sample");
+    let mut rows = vec![svec!["name", "email", "card"]];
+    for i in 0..100 {
+        rows.push(vec![
+            format!("name{i}"),
+            format!("user{i}@example.com"),
+            "4111 1111 1111 1111".to_string(),
+        ]);
+    }
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("piiscan");
+    cmd.arg("--select")
+        .arg("email,card")
+        .arg("--sample")
+        .arg("10")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "pii_type",
+            "matches",
+            "scanned",
+            "match_rate",
+            "confidence"
+        ],
+        svec!["email", "email", "10", "10", "1", "1"],
+        svec!["card", "credit_card", "10", "10", "1", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn piiscan_column_name_hints() {
+    let wrk = Workdir::new("piiscan_column_name_hints");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["client_ip", "shipping", "homeTel", "hotel"],
+            svec![
+                "192.168.1.1",
+                "192.168.1.1",
+                "+1 555-123-4567",
+                "+1 555-123-4567"
+            ],
+        ],
+    );
+
+    let mut cmd = wrk.command("piiscan");
+    cmd.arg("in.csv");
+
+    // only the words of the column names hint at the PII type, so "shipping" &
+    // "hotel" get no bonus, unlike "client_ip" & "homeTel"
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "pii_type",
+            "matches",
+            "scanned",
+            "match_rate",
+            "confidence"
+        ],
+        svec!["client_ip", "ip_address", "1", "1", "1", "1"],
+        svec!["shipping", "ip_address", "1", "1", "1", "0.9"],
+        svec!["homeTel", "phone", "1", "1", "1", "0.8"],
+        svec!["hotel", "phone", "1", "1", "1", "0.7"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn piiscan_min_rate_json() {
+    let wrk = Workdir::new("piiscan_min_rate_json");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["contact", "comment"],
+            svec!["a@example.com", "call +44 20 7946 0958"],
+            svec!["b@example.com", "+44 20 7946 0958"],
+            svec!["c@example.com", "no"],
+            svec!["d@example.com", "nope"],
+        ],
+    );
+
+    let mut cmd = wrk.command("piiscan");
+    cmd.arg("--min-rate").arg("0.5").arg("--json").arg("in.csv");
+
+    let output = wrk.output(&mut cmd);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "column": "contact",
+            "pii_type": "email",
+            "matches": 4,
+            "scanned": 4,
+            "match_rate": 1.0,
+            "confidence": 1.0,
+        }])
+    );
+}
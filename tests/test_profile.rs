@@ -0,0 +1,128 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "age", "city"],
+        svec!["Alice", "30", "Boston"],
+        svec!["Bob", "25", ""],
+        svec!["Carol", "", ""],
+        svec!["Dave", "40", "Boston"],
+        svec!["Eve", "35", "Denver"],
+    ]
+}
+
+#[test]
+fn profile_markdown() {
+    let wrk = Workdir::new("profile_markdown");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv").args(["--format", "markdown"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("# Profile of in.csv"));
+    assert!(got.contains("| Records | 5 |"));
+    assert!(got.contains("| Fields | 3 |"));
+    assert!(got.contains("| Delimiter | , |"));
+    assert!(got.contains("| Rows without nulls | 3 (60%) |"));
+    assert!(got.contains("| age | Integer | 1 |"));
+    assert!(got.contains("## city"));
+    assert!(got.contains("| Boston | 2 | 40 |"));
+    assert!(got.contains("| (NULL) | 2 | 40 |"));
+    assert!(got.contains("### JSON Schema"));
+    assert!(got.contains("\"integer\""));
+}
+
+#[test]
+fn profile_null_patterns() {
+    let wrk = Workdir::new("profile_null_patterns");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv").args(["--format", "markdown"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.contains("| (none) | 3 | 60 |"));
+    assert!(got.contains("| city | 1 | 20 |"));
+    assert!(got.contains("| age, city | 1 | 20 |"));
+}
+
+#[test]
+fn profile_histogram() {
+    let wrk = Workdir::new("profile_histogram");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv")
+        .args(["--format", "markdown"])
+        .args(["--bins", "3"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.contains("### Histogram"));
+    assert!(got.contains("| [25, 30) | 1 | 25 |"));
+    assert!(got.contains("| [35, 40] | 2 | 50 |"));
+    // only the numeric age column has a histogram
+    assert_eq!(got.matches("### Histogram").count(), 1);
+}
+
+#[test]
+fn profile_all_unique() {
+    let wrk = Workdir::new("profile_all_unique");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv").args(["--format", "markdown"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.contains("All the values are unique."));
+}
+
+#[test]
+fn profile_html() {
+    let wrk = Workdir::new("profile_html");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "score"],
+            svec!["<b>Alice</b>", "1"],
+            svec!["Bob & Co", "2"],
+        ],
+    );
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv").args(["--title", "Scores"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("<!DOCTYPE html>"));
+    assert!(got.contains("<title>Scores</title>"));
+    assert!(got.contains("&lt;b&gt;Alice&lt;/b&gt;"));
+    assert!(got.contains("Bob &amp; Co"));
+    assert!(!got.contains("<b>Alice</b>"));
+    assert!(got.contains("<div class=\"bar\""));
+    assert!(got.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn profile_output() {
+    let wrk = Workdir::new("profile_output");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("in.csv")
+        .args(["--format", "markdown"])
+        .args(["--output", "report.md"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.from_str::<String>(&wrk.path("report.md"));
+    assert!(got.starts_with("# Profile of in.csv"));
+}
+
+#[test]
+fn profile_stdin_error() {
+    let wrk = Workdir::new("profile_stdin_error");
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("-");
+
+    wrk.assert_err(&mut cmd);
+}
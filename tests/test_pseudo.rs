@@ -59,3 +59,209 @@ fn pseudo_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn pseudo_mapping() {
+    let wrk = Workdir::new("pseudo_mapping");
+    wrk.create(
+        "data1.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["Mary", "yellow"],
+            svec!["John", "blue"],
+            svec!["Mary", "purple"],
+        ],
+    );
+    wrk.create(
+        "data2.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["Sue", "orange"],
+            svec!["John", "magenta"],
+        ],
+    );
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("name")
+        .arg("--mapping")
+        .arg("mapping.csv")
+        .arg("data1.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors"],
+        svec!["0", "yellow"],
+        svec!["1", "blue"],
+        svec!["0", "purple"],
+    ];
+    assert_eq!(got, expected);
+
+    // the identifiers of the first run are reused, & new ones continue after them
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("name")
+        .arg("--mapping")
+        .arg("mapping.csv")
+        .arg("data2.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors"],
+        svec!["2", "orange"],
+        svec!["1", "magenta"],
+    ];
+    assert_eq!(got, expected);
+
+    let got: String = wrk.from_str(&wrk.path("mapping.csv"));
+    assert_eq!(got, "value,id\nMary,0\nJohn,1\nSue,2\n");
+
+    // the temporary file the mapping is saved through is renamed away
+    let tmp_files = std::fs::read_dir(wrk.path("."))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".tmp")
+        })
+        .count();
+    assert_eq!(tmp_files, 0);
+}
+
+#[test]
+fn pseudo_shared_columns() {
+    let wrk = Workdir::new("pseudo_shared_columns");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["sender", "recipient", "amount"],
+            svec!["Mary", "John", "10"],
+            svec!["John", "Sue", "20"],
+            svec!["Sue", "Mary", "30"],
+        ],
+    );
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("sender,recipient").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sender", "recipient", "amount"],
+        svec!["0", "1", "10"],
+        svec!["1", "2", "20"],
+        svec!["2", "0", "30"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "apply")]
+fn pseudo_hmac_key() {
+    let wrk = Workdir::new("pseudo_hmac_key");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["Mary", "yellow"],
+            svec!["John", "blue"],
+            svec!["Mary", "purple"],
+        ],
+    );
+    let expected = vec![
+        svec!["name", "colors"],
+        svec![
+            "dfe36b4510ae9480567fbdf78302125b309dfdad0d6c24ffcea33bc640cacd5c",
+            "yellow"
+        ],
+        svec![
+            "94369bc51b972114a0db1bb173fbeb6802a462407b180cbef068a90813b256d0",
+            "blue"
+        ],
+        svec![
+            "dfe36b4510ae9480567fbdf78302125b309dfdad0d6c24ffcea33bc640cacd5c",
+            "purple"
+        ],
+    ];
+
+    let mut cmd = wrk.command("pseudo");
+    cmd.env("PSEUDO_KEY", "secret")
+        .arg("name")
+        .arg("--hmac-key")
+        .arg("env:PSEUDO_KEY")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("pseudo");
+    cmd.env("QSV_HMAC_KEY", "secret")
+        .arg("name")
+        .arg("--keyed")
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pseudo_reverse() {
+    let wrk = Workdir::new("pseudo_reverse");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["sender", "recipient"],
+            svec!["Mary", "John"],
+            svec!["John", "Sue"],
+        ],
+    );
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("sender,recipient")
+        .arg("--mapping")
+        .arg("mapping.csv")
+        .arg("--output")
+        .arg("pseudo.csv")
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("sender,recipient")
+        .arg("--mapping")
+        .arg("mapping.csv")
+        .arg("--reverse")
+        .arg("pseudo.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sender", "recipient"],
+        svec!["Mary", "John"],
+        svec!["John", "Sue"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pseudo_reverse_requires_mapping() {
+    let wrk = Workdir::new("pseudo_reverse_requires_mapping");
+    wrk.create("data.csv", vec![svec!["name"], svec!["0"]]);
+    let mut cmd = wrk.command("pseudo");
+    cmd.arg("name").arg("--reverse").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn pseudo_reverse_keyed_error() {
+    let wrk = Workdir::new("pseudo_reverse_keyed_error");
+    wrk.create("data.csv", vec![svec!["name"], svec!["0"]]);
+    wrk.create(
+        "mapping.csv",
+        vec![svec!["value", "id"], svec!["John", "0"]],
+    );
+    let mut cmd = wrk.command("pseudo");
+    cmd.env("QSV_HMAC_KEY", "secret")
+        .arg("name")
+        .args(["--mapping", "mapping.csv"])
+        .arg("--reverse")
+        .arg("--keyed")
+        .arg("data.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("--reverse cannot be used with --hmac-key or --keyed."));
+}
@@ -157,3 +157,252 @@ fn generate_schema_with_defaults_to_stdout() {
 
     assert_json_eq!(expected_schema_json, output_schema_json);
 }
+
+#[test]
+fn generate_schema_from_stats_cache() {
+    let wrk = Workdir::new("generate_schema_from_stats_cache");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "alpha"],
+            svec!["2", "beta"],
+            svec!["3", "gamma"],
+        ],
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["--cache", "--cardinality", "--infer-dates"])
+        .args(["--dates-whitelist", "date,time,due,opened,closed"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    // doctor the cached max of the id column, so we can tell schema used the cache
+    let cache_json: String = wrk.from_str(&wrk.path("in.csv.stats.json"));
+    let mut cache: Value = serde_json::from_str(&cache_json).unwrap();
+    let max_idx = cache["headers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .position(|h| h == "max")
+        .unwrap()
+        - 1;
+    cache["stats"][0][max_idx] = Value::String("99".to_string());
+    wrk.create_from_string("in.csv.stats.json", &cache.to_string());
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("--stdout").arg("in.csv");
+    let got: String = wrk.stdout(&mut cmd);
+    let got: Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(got["properties"]["id"]["maximum"], 99);
+}
+
+fn table_schema_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "color", "amount", "start_date"],
+        svec!["1", "red", "1.5", "2022-01-01"],
+        svec!["2", "blue", "", "2022-01-02"],
+        svec!["3", "red", "3.25", "2022-01-03"],
+    ]
+}
+
+#[test]
+fn generate_table_schema() {
+    let wrk = Workdir::new("generate_table_schema").flexible(true);
+    wrk.create("in.csv", table_schema_data());
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["--format", "tableschema"])
+        .arg("--stdout")
+        .arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let table_schema: Value = serde_json::from_str(&got).unwrap();
+    let fields = table_schema["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 4);
+
+    assert_eq!(fields[0]["name"], "id");
+    assert_eq!(fields[0]["type"], "integer");
+    assert_eq!(fields[0]["constraints"]["required"], true);
+    assert_eq!(fields[0]["constraints"]["minimum"], 1);
+    assert_eq!(fields[0]["constraints"]["maximum"], 3);
+
+    assert_eq!(fields[1]["type"], "string");
+    assert_eq!(
+        fields[1]["constraints"]["enum"],
+        serde_json::json!(["blue", "red"])
+    );
+
+    assert_eq!(fields[2]["type"], "number");
+    assert_eq!(fields[2]["constraints"]["required"], Value::Null);
+
+    assert_eq!(fields[3]["type"], "date");
+    assert_eq!(fields[3]["format"], "any");
+    assert_eq!(table_schema["missingValues"], serde_json::json!([""]));
+}
+
+#[test]
+fn generate_data_package() {
+    let wrk = Workdir::new("generate_data_package").flexible(true);
+    wrk.create("My Data.csv", table_schema_data());
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["--format", "datapackage"]).arg("My Data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("datapackage.json"));
+    let data_package: Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(data_package["name"], "my-data");
+    let resource = &data_package["resources"][0];
+    assert_eq!(resource["path"], "My Data.csv");
+    assert_eq!(resource["format"], "csv");
+    assert_eq!(resource["mediatype"], "text/csv");
+    assert_eq!(resource["dialect"]["delimiter"], ",");
+    assert_eq!(resource["stats"]["rows"], 3);
+    assert_eq!(resource["stats"]["fields"], 4);
+    assert_eq!(resource["schema"]["fields"][3]["type"], "date");
+}
+
+#[test]
+fn generate_table_schema_and_validate_with_no_errors() {
+    let wrk = Workdir::new("generate_table_schema_and_validate").flexible(true);
+    let csv = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("adur-public-toilets.csv", &csv);
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["--format", "tableschema"])
+        .arg("--pattern-columns")
+        .arg("ReportEmail,OpeningHours")
+        .arg("adur-public-toilets.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd2 = wrk.command("validate");
+    cmd2.arg("adur-public-toilets.csv")
+        .arg("adur-public-toilets.csv.tableschema.json");
+    wrk.assert_success(&mut cmd2);
+
+    assert!(!Path::new(&wrk.path("adur-public-toilets.csv.invalid")).exists());
+}
+
+#[test]
+fn generate_schema_infer_formats() {
+    let wrk = Workdir::new("generate_schema_infer_formats").flexible(true);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["email", "site", "uid", "ip4", "ip6", "mixed", "opened", "closed", "due"],
+            svec![
+                "a@example.com",
+                "https://example.com",
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "10.0.0.1",
+                "::1",
+                "a@example.com",
+                "2022-01-01",
+                "2022-01-01T10:00:00Z",
+                "01/02/2022"
+            ],
+            svec![
+                "b@example.org",
+                "http://example.org/path?q=1",
+                "",
+                "192.168.1.254",
+                "2001:db8::ff00:42:8329",
+                "not an email",
+                "2022-12-31",
+                "2022-12-31T23:59:59+01:00",
+                "12/31/2022"
+            ],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("--infer-formats").arg("--stdout").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let got: Value = serde_json::from_str(&got).unwrap();
+    let properties = &got["properties"];
+    assert_eq!(properties["email"]["format"], "email");
+    assert_eq!(properties["site"]["format"], "uri");
+    assert_eq!(properties["uid"]["format"], "uuid");
+    assert_eq!(properties["ip4"]["format"], "ipv4");
+    assert_eq!(properties["ip6"]["format"], "ipv6");
+    assert_eq!(properties["mixed"]["format"], Value::Null);
+    assert_eq!(properties["opened"]["format"], "date");
+    assert_eq!(properties["closed"]["format"], "date-time");
+    assert_eq!(properties["due"]["format"], Value::Null);
+    assert_eq!(properties["email"]["minLength"], 13);
+    assert_eq!(properties["email"]["maxLength"], 13);
+}
+
+#[test]
+fn generate_schema_unique_and_validate() {
+    let wrk = Workdir::new("generate_schema_unique_and_validate").flexible(true);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "color"],
+            svec!["1", "red"],
+            svec!["2", "blue"],
+            svec!["3", "red"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("--unique").arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("in.csv.schema.json"));
+    let got: Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(got["properties"]["id"]["unique"], true);
+    assert_eq!(got["properties"]["color"]["unique"], Value::Null);
+
+    wrk.create(
+        "new.csv",
+        vec![svec!["id", "color"], svec!["1", "red"], svec!["1", "blue"]],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("new.csv").arg("in.csv.schema.json");
+    wrk.assert_err(&mut cmd);
+
+    let errors: String = wrk.from_str(&wrk.path("new.csv.validation-errors.tsv"));
+    assert_eq!(
+        errors,
+        "row_number\tfield\terror\n2\tid\tduplicate unique value \"1\", first seen in row 1\n"
+    );
+}
+
+#[test]
+fn generate_schema_sample() {
+    let wrk = Workdir::new("generate_schema_sample").flexible(true);
+    let mut rows = vec![svec!["id", "code"]];
+    for i in 1..=100 {
+        rows.push(vec![i.to_string(), format!("code{i}")]);
+    }
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["--sample", "10"]).arg("--stdout").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let got: Value = serde_json::from_str(&got).unwrap();
+    // the 100 codes are over the enum threshold, but not the 10 of the sample
+    assert_eq!(
+        got["properties"]["code"]["enum"].as_array().unwrap().len(),
+        10
+    );
+    assert_eq!(
+        got["properties"]["id"]["description"],
+        "id column from in.csv"
+    );
+}
+
+#[test]
+fn generate_schema_sample_unique_error() {
+    let wrk = Workdir::new("generate_schema_sample_unique_error").flexible(true);
+    wrk.create("in.csv", vec![svec!["id"], svec!["1"]]);
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["--sample", "10"]).arg("--unique").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
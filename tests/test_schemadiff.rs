@@ -0,0 +1,156 @@
+use serde_json::json;
+
+use crate::workdir::Workdir;
+
+fn old_schema() -> serde_json::Value {
+    json!({
+        "$schema": "https://json-schema.org/draft-07/schema",
+        "title": "JSON Schema for in.csv",
+        "type": "object",
+        "properties": {
+            "id": {"type": ["integer"], "minimum": 1, "maximum": 1000},
+            "name": {"type": ["string"], "minLength": 1, "maxLength": 20},
+            "status": {"type": ["string"], "enum": ["open", "closed"]},
+            "amount": {"type": ["number", "null"]},
+            "notes": {"type": ["string", "null"]}
+        },
+        "required": ["id", "name", "status", "amount", "notes"]
+    })
+}
+
+fn run_schemadiff(
+    wrk: &Workdir,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+) -> (Vec<Vec<String>>, bool) {
+    wrk.create_from_string("old.schema.json", &old.to_string());
+    wrk.create_from_string("new.schema.json", &new.to_string());
+
+    let mut cmd = wrk.command("schemadiff");
+    cmd.arg("old.schema.json").arg("new.schema.json");
+
+    let output = wrk.output(&mut cmd);
+    let mut rdr = csv::Reader::from_reader(&*output.stdout);
+    let report = rdr
+        .records()
+        .map(|record| record.unwrap().iter().map(String::from).collect())
+        .collect();
+    (report, output.status.success())
+}
+
+#[test]
+fn schemadiff_identical() {
+    let wrk = Workdir::new("schemadiff_identical");
+    let (report, success) = run_schemadiff(&wrk, &old_schema(), &old_schema());
+    assert!(success);
+    assert!(report.is_empty());
+}
+
+#[test]
+fn schemadiff_non_breaking() {
+    let wrk = Workdir::new("schemadiff_non_breaking");
+    let mut new = old_schema();
+    new["properties"]["id"] = json!({"type": ["number"], "minimum": 0});
+    new["properties"]["name"] = json!({"type": ["string", "null"], "maxLength": 50});
+    new["properties"]["status"]["enum"] = json!(["open", "closed", "pending"]);
+    new["properties"]["created"] = json!({"type": ["string", "null"], "format": "date"});
+
+    let (report, success) = run_schemadiff(&wrk, &old_schema(), &new);
+    assert!(success);
+    let changes: Vec<(&str, &str, &str)> = report
+        .iter()
+        .map(|row| (row[0].as_str(), row[1].as_str(), row[2].as_str()))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("id", "type", "non-breaking"),
+            ("id", "minimum", "non-breaking"),
+            ("id", "maximum", "non-breaking"),
+            ("name", "required", "non-breaking"),
+            ("name", "minLength", "non-breaking"),
+            ("name", "maxLength", "non-breaking"),
+            ("status", "enum", "non-breaking"),
+            ("created", "added", "non-breaking"),
+        ]
+    );
+    assert_eq!(report[6][5], "enum values added: pending");
+}
+
+#[test]
+fn schemadiff_breaking() {
+    let wrk = Workdir::new("schemadiff_breaking");
+    let mut new = old_schema();
+    new["properties"].as_object_mut().unwrap().remove("notes");
+    new["properties"]["id"]["maximum"] = json!(500);
+    new["properties"]["status"]["enum"] = json!(["open"]);
+    new["properties"]["amount"] = json!({"type": ["integer"]});
+    new["properties"]["email"] = json!({"type": ["string"], "format": "email"});
+
+    let (report, success) = run_schemadiff(&wrk, &old_schema(), &new);
+    assert!(!success);
+    let changes: Vec<(&str, &str, &str)> = report
+        .iter()
+        .map(|row| (row[0].as_str(), row[1].as_str(), row[2].as_str()))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("id", "maximum", "breaking"),
+            ("status", "enum", "breaking"),
+            ("amount", "type", "breaking"),
+            ("amount", "required", "breaking"),
+            ("notes", "removed", "breaking"),
+            ("email", "added", "breaking"),
+        ]
+    );
+    assert_eq!(report[0][3], "1000");
+    assert_eq!(report[0][4], "500");
+    assert_eq!(report[0][5], "maximum tightened");
+    assert_eq!(report[1][5], "enum values removed: closed");
+    assert_eq!(report[2][5], "type narrowed");
+}
+
+#[test]
+fn schemadiff_json_breaking_only() {
+    let wrk = Workdir::new("schemadiff_json_breaking_only");
+    let mut new = old_schema();
+    new["properties"]["name"]["pattern"] = json!("^[A-Z]");
+    new["properties"]["status"]
+        .as_object_mut()
+        .unwrap()
+        .remove("enum");
+    wrk.create_from_string("old.schema.json", &old_schema().to_string());
+    wrk.create_from_string("new.schema.json", &new.to_string());
+
+    let mut cmd = wrk.command("schemadiff");
+    cmd.arg("old.schema.json")
+        .arg("new.schema.json")
+        .arg("--json")
+        .arg("--breaking-only");
+
+    let output = wrk.output(&mut cmd);
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["breaking"], 1);
+    assert_eq!(json["non_breaking"], 1);
+    assert_eq!(json["changes"][0]["field"], "name");
+    assert_eq!(json["changes"][0]["change"], "pattern");
+    assert_eq!(json["changes"][0]["impact"], "breaking");
+    assert_eq!(json["changes"][0]["old"], serde_json::Value::Null);
+    assert_eq!(json["changes"][0]["new"], "^[A-Z]");
+    assert_eq!(json["changes"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn schemadiff_invalid_schema() {
+    let wrk = Workdir::new("schemadiff_invalid_schema");
+    wrk.create_from_string("old.schema.json", &old_schema().to_string());
+    wrk.create_from_string("new.schema.json", r#"{"type": "object"}"#);
+
+    let mut cmd = wrk.command("schemadiff");
+    cmd.arg("old.schema.json").arg("new.schema.json");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("The JSON Schema new.schema.json has no 'properties' object."));
+}
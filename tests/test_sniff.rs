@@ -283,3 +283,49 @@ fn sniff_prefer_dmy() {
 
     assert_eq!(got, expected);
 }
+
+#[test]
+fn sniff_stats_cache_record_count() {
+    let wrk = Workdir::new("sniff_stats_cache_record_count");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    // doctor the cached record count, so we can tell sniff used it
+    let cache_path = wrk.path("in.csv.stats.json");
+    let cache: String = wrk.from_str(&cache_path);
+    let cache = cache.replace(r#""record_count": 2"#, r#""record_count": 42"#);
+    wrk.create_from_string("in.csv.stats.json", &cache);
+
+    let mut cmd = wrk.command("sniff");
+    cmd.arg("--json").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let got: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(got["num_records"], 42);
+}
+
+#[test]
+fn sniff_stats_cache_other_delimiter() {
+    let wrk = Workdir::new("sniff_stats_cache_other_delimiter");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").args(["--delimiter", ";"]).arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let cache_path = wrk.path("in.csv.stats.json");
+    let cache: String = wrk.from_str(&cache_path);
+    let cache = cache.replace(r#""record_count": 2"#, r#""record_count": 42"#);
+    wrk.create_from_string("in.csv.stats.json", &cache);
+
+    // the cache was computed with another delimiter, so sniff counts the rows itself
+    let mut cmd = wrk.command("sniff");
+    cmd.arg("--json").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let got: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(got["num_records"], 2);
+}
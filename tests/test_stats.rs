@@ -654,3 +654,152 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+fn groupby_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["state", "city", "pop"],
+        svec!["NY", "New York", "8"],
+        svec!["CA", "Los Angeles", "4"],
+        svec!["NY", "Buffalo", "2"],
+        svec!["CA", "San Diego", ""],
+    ]
+}
+
+#[test]
+fn stats_groupby() {
+    let wrk = Workdir::new("stats_groupby");
+    wrk.create("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--groupby")
+        .arg("state")
+        .arg("--select")
+        .arg("pop")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "state",
+            "field",
+            "type",
+            "sum",
+            "min",
+            "max",
+            "min_length",
+            "max_length",
+            "mean",
+            "stddev",
+            "variance",
+            "nullcount"
+        ],
+        svec!["CA", "pop", "Integer", "4", "4", "4", "0", "1", "4", "0", "0", "1"],
+        svec!["NY", "pop", "Integer", "10", "2", "8", "1", "1", "5", "3", "9", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_groupby_index() {
+    let wrk = Workdir::new("stats_groupby_index");
+    wrk.create_indexed("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--groupby")
+        .arg("state")
+        .arg("--select")
+        .arg("pop")
+        .arg("--jobs")
+        .arg("2")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 3);
+    assert_eq!(&got[1][..4], &["CA", "pop", "Integer", "4"]);
+    assert_eq!(&got[2][..4], &["NY", "pop", "Integer", "10"]);
+}
+
+#[test]
+fn stats_groupby_multiple_keys() {
+    let wrk = Workdir::new("stats_groupby_multiple_keys");
+    wrk.create("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--groupby")
+        .arg("state,city")
+        .arg("--select")
+        .arg("pop")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(&got[0][..3], &["state", "city", "field"]);
+    let keys: Vec<(String, String, String)> = got[1..]
+        .iter()
+        .map(|r| (r[0].clone(), r[1].clone(), r[5].clone()))
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            ("CA".to_string(), "Los Angeles".to_string(), "4".to_string()),
+            ("CA".to_string(), "San Diego".to_string(), "".to_string()),
+            ("NY".to_string(), "Buffalo".to_string(), "2".to_string()),
+            ("NY".to_string(), "New York".to_string(), "8".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn stats_cache() {
+    let wrk = Workdir::new("stats_cache");
+    wrk.create("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").arg("--cardinality").arg("in.csv");
+    let got: String = wrk.stdout(&mut cmd);
+
+    let cache_json: String = wrk.from_str(&wrk.path("in.csv.stats.json"));
+    let cache: serde_json::Value = serde_json::from_str(&cache_json).unwrap();
+    assert_eq!(cache["record_count"], 4);
+    assert_eq!(cache["options"]["cardinality"], true);
+    assert_eq!(cache["fields"], serde_json::json!(["state", "city", "pop"]));
+    assert_eq!(cache["headers"][0], "field");
+    assert_eq!(cache["stats"][2][0], "Integer");
+
+    // running stats again with the same options reuses the cache
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cardinality").arg("in.csv");
+    let got_cached: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, got_cached);
+}
+
+#[test]
+fn stats_cache_same_size_rewrite() {
+    let wrk = Workdir::new("stats_cache_same_size_rewrite");
+    wrk.create("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").arg("in.csv");
+    let got: String = wrk.stdout(&mut cmd);
+
+    // rewrite the input right away with other values of the same size
+    let mut data = groupby_data();
+    data[1][2] = "9".to_string();
+    wrk.create("in.csv", data);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("in.csv");
+    let got_rewritten: String = wrk.stdout(&mut cmd);
+    assert_ne!(got, got_rewritten);
+}
+
+#[test]
+fn stats_cache_not_written_for_select() {
+    let wrk = Workdir::new("stats_cache_not_written_for_select");
+    wrk.create("in.csv", groupby_data());
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--cache").args(["--select", "pop"]).arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    assert!(!wrk.path("in.csv.stats.json").exists());
+}
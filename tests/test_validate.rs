@@ -199,3 +199,274 @@ fn validate_adur_public_toilets_dataset_with_json_schema_url() {
     assert_eq!(adur_errors(), validation_error_output);
     wrk.assert_err(&mut cmd);
 }
+
+#[test]
+fn validate_primary_key() {
+    let wrk = Workdir::new("validate_primary_key").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Xaviers"],
+            svec!["2", "Magneto"],
+            svec!["1", "Iceman"],
+            svec!["", "Storm"],
+        ],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--primary-key", "id"]);
+
+    wrk.assert_err(&mut cmd);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(invalid_output, "id,name\n1,Iceman\n,Storm\n");
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    assert_eq!(
+        validation_error_output,
+        "row_number\tfield\terror\n3\tid\tduplicate primary key value \"1\", first seen in row \
+         1\n4\tid\tprimary key column id is empty\n"
+    );
+}
+
+#[test]
+fn validate_composite_primary_key_valid() {
+    let wrk = Workdir::new("validate_composite_primary_key_valid").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["country", "city"],
+            svec!["US", "Springfield"],
+            svec!["CA", "Springfield"],
+            svec!["US", "Boston"],
+        ],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--primary-key", "country,city"]);
+
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn validate_foreign_key() {
+    let wrk = Workdir::new("validate_foreign_key").flexible(true);
+    wrk.create(
+        "countries.csv",
+        vec![
+            svec!["code", "country"],
+            svec!["US", "United States"],
+            svec!["CA", "Canada"],
+        ],
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["city", "country_code"],
+            svec!["Boston", "US"],
+            svec!["Toronto", "CA"],
+            svec!["Mexico City", "MX"],
+            svec!["Atlantis", ""],
+        ],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .args(["--foreign-key", "country_code:countries.csv:code"]);
+
+    wrk.assert_err(&mut cmd);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(invalid_output, "city,country_code\nMexico City,MX\n");
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    assert_eq!(
+        validation_error_output,
+        "row_number\tfield\terror\n3\tcountry_code\tforeign key value \"MX\" not found in code of \
+         countries.csv\n"
+    );
+}
+
+#[test]
+fn validate_foreign_key_column_count_mismatch() {
+    let wrk = Workdir::new("validate_foreign_key_column_count_mismatch").flexible(true);
+    wrk.create(
+        "countries.csv",
+        vec![svec!["code", "country"], svec!["US", "United States"]],
+    );
+    wrk.create(
+        "data.csv",
+        vec![svec!["city", "country_code"], svec!["Boston", "US"]],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .args(["--foreign-key", "country_code:countries.csv:code,country"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+fn table_schema() -> &'static str {
+    r#"{
+  "fields": [
+    {"name": "id", "type": "integer", "constraints": {"required": true, "minimum": 1}},
+    {"name": "email", "type": "string", "format": "email", "constraints": {"unique": true}},
+    {"name": "status", "type": "string", "constraints": {"enum": ["open", "closed"]}},
+    {"name": "opened", "type": "date"},
+    {"name": "code", "type": "string", "constraints": {"pattern": "[A-Z]{3}"}}
+  ],
+  "missingValues": ["", "NA"],
+  "primaryKey": "id"
+}"#
+}
+
+#[test]
+fn validate_table_schema() {
+    let wrk = Workdir::new("validate_table_schema").flexible(true);
+    wrk.create_from_string("schema.json", table_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "email", "status", "opened", "code"],
+            svec!["1", "a@example.com", "open", "2022-01-01", "ABC"],
+            svec!["2", "NA", "NA", "NA", "NA"],
+            svec!["3", "a@example.com", "closed", "2022-01-03", "DEF"],
+            svec!["1", "b@example.com", "pending", "2022-01-04", "ABCD"],
+            svec!["0", "not an email", "open", "Jan 5, 2022", "GHI"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("schema.json");
+    wrk.assert_err(&mut cmd);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(
+        invalid_output,
+        "id,email,status,opened,code\n3,a@example.com,closed,2022-01-03,DEF\n1,b@example.com,\
+         pending,2022-01-04,ABCD\n0,not an email,open,\"Jan 5, 2022\",GHI\n"
+    );
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let errors: Vec<&str> = validation_error_output.lines().collect();
+    assert_eq!(errors[0], "row_number\tfield\terror");
+    assert!(
+        errors.contains(&"3\temail\tduplicate unique value \"a@example.com\", first seen in row 1")
+    );
+    assert!(errors.contains(&"4\tid\tduplicate primary key value \"1\", first seen in row 1"));
+    assert!(errors.iter().any(|e| e.starts_with("4\tstatus\t")));
+    assert!(errors.iter().any(|e| e.starts_with("4\tcode\t")));
+    assert!(errors.iter().any(|e| e.starts_with("5\tid\t")));
+    assert!(errors.iter().any(|e| e.starts_with("5\temail\t")));
+    assert!(errors.iter().any(|e| e.starts_with("5\topened\t")));
+    assert!(!errors.iter().any(|e| e.starts_with("2\t")));
+}
+
+#[test]
+fn validate_data_package() {
+    let wrk = Workdir::new("validate_data_package").flexible(true);
+    wrk.create_from_string(
+        "datapackage.json",
+        &format!(
+            r#"{{"name": "test", "resources": [{{"name": "data", "path": "data.csv", "schema": {}}}]}}"#,
+            table_schema()
+        ),
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "email", "status", "opened", "code"],
+            svec!["1", "a@example.com", "open", "2022-01-01", "ABC"],
+            svec!["2", "", "closed", "", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("datapackage.json");
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn validate_summary() {
+    let wrk = Workdir::new("validate_summary").flexible(true);
+    wrk.create_from_string("schema.json", table_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "email", "status", "opened", "code"],
+            svec!["1", "a@example.com", "open", "2022-01-01", "ABC"],
+            svec!["2", "b@example.com", "pending", "2022-01-02", "ABC"],
+            svec!["3", "c@example.com", "done", "2022-01-03", "ABC"],
+            svec!["4", "d@example.com", "shut", "2022-01-04", "abc"],
+            svec!["4", "e@example.com", "open", "2022-01-05", "DEF"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--summary", "summary.json"])
+        .args(["--summary-examples", "2"]);
+    wrk.assert_err(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("summary.json"));
+    let summary: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(summary["records"], 5);
+    assert_eq!(summary["valid"], 1);
+    assert_eq!(summary["invalid"], 4);
+    assert_eq!(summary["errors"], 5);
+    assert_eq!(
+        summary["keywords"],
+        serde_json::json!({"enum": 3, "pattern": 1, "primaryKey": 1})
+    );
+
+    let columns: Vec<&String> = summary["columns"].as_object().unwrap().keys().collect();
+    assert_eq!(columns, vec!["id", "status", "code"]);
+
+    let status = &summary["columns"]["status"];
+    assert_eq!(status["errors"], 3);
+    let examples = status["keywords"]["enum"]["examples"].as_array().unwrap();
+    assert_eq!(status["keywords"]["enum"]["errors"], 3);
+    assert_eq!(examples.len(), 2);
+    assert_eq!(examples[0]["row"], 2);
+    assert_eq!(examples[0]["value"], "pending");
+    assert_eq!(examples[1]["row"], 3);
+    assert_eq!(examples[1]["value"], "done");
+
+    let primary_key = &summary["columns"]["id"]["keywords"]["primaryKey"];
+    assert_eq!(primary_key["examples"][0]["row"], 5);
+    assert_eq!(primary_key["examples"][0]["value"], "4");
+}
+
+#[test]
+fn validate_summary_stdout_all_valid() {
+    let wrk = Workdir::new("validate_summary_stdout_all_valid").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Xaviers"],
+            svec!["2", "Magneto"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .args(["--primary-key", "id"])
+        .args(["--summary", "-"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let summary: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(summary["records"], 2);
+    assert_eq!(summary["valid"], 2);
+    assert_eq!(summary["invalid"], 0);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(summary["columns"], serde_json::json!({}));
+}
+
+#[test]
+fn validate_summary_requires_schema() {
+    let wrk = Workdir::new("validate_summary_requires_schema").flexible(true);
+    wrk.create("data.csv", vec![svec!["id"], svec!["1"]]);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--summary", "summary.json"]);
+    wrk.assert_err(&mut cmd);
+}
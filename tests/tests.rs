@@ -35,12 +35,20 @@ mod test_apply;
 #[cfg(any(feature = "full", feature = "lite"))]
 mod test_behead;
 #[cfg(any(feature = "full", feature = "lite"))]
+mod test_bin;
+#[cfg(any(feature = "full", feature = "lite"))]
 mod test_cat;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_check;
 mod test_combos;
 mod test_comments;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_correlation;
 mod test_count;
 mod test_dedup;
 #[cfg(any(feature = "full", feature = "lite"))]
+mod test_drift;
+#[cfg(any(feature = "full", feature = "lite"))]
 mod test_enumerate;
 mod test_excel;
 mod test_exclude;
@@ -61,6 +69,10 @@ mod test_fmt;
 #[cfg(all(feature = "foreach", target_family = "unix"))]
 mod test_foreach;
 mod test_frequency;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_geo;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_geojoin;
 mod test_headers;
 mod test_index;
 mod test_input;
@@ -71,7 +83,13 @@ mod test_jsonl;
 #[cfg(feature = "luau")]
 mod test_luau;
 #[cfg(any(feature = "full", feature = "lite"))]
+mod test_outliers;
+#[cfg(any(feature = "full", feature = "lite"))]
 mod test_partition;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_piiscan;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_profile;
 mod test_pseudo;
 #[cfg(feature = "python")]
 mod test_py;
@@ -83,6 +101,8 @@ mod test_safenames;
 mod test_sample;
 #[cfg(any(feature = "full", feature = "lite"))]
 mod test_schema;
+#[cfg(any(feature = "full", feature = "lite"))]
+mod test_schemadiff;
 mod test_search;
 mod test_searchset;
 mod test_select;